@@ -0,0 +1,121 @@
+//! Frozen screenshot latency: the old path kept each monitor as a base64 PNG
+//! string and decoded the whole frame for every crop; the current path keeps
+//! the decoded `Arc<RgbaImage>` and only encodes what a consumer needs.
+//!
+//! `cargo bench --bench frozen_screenshot`
+
+use base64::Engine;
+use criterion::{criterion_group, criterion_main, Criterion};
+use dh_transshot_lib::bench::{crop_region, encode_jpeg, encode_png, encode_png_fast};
+use image::{ImageFormat, Rgba, RgbaImage};
+use std::hint::black_box;
+use std::sync::Arc;
+
+const WIDTH: u32 = 3840;
+const HEIGHT: u32 = 2160;
+/// Selection cropped from the frame (x, y, width, height).
+const CROP: (u32, u32, u32, u32) = (1200, 700, 800, 600);
+
+/// A 4K frame that compresses like a desktop: flat panels and title bars
+/// with rows of short dark strokes standing in for text.
+fn synthetic_frame() -> RgbaImage {
+    let mut seed = 0x2545_f491_u32;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed
+    };
+    let mut img = RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([236, 239, 244, 255]));
+    for panel in 0..12u32 {
+        let (px, py) = ((panel % 4) * 960, (panel / 4) * 720);
+        let shade = 200 + (next() % 50) as u8;
+        for y in py + 24..py + 696 {
+            for x in px + 24..px + 936 {
+                let title_bar = y < py + 64;
+                let pixel = if title_bar {
+                    Rgba([60, 64, 72, 255])
+                } else {
+                    Rgba([shade, shade, 255 - shade / 4, 255])
+                };
+                img.put_pixel(x, y, pixel);
+            }
+        }
+        // Text-like runs: 14px lines of 3–9px "glyphs" separated by gaps.
+        for line in 0..30 {
+            let y0 = py + 84 + line * 20;
+            let mut x = px + 40;
+            while x < px + 900 {
+                let glyph = 3 + next() % 7;
+                let ink = (next() % 60) as u8;
+                for y in y0..y0 + 14 {
+                    for gx in x..(x + glyph).min(px + 920) {
+                        if next() % 3 != 0 {
+                            img.put_pixel(gx, y, Rgba([ink, ink, ink + 20, 255]));
+                        }
+                    }
+                }
+                x += glyph + 2 + next() % 6;
+            }
+        }
+    }
+    img
+}
+
+fn to_base64_png(frame: &RgbaImage) -> String {
+    base64::engine::general_purpose::STANDARD.encode(encode_png(frame).unwrap())
+}
+
+/// Work done right after capture, per monitor, before the overlay can open.
+fn store(c: &mut Criterion) {
+    let frame = synthetic_frame();
+    let mut group = c.benchmark_group("store_frame_4k");
+    group.sample_size(10);
+    group.bench_function("before_base64_png", |b| {
+        b.iter(|| to_base64_png(black_box(&frame)))
+    });
+    group.bench_function("after_arc_rgba", |b| {
+        b.iter(|| Arc::new(black_box(&frame).clone()))
+    });
+    group.finish();
+}
+
+/// `capture_region` / `capture_and_ocr`: selection → JPEG bytes. The old
+/// path decoded the stored PNG and cropped the `DynamicImage`.
+fn crop(c: &mut Criterion) {
+    let frame = Arc::new(synthetic_frame());
+    let stored = to_base64_png(&frame);
+    let (x, y, width, height) = CROP;
+    let mut group = c.benchmark_group("crop_800x600_from_4k");
+    group.sample_size(10);
+    group.bench_function("before_decode_base64_png", |b| {
+        b.iter(|| {
+            let png = base64::engine::general_purpose::STANDARD
+                .decode(black_box(&stored))
+                .unwrap();
+            let full = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+            encode_jpeg(&full.crop_imm(x, y, width, height)).unwrap()
+        })
+    });
+    group.bench_function("after_arc_rgba", |b| {
+        b.iter(|| encode_jpeg(&crop_region(black_box(&frame), x, y, width, height)).unwrap())
+    });
+    group.finish();
+}
+
+/// What the overlay's image request costs now that encoding is deferred.
+fn overlay(c: &mut Criterion) {
+    let frame = Arc::new(synthetic_frame());
+    let mut group = c.benchmark_group("overlay_fetch_4k");
+    group.sample_size(10);
+    group.bench_function("after_encode_png", |b| {
+        b.iter(|| encode_png(black_box(&frame)).unwrap())
+    });
+    group.bench_function("after_encode_png_fast", |b| {
+        b.iter(|| encode_png_fast(black_box(&frame)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, store, crop, overlay);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// Placeholder replaced with the target language name in action prompts.
+pub const TARGET_LANG_PLACEHOLDER: &str = "{target_lang}";
+
+/// Id prefix used for action entries in `hotkey-action` / `tray-action`
+/// payloads, e.g. `"action:summarize"`.
+pub const ACTION_EVENT_PREFIX: &str = "action:";
+
+/// A text transformation sent through the chat-completion pipeline.
+/// `prompt` becomes the system prompt; the selected text is the user message.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextAction {
+    pub id: String,
+    pub name: String,
+    pub prompt: String,
+    /// Built-in actions are supplied by the app and not persisted.
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl TextAction {
+    fn builtin(id: &str, name: &str, prompt: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+            builtin: true,
+        }
+    }
+
+    /// System prompt with `{target_lang}` substituted.
+    pub fn system_prompt(&self, target_lang: &str) -> String {
+        self.prompt.replace(TARGET_LANG_PLACEHOLDER, target_lang)
+    }
+}
+
+const OUTPUT_ONLY: &str = "Output ONLY the result, without explanations or preamble.";
+
+pub fn builtin_actions() -> Vec<TextAction> {
+    vec![
+        TextAction::builtin(
+            "fix_grammar",
+            "修正语法",
+            &format!(
+                "Fix grammar, spelling and punctuation in the following text. Keep its \
+                 language, meaning, tone and formatting unchanged. {OUTPUT_ONLY}"
+            ),
+        ),
+        TextAction::builtin(
+            "make_formal",
+            "改为正式语气",
+            &format!(
+                "Rewrite the following text in a formal, professional tone. Keep its \
+                 language and meaning unchanged. {OUTPUT_ONLY}"
+            ),
+        ),
+        TextAction::builtin(
+            "make_casual",
+            "改为口语语气",
+            &format!(
+                "Rewrite the following text in a casual, friendly tone. Keep its \
+                 language and meaning unchanged. {OUTPUT_ONLY}"
+            ),
+        ),
+        TextAction::builtin(
+            "summarize",
+            "总结",
+            &format!(
+                "Summarize the following text concisely in {TARGET_LANG_PLACEHOLDER}. \
+                 {OUTPUT_ONLY}"
+            ),
+        ),
+        TextAction::builtin(
+            "explain",
+            "解释",
+            &format!(
+                "Explain the following text in {TARGET_LANG_PLACEHOLDER} for a \
+                 non-expert reader: what it means, key terms and any implied context. \
+                 {OUTPUT_ONLY}"
+            ),
+        ),
+        TextAction::builtin(
+            "key_points",
+            "提取要点",
+            &format!(
+                "Extract the key points of the following text as a short bulleted \
+                 list in {TARGET_LANG_PLACEHOLDER}, one point per line starting with \
+                 \"- \". {OUTPUT_ONLY}"
+            ),
+        ),
+    ]
+}
+
+/// Built-in actions followed by the user's custom actions. A custom action
+/// with the same id as a built-in replaces it (so built-in prompts can be tuned).
+pub fn all_actions(custom: &[TextAction]) -> Vec<TextAction> {
+    let mut actions: Vec<TextAction> = builtin_actions()
+        .into_iter()
+        .filter(|b| !custom.iter().any(|c| c.id == b.id))
+        .collect();
+    actions.extend(custom.iter().cloned().map(|mut c| {
+        c.builtin = false;
+        c
+    }));
+    actions
+}
+
+pub fn find_action(custom: &[TextAction], id: &str) -> Option<TextAction> {
+    all_actions(custom).into_iter().find(|a| a.id == id)
+}
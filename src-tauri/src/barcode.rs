@@ -0,0 +1,116 @@
+use crate::ocr::structured::BoundingBox;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use log::info;
+use serde::Serialize;
+
+/// Crops whose longest edge is below this are retried at 2x when nothing is
+/// found; codes in chat thumbnails are often only a few pixels per module.
+const UPSCALE_RETRY_EDGE: u32 = 1000;
+
+/// One decoded QR / Data Matrix / 1D barcode.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedCode {
+    /// Symbology name as reported by the decoder, e.g. `"qrcode"`, `"ean 13"`.
+    pub format: String,
+    pub text: String,
+    /// Whether `text` looks like a link the UI can offer to open.
+    pub is_url: bool,
+    /// Bounding box of the detected symbol.
+    pub bbox: BoundingBox,
+    /// Corner / finder points reported by the decoder.
+    pub points: Vec<[f64; 2]>,
+}
+
+fn is_url(text: &str) -> bool {
+    let lower = text.trim().to_ascii_lowercase();
+    [
+        "http://",
+        "https://",
+        "mailto:",
+        "tel:",
+        "weixin://",
+        "alipays://",
+    ]
+    .iter()
+    .any(|scheme| lower.starts_with(scheme))
+}
+
+fn decode_luma(img: &DynamicImage, scale: f64) -> Vec<DecodedCode> {
+    let luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let results = match rxing::helpers::detect_multiple_in_luma(luma.into_raw(), width, height) {
+        Ok(results) => results,
+        // The decoder reports "nothing found" as an error as well.
+        Err(e) => {
+            info!("[Barcode] 未检测到条码 ({}x{}): {}", width, height, e);
+            return Vec::new();
+        }
+    };
+
+    results
+        .iter()
+        .map(|result| {
+            let points: Vec<[f64; 2]> = result
+                .getPoints()
+                .iter()
+                .map(|p| [p.x as f64 / scale, p.y as f64 / scale])
+                .collect();
+            let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+                (lo.min(p[0]), hi.max(p[0]))
+            });
+            let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+                (lo.min(p[1]), hi.max(p[1]))
+            });
+            let bbox = if points.is_empty() {
+                BoundingBox::default()
+            } else {
+                BoundingBox {
+                    x: min_x,
+                    y: min_y,
+                    width: max_x - min_x,
+                    height: max_y - min_y,
+                }
+            };
+            let text = result.getText().to_string();
+            DecodedCode {
+                format: result.getBarcodeFormat().to_string(),
+                is_url: is_url(&text),
+                text,
+                bbox,
+                points,
+            }
+        })
+        .collect()
+}
+
+/// Decode every QR code, Data Matrix and common 1D barcode in the image,
+/// fully offline. Coordinates are image pixels. Blocking.
+pub fn decode(img: &DynamicImage) -> Vec<DecodedCode> {
+    let started = std::time::Instant::now();
+    let mut codes = decode_luma(img, 1.0);
+    if codes.is_empty() && img.width().max(img.height()) < UPSCALE_RETRY_EDGE {
+        let upscaled = img.resize(img.width() * 2, img.height() * 2, FilterType::Nearest);
+        codes = decode_luma(&upscaled, 2.0);
+    }
+    info!(
+        "[Barcode] 解码完成, image {}x{}, codes={}, 耗时 {}ms",
+        img.width(),
+        img.height(),
+        codes.len(),
+        started.elapsed().as_millis()
+    );
+    codes
+}
+
+/// Move boxes from crop coordinates into screenshot coordinates.
+pub fn offset(codes: &mut [DecodedCode], dx: f64, dy: f64) {
+    for code in codes {
+        code.bbox.x += dx;
+        code.bbox.y += dy;
+        for point in &mut code.points {
+            point[0] += dx;
+            point[1] += dy;
+        }
+    }
+}
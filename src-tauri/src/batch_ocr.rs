@@ -0,0 +1,203 @@
+use crate::ocr::OcrMode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Image extensions picked up when walking the input directory.
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "webp", "bmp", "tif", "tiff"];
+
+/// Progress file kept in the output directory; a job started again with the
+/// same input, output and options skips the files recorded as done.
+pub const MANIFEST_FILE_NAME: &str = ".transshot-batch.json";
+
+/// File name of the combined result for `BatchOutputFormat::Json`.
+pub const COMBINED_FILE_NAME: &str = "ocr-results.json";
+
+/// Default output directory name, created inside the input directory.
+pub const DEFAULT_OUTPUT_DIR_NAME: &str = "transshot-ocr";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOutputFormat {
+    /// One `<name>.txt` per image (and `<name>.<target>.txt` with a translation).
+    #[default]
+    Txt,
+    /// One `.md` per image; OCR defaults to Markdown mode.
+    Md,
+    /// A single `ocr-results.json` with every file's result.
+    Json,
+}
+
+impl BatchOutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Md => "md",
+            Self::Json => "json",
+        }
+    }
+
+    /// OCR mode used when the request does not name one.
+    pub fn default_mode(self) -> OcrMode {
+        match self {
+            Self::Md => OcrMode::Markdown,
+            Self::Txt | Self::Json => OcrMode::Plain,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryStatus {
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchEntry {
+    pub status: EntryStatus,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub translation: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BatchManifest {
+    /// Language / mode / target / format the entries were produced with.
+    #[serde(default)]
+    pub options_key: String,
+    /// Keyed by the image path relative to the input directory, `/`-separated.
+    #[serde(default)]
+    pub entries: BTreeMap<String, BatchEntry>,
+}
+
+impl BatchManifest {
+    /// Load the manifest from `output_dir`, starting over when it is missing,
+    /// unreadable or was written with different options. Blocking.
+    pub fn load(output_dir: &Path, options_key: &str) -> Self {
+        let loaded = std::fs::read(output_dir.join(MANIFEST_FILE_NAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok());
+        match loaded {
+            Some(manifest) if manifest.options_key == options_key => manifest,
+            _ => Self {
+                options_key: options_key.to_string(),
+                entries: BTreeMap::new(),
+            },
+        }
+    }
+
+    pub fn is_done(&self, file: &str) -> bool {
+        matches!(
+            self.entries.get(file),
+            Some(BatchEntry {
+                status: EntryStatus::Done,
+                ..
+            })
+        )
+    }
+}
+
+/// Identifies the settings that change a file's result, so a resumed job
+/// does not mix results from different languages or modes.
+pub fn options_key(
+    language: &str,
+    mode: OcrMode,
+    target_lang: Option<&str>,
+    format: BatchOutputFormat,
+) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        language,
+        mode.id(),
+        target_lang.unwrap_or(""),
+        format.extension()
+    )
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Image files under `input_dir` as `/`-separated relative paths, sorted.
+/// `exclude` (the output directory) is skipped when walking. Blocking.
+pub fn collect_images(
+    input_dir: &Path,
+    recursive: bool,
+    exclude: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![input_dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if recursive && path != exclude {
+                    dirs.push(path);
+                }
+            } else if file_type.is_file() && is_image(&path) {
+                if let Ok(relative) = path.strip_prefix(input_dir) {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Per-file output path: the relative path with the output extension
+/// appended (`scan.png` → `scan.png.txt`), or `<name>.<target_lang>.<ext>`
+/// for the translation. The source extension is kept so `scan.png` and
+/// `scan.jpg` in one folder don't write the same file.
+pub fn output_path(
+    output_dir: &Path,
+    file: &str,
+    format: BatchOutputFormat,
+    target_lang: Option<&str>,
+) -> PathBuf {
+    let relative = Path::new(file);
+    let file_name = relative
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("image");
+    let name = match target_lang {
+        Some(lang) => format!("{}.{}.{}", file_name, lang, format.extension()),
+        None => format!("{}.{}", file_name, format.extension()),
+    };
+    output_dir.join(relative.with_file_name(name))
+}
+
+#[derive(Serialize)]
+struct CombinedEntry<'a> {
+    file: &'a str,
+    text: &'a str,
+    translation: Option<&'a str>,
+    error: Option<&'a str>,
+}
+
+/// JSON array of `{ file, text, translation, error }` in file order.
+pub fn render_combined(manifest: &BatchManifest, files: &[String]) -> anyhow::Result<Vec<u8>> {
+    let entries: Vec<CombinedEntry> = files
+        .iter()
+        .filter_map(|file| {
+            let entry = manifest.entries.get(file)?;
+            Some(CombinedEntry {
+                file,
+                text: &entry.text,
+                translation: entry.translation.as_deref(),
+                error: entry.error.as_deref(),
+            })
+        })
+        .collect();
+    Ok(serde_json::to_vec_pretty(&entries)?)
+}
@@ -0,0 +1,58 @@
+use crate::actions::{self, TextAction};
+use crate::config::AppState;
+use crate::translation::OpenAiCompatProvider;
+use log::{error, info};
+use tauri::State;
+
+/// List built-in and custom text actions (for the UI, tray and hotkey settings).
+#[tauri::command]
+pub async fn list_text_actions(state: State<'_, AppState>) -> Result<Vec<TextAction>, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(actions::all_actions(&settings.actions))
+}
+
+/// Run a text action (fix grammar, summarize, custom prompt...) on `text`
+/// using the translation service's chat-completion configuration.
+#[tauri::command]
+pub async fn run_text_action(
+    state: State<'_, AppState>,
+    action_id: String,
+    text: String,
+    target_lang: String,
+) -> Result<String, String> {
+    info!(
+        "[Action] run_text_action 开始, action={}, target_lang={}, 文本长度={}",
+        action_id,
+        target_lang,
+        text.len()
+    );
+    let (action, base_url, api_key, model, extra) = {
+        let settings = state.settings.lock().map_err(|e| e.to_string())?;
+        let action = actions::find_action(&settings.actions, &action_id)
+            .ok_or_else(|| format!("Unknown text action {}", action_id))?;
+        let (b, k, m) = settings
+            .translation
+            .resolved(&settings.base_url, &settings.api_key);
+        (action, b, k, m, settings.translation.extra.clone())
+    };
+    let client = state.http_client.clone();
+
+    let provider = OpenAiCompatProvider::new(client);
+    let result = provider
+        .complete(
+            &action.system_prompt(&target_lang),
+            &text,
+            &base_url,
+            &api_key,
+            &model,
+            &extra,
+            "Action",
+        )
+        .await
+        .map_err(|e| e.to_string());
+    match &result {
+        Ok(output) => info!("[Action] {} 完成, 结果长度={}", action.id, output.len()),
+        Err(e) => error!("[Action] {} 失败: {}", action.id, e),
+    }
+    result
+}
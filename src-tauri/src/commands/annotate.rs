@@ -0,0 +1,156 @@
+use crate::config::AppState;
+use crate::render::annotate::{self, Annotation};
+use crate::screenshot::save::{self, SaveFormat};
+use image::{DynamicImage, RgbaImage};
+use log::{error, info};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// A capture loaded from a sidecar, waiting for `open_overlays` to place it.
+pub(crate) struct EditSource {
+    pub path: PathBuf,
+    pub base: RgbaImage,
+    pub annotation: Annotation,
+}
+
+/// What `write_annotated` wrote.
+pub(crate) struct AnnotatedFile {
+    pub width: u32,
+    pub height: u32,
+    pub bytes: usize,
+    pub sidecar: PathBuf,
+}
+
+/// Render `annotation` over `base`, write it to `path` in `format` and store
+/// the sidecar next to it. Blocking.
+pub(crate) fn write_annotated(
+    path: &Path,
+    base: &RgbaImage,
+    annotation: &Annotation,
+    format: SaveFormat,
+    jpeg_quality: u8,
+) -> anyhow::Result<AnnotatedFile> {
+    let rendered = annotate::render_annotation(base, annotation)?;
+    let bytes = save::encode_image(&DynamicImage::ImageRgba8(rendered), format, jpeg_quality)?;
+    crate::output::write_file(path, &bytes)?;
+    let sidecar = annotate::write_sidecar(path, base, annotation)?;
+    Ok(AnnotatedFile {
+        width: base.width(),
+        height: base.height(),
+        bytes: bytes.len(),
+        sidecar,
+    })
+}
+
+/// Crop of the frozen screenshot of `monitor_index`, in image pixels.
+fn frozen_crop(
+    state: &AppState,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, String> {
+    let frozen = super::ocr::frozen_screenshot(state, monitor_index)?;
+    Ok(crate::screenshot::crop_region(&frozen, x, y, width, height).to_rgba8())
+}
+
+/// Render annotations over a region of the frozen screenshot; returns a
+/// base64 PNG. Shape coordinates are relative to the region.
+#[tauri::command]
+pub async fn render_annotation(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    annotation: Annotation,
+) -> Result<String, String> {
+    let base = frozen_crop(&state, monitor_index, x, y, width, height)?;
+    let shapes = annotation.shapes.len();
+    let png = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<u8>> {
+        let rendered = annotate::render_annotation(&base, &annotation)?;
+        crate::screenshot::encode_png(&rendered)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[Annotate] 标注渲染失败: {}", e);
+        e.to_string()
+    })?;
+    info!(
+        "[Annotate] 标注渲染完成, shapes={}, size={}x{}",
+        shapes, width, height
+    );
+    use base64::Engine;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/// Render annotations over a region of the frozen screenshot and save it to
+/// `path` (format from the extension, PNG when unknown), with its sidecar.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_annotated(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    annotation: Annotation,
+    path: String,
+) -> Result<(), String> {
+    let base = frozen_crop(&state, monitor_index, x, y, width, height)?;
+    let jpeg_quality = state
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .screenshot
+        .jpeg_quality;
+    let path_for_write = PathBuf::from(&path);
+    let written = tokio::task::spawn_blocking(move || {
+        let format = SaveFormat::from_path(&path_for_write).unwrap_or_default();
+        write_annotated(&path_for_write, &base, &annotation, format, jpeg_quality)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[Annotate] 标注截图保存失败: {}", e);
+        e.to_string()
+    })?;
+    info!(
+        "[Annotate] 标注截图已保存: {}, sidecar={}",
+        path,
+        written.sidecar.display()
+    );
+    Ok(())
+}
+
+/// Reopen an annotated capture for editing. `path` is the image or its
+/// sidecar. The unannotated capture is pasted into the middle of the monitor
+/// under the cursor and the overlay opens on it with the saved shapes;
+/// confirming writes the result back to the same image.
+#[tauri::command]
+pub async fn open_annotation(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    info!("[Annotate] 重新打开标注截图: {}", path);
+    let source_path = PathBuf::from(&path);
+    let (base, annotation) =
+        tokio::task::spawn_blocking(move || annotate::read_sidecar(&source_path))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| {
+                error!("[Annotate] 读取标注文件失败: {}", e);
+                e.to_string()
+            })?;
+    let edit = EditSource {
+        path: annotate::image_path_of(Path::new(&path)),
+        base,
+        annotation,
+    };
+    super::screenshot::open_overlays(&app, &state, "screenshot".to_string(), Some(edit)).await
+}
@@ -0,0 +1,41 @@
+use super::ocr::frozen_screenshot;
+use crate::barcode::{self, DecodedCode};
+use crate::config::AppState;
+use log::info;
+use tauri::State;
+
+/// Decode QR codes / barcodes locally on a frozen screenshot. Without a
+/// region the whole monitor is scanned. Locations are in screenshot pixels
+/// (region offset included), so the overlay can highlight them directly.
+#[tauri::command]
+pub async fn decode_codes(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: Option<u32>,
+    y: Option<u32>,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<Vec<DecodedCode>, String> {
+    let (x, y) = (x.unwrap_or(0), y.unwrap_or(0));
+    let (width, height) = (width.unwrap_or(u32::MAX), height.unwrap_or(u32::MAX));
+    info!(
+        "[Barcode] decode_codes 开始, monitor={}, region=({},{},{}x{})",
+        monitor_index, x, y, width, height
+    );
+
+    let frozen = frozen_screenshot(&state, monitor_index)?;
+    // A region past the screenshot edge is clamped by `crop_region`; offset
+    // the results by the origin that was actually cropped.
+    let (x, y, width, height) =
+        crate::screenshot::clamp_region(frozen.width(), frozen.height(), x, y, width, height);
+    let mut codes = tokio::task::spawn_blocking(move || {
+        let crop = crate::screenshot::crop_region(&frozen, x, y, width, height);
+        barcode::decode(&crop)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    barcode::offset(&mut codes, x as f64, y as f64);
+
+    info!("[Barcode] decode_codes 完成, codes={}", codes.len());
+    Ok(codes)
+}
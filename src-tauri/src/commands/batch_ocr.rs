@@ -0,0 +1,465 @@
+use super::image_ocr::normalize_image;
+use super::ocr::OcrService;
+use crate::batch_ocr::{
+    self, BatchEntry, BatchManifest, BatchOutputFormat, EntryStatus, COMBINED_FILE_NAME,
+    DEFAULT_OUTPUT_DIR_NAME,
+};
+use crate::config::AppState;
+use crate::jobs::JobControl;
+use crate::ocr::OcrMode;
+use crate::translation::{OpenAiCompatProvider, TranslationContext};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+use tokio::task::JoinSet;
+
+/// Images recognized at the same time when the request does not say.
+const DEFAULT_CONCURRENCY: usize = 2;
+/// Upper bound for the requested concurrency; each image may itself be split
+/// into up to 3 concurrent tile requests.
+const MAX_CONCURRENCY: usize = 6;
+/// Attempts per image before it is recorded as failed.
+const MAX_ATTEMPTS: u32 = 2;
+/// Pause before retrying a failed image, e.g. after a rate-limit response.
+const RETRY_DELAY: Duration = Duration::from_secs(3);
+/// How often the job loop checks for cancel/pause while images are running.
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Deserialize)]
+pub struct BatchOcrRequest {
+    pub input_dir: String,
+    /// Defaults to `<input_dir>/transshot-ocr`.
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub recursive: bool,
+    pub language: String,
+    /// Defaults to Markdown for `md` output, plain text otherwise.
+    #[serde(default)]
+    pub mode: Option<OcrMode>,
+    /// Also translate each result with the translation service.
+    #[serde(default)]
+    pub target_lang: Option<String>,
+    #[serde(default)]
+    pub format: BatchOutputFormat,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Clone, Serialize)]
+struct BatchProgress {
+    job_id: String,
+    /// Files finished in this run or a previous one (done or failed).
+    completed: usize,
+    failed: usize,
+    /// Files already done in a previous run.
+    skipped: usize,
+    total: usize,
+    /// Relative path of the file that just finished.
+    current: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct BatchFinished {
+    job_id: String,
+    output_dir: String,
+    completed: usize,
+    failed: usize,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/// Everything one image needs, captured once per job.
+struct BatchParams {
+    service: OcrService,
+    client: reqwest::Client,
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    language: String,
+    mode: OcrMode,
+    format: BatchOutputFormat,
+    translate: Option<TranslateParams>,
+}
+
+struct TranslateParams {
+    target_lang: String,
+    base_url: String,
+    api_key: String,
+    model: String,
+    extra: String,
+}
+
+#[derive(Default)]
+struct Counts {
+    completed: usize,
+    failed: usize,
+    skipped: usize,
+    total: usize,
+}
+
+/// Start OCR of every image in a directory in the background. Returns the job
+/// id immediately; progress is reported via `batch-ocr-progress` events and
+/// the result via a single `batch-ocr-finished` event. Starting again with the
+/// same directories and options resumes where a cancelled or failed run
+/// stopped.
+#[tauri::command]
+pub async fn start_batch_ocr(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request: BatchOcrRequest,
+) -> Result<String, String> {
+    info!("[BatchOcr] start_batch_ocr, request={:?}", request);
+    let input_dir = PathBuf::from(&request.input_dir);
+    if !input_dir.is_dir() {
+        return Err(format!("目录不存在: {}", request.input_dir));
+    }
+    let output_dir = request
+        .output_dir
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| input_dir.join(DEFAULT_OUTPUT_DIR_NAME));
+    let target_lang = request.target_lang.filter(|lang| !lang.trim().is_empty());
+    let translate = match target_lang {
+        Some(target_lang) => {
+            let settings = state.settings.lock().map_err(|e| e.to_string())?;
+            let (b, k, m) = settings
+                .translation
+                .resolved(&settings.base_url, &settings.api_key);
+            Some(TranslateParams {
+                target_lang,
+                base_url: b,
+                api_key: k,
+                model: m,
+                extra: settings.translation.extra.clone(),
+            })
+        }
+        None => None,
+    };
+    let params = Arc::new(BatchParams {
+        service: OcrService::from_state(&state)?,
+        client: state.http_client.clone(),
+        input_dir,
+        output_dir,
+        language: request.language,
+        mode: request
+            .mode
+            .unwrap_or_else(|| request.format.default_mode()),
+        format: request.format,
+        translate,
+    });
+    let concurrency = request
+        .concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+    let recursive = request.recursive;
+    let (job_id, control) = state.jobs.start("batch-ocr");
+    info!(
+        "[BatchOcr] 任务已创建, job_id={}, output={}, concurrency={}",
+        job_id,
+        params.output_dir.display(),
+        concurrency
+    );
+
+    let task_job_id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut counts = Counts::default();
+        let result = run_batch_job(
+            &app,
+            &task_job_id,
+            &control,
+            params.clone(),
+            recursive,
+            concurrency,
+            &mut counts,
+        )
+        .await;
+
+        let (cancelled, error) = match result {
+            Ok(true) => {
+                info!(
+                    "[BatchOcr] 任务完成, job_id={}, completed={}, failed={}",
+                    task_job_id, counts.completed, counts.failed
+                );
+                (false, None)
+            }
+            Ok(false) => {
+                info!("[BatchOcr] 任务已取消, job_id={}", task_job_id);
+                (true, None)
+            }
+            Err(e) => {
+                error!("[BatchOcr] 任务失败, job_id={}: {}", task_job_id, e);
+                (false, Some(e.to_string()))
+            }
+        };
+        app.state::<AppState>().jobs.finish(&task_job_id);
+        let _ = app.emit(
+            "batch-ocr-finished",
+            BatchFinished {
+                job_id: task_job_id.clone(),
+                output_dir: params.output_dir.display().to_string(),
+                completed: counts.completed,
+                failed: counts.failed,
+                cancelled,
+                error,
+            },
+        );
+    });
+
+    Ok(job_id)
+}
+
+/// Stop starting new images; images already running finish.
+#[tauri::command]
+pub async fn pause_batch_ocr(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    info!("[BatchOcr] pause_batch_ocr, job_id={}", job_id);
+    if state.jobs.pause(&job_id) {
+        Ok(())
+    } else {
+        Err(format!("No running job {}", job_id))
+    }
+}
+
+#[tauri::command]
+pub async fn resume_batch_ocr(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    info!("[BatchOcr] resume_batch_ocr, job_id={}", job_id);
+    if state.jobs.resume(&job_id) {
+        Ok(())
+    } else {
+        Err(format!("No running job {}", job_id))
+    }
+}
+
+/// Cancel a batch job. Images in flight are abandoned; finished ones stay in
+/// the manifest so the job can be resumed later.
+#[tauri::command]
+pub async fn cancel_batch_ocr(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    info!("[BatchOcr] cancel_batch_ocr, job_id={}", job_id);
+    if state.jobs.cancel(&job_id) {
+        Ok(())
+    } else {
+        Err(format!("No running job {}", job_id))
+    }
+}
+
+fn emit_progress(app: &tauri::AppHandle, job_id: &str, counts: &Counts, current: Option<String>) {
+    let _ = app.emit(
+        "batch-ocr-progress",
+        BatchProgress {
+            job_id: job_id.to_string(),
+            completed: counts.completed,
+            failed: counts.failed,
+            skipped: counts.skipped,
+            total: counts.total,
+            current,
+        },
+    );
+}
+
+async fn save_manifest(manifest: &BatchManifest, output_dir: &Path) -> anyhow::Result<()> {
+    let bytes = serde_json::to_vec_pretty(manifest)?;
+    let path = output_dir.join(batch_ocr::MANIFEST_FILE_NAME);
+    tokio::task::spawn_blocking(move || crate::output::write_file(&path, &bytes)).await?
+}
+
+/// Returns `Ok(false)` when cancelled.
+async fn run_batch_job(
+    app: &tauri::AppHandle,
+    job_id: &str,
+    control: &JobControl,
+    params: Arc<BatchParams>,
+    recursive: bool,
+    concurrency: usize,
+    counts: &mut Counts,
+) -> anyhow::Result<bool> {
+    let options_key = batch_ocr::options_key(
+        &params.language,
+        params.mode,
+        params.translate.as_ref().map(|t| t.target_lang.as_str()),
+        params.format,
+    );
+    let (files, mut manifest) = {
+        let params = params.clone();
+        tokio::task::spawn_blocking(move || {
+            let files =
+                batch_ocr::collect_images(&params.input_dir, recursive, &params.output_dir)?;
+            let manifest = BatchManifest::load(&params.output_dir, &options_key);
+            anyhow::Ok((files, manifest))
+        })
+        .await??
+    };
+    let mut queue: VecDeque<String> = files
+        .iter()
+        .filter(|file| !manifest.is_done(file))
+        .cloned()
+        .collect();
+    counts.total = files.len();
+    counts.skipped = files.len() - queue.len();
+    counts.completed = counts.skipped;
+    info!(
+        "[BatchOcr] 共 {} 张图片, 已完成跳过 {}, 待处理 {}",
+        counts.total,
+        counts.skipped,
+        queue.len()
+    );
+    emit_progress(app, job_id, counts, None);
+
+    let mut tasks = JoinSet::new();
+    loop {
+        if control.is_cancelled() {
+            tasks.abort_all();
+            save_manifest(&manifest, &params.output_dir).await?;
+            return Ok(false);
+        }
+        if !control.is_paused() {
+            while tasks.len() < concurrency {
+                let Some(file) = queue.pop_front() else {
+                    break;
+                };
+                let params = params.clone();
+                tasks.spawn(async move {
+                    let entry = process_file(&params, &file).await;
+                    (file, entry)
+                });
+            }
+        }
+        if tasks.is_empty() {
+            if queue.is_empty() {
+                break;
+            }
+            control.wait_while_paused().await;
+            continue;
+        }
+
+        let joined = tokio::select! {
+            joined = tasks.join_next() => joined,
+            _ = tokio::time::sleep(CONTROL_POLL_INTERVAL) => continue,
+        };
+        let Some(joined) = joined else {
+            continue;
+        };
+        let (file, entry) = joined?;
+        counts.completed += 1;
+        if entry.status == EntryStatus::Failed {
+            counts.failed += 1;
+        }
+        manifest.entries.insert(file.clone(), entry);
+        save_manifest(&manifest, &params.output_dir).await?;
+        emit_progress(app, job_id, counts, Some(file));
+    }
+
+    if params.format == BatchOutputFormat::Json {
+        let bytes = batch_ocr::render_combined(&manifest, &files)?;
+        let path = params.output_dir.join(COMBINED_FILE_NAME);
+        tokio::task::spawn_blocking(move || crate::output::write_file(&path, &bytes)).await??;
+    }
+    Ok(true)
+}
+
+/// OCR (and translate) one image with retries, writing per-file output.
+/// Errors are recorded in the returned entry instead of failing the job.
+async fn process_file(params: &BatchParams, file: &str) -> BatchEntry {
+    let mut attempt = 1;
+    loop {
+        match recognize_file(params, file).await {
+            Ok((text, translation)) => {
+                return BatchEntry {
+                    status: EntryStatus::Done,
+                    text,
+                    translation,
+                    error: None,
+                }
+            }
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                warn!(
+                    "[BatchOcr] {} 第 {} 次处理失败, {}s 后重试: {}",
+                    file,
+                    attempt,
+                    RETRY_DELAY.as_secs(),
+                    e
+                );
+                tokio::time::sleep(RETRY_DELAY).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                error!("[BatchOcr] {} 处理失败: {}", file, e);
+                return BatchEntry {
+                    status: EntryStatus::Failed,
+                    text: String::new(),
+                    translation: None,
+                    error: Some(e.to_string()),
+                };
+            }
+        }
+    }
+}
+
+async fn recognize_file(
+    params: &BatchParams,
+    file: &str,
+) -> anyhow::Result<(String, Option<String>)> {
+    let started = std::time::Instant::now();
+    let path = params.input_dir.join(file);
+    let bytes = tokio::task::spawn_blocking(move || {
+        let bytes = std::fs::read(&path)?;
+        normalize_image(bytes)
+    })
+    .await??;
+    let text = params
+        .service
+        .recognize(&params.client, &bytes, &params.language, params.mode)
+        .await?;
+
+    let translation = match &params.translate {
+        Some(t) if !text.trim().is_empty() => Some(
+            OpenAiCompatProvider::new(params.client.clone())
+                .translate(
+                    &text,
+                    &params.language,
+                    &t.target_lang,
+                    &t.base_url,
+                    &t.api_key,
+                    &t.model,
+                    &t.extra,
+                    &TranslationContext::default(),
+                )
+                .await?,
+        ),
+        _ => None,
+    };
+
+    if params.format != BatchOutputFormat::Json {
+        let mut outputs = vec![(
+            batch_ocr::output_path(&params.output_dir, file, params.format, None),
+            text.clone(),
+        )];
+        if let (Some(t), Some(translation)) = (&params.translate, &translation) {
+            outputs.push((
+                batch_ocr::output_path(
+                    &params.output_dir,
+                    file,
+                    params.format,
+                    Some(&t.target_lang),
+                ),
+                translation.clone(),
+            ));
+        }
+        tokio::task::spawn_blocking(move || {
+            for (path, content) in outputs {
+                crate::output::write_file(&path, content.as_bytes())?;
+            }
+            anyhow::Ok(())
+        })
+        .await??;
+    }
+    info!(
+        "[BatchOcr] {} 完成, 结果长度={}, 耗时 {}ms",
+        file,
+        text.len(),
+        started.elapsed().as_millis()
+    );
+    Ok((text, translation))
+}
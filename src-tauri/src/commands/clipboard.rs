@@ -1,4 +1,6 @@
-use log::{error, info, warn};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use log::warn;
+use log::{error, info};
 
 /// Helper: build a PowerShell Command with CREATE_NO_WINDOW on Windows to suppress the console window.
 #[cfg(target_os = "windows")]
@@ -382,7 +384,7 @@ fn get_selected_text_clipboard_fallback_windows() -> Result<String, String> {
 #[tauri::command]
 pub async fn read_clipboard() -> Result<String, String> {
     info!("[Clipboard] read_clipboard 请求");
-    let result = tokio::task::spawn_blocking(|| {
+    let result: Result<String, String> = tokio::task::spawn_blocking(|| {
         #[cfg(target_os = "macos")]
         {
             pbpaste_utf8()
@@ -411,9 +413,14 @@ pub async fn read_clipboard() -> Result<String, String> {
     result
 }
 
-/// Save base64 PNG data to a file path chosen by the user.
+/// Save base64 image data to a file path chosen by the user, transcoding to
+/// PNG / JPEG / WebP according to the path's extension.
 #[tauri::command]
-pub async fn save_file(path: String, base64_data: String) -> Result<(), String> {
+pub async fn save_file(
+    state: tauri::State<'_, crate::config::AppState>,
+    path: String,
+    base64_data: String,
+) -> Result<(), String> {
     info!(
         "[Clipboard] save_file, path={}, base64 size={}",
         path,
@@ -423,11 +430,168 @@ pub async fn save_file(path: String, base64_data: String) -> Result<(), String>
     let bytes = base64::engine::general_purpose::STANDARD
         .decode(&base64_data)
         .map_err(|e| format!("base64 decode failed: {}", e))?;
-    std::fs::write(&path, &bytes).map_err(|e| format!("write file failed: {}", e))?;
+    let jpeg_quality = state
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .screenshot
+        .jpeg_quality;
+    // The data is usually PNG; re-encode when the chosen extension says otherwise.
+    let path_for_encode = path.clone();
+    let bytes = tokio::task::spawn_blocking(move || {
+        crate::screenshot::save::transcode_for_path(
+            std::path::Path::new(&path_for_encode),
+            bytes,
+            jpeg_quality,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("transcode failed: {}", e))?;
+    crate::output::write_file(std::path::Path::new(&path), &bytes)
+        .map_err(|e| format!("write file failed: {}", e))?;
     info!("[Clipboard] 文件已保存: {}, size={}", path, bytes.len());
     Ok(())
 }
 
+/// A temp file name no other clipboard transfer in this process uses, so
+/// concurrent copies and reads (hotkey, tray, pins) never share a file.
+fn unique_temp_path(kind: &str) -> std::path::PathBuf {
+    static TEMP_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let seq = TEMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "dh_transshot_clipboard_{}_{}_{}.png",
+        kind,
+        std::process::id(),
+        seq
+    ))
+}
+
+/// Read the image currently on the system clipboard as PNG bytes. Blocking.
+/// The image is exported through a temp file, mirroring `write_clipboard_image`.
+pub(crate) fn read_clipboard_image() -> Result<Vec<u8>, String> {
+    let tmp_path = unique_temp_path("read");
+    let bytes = export_clipboard_image(&tmp_path).and_then(|()| {
+        std::fs::read(&tmp_path).map_err(|e| format!("Failed to read temp file: {}", e))
+    });
+    let _ = std::fs::remove_file(&tmp_path);
+    let bytes = bytes?;
+    info!("[Clipboard] 剪贴板图片已读取, size={}", bytes.len());
+    Ok(bytes)
+}
+
+#[cfg(target_os = "macos")]
+fn export_clipboard_image(path: &std::path::Path) -> Result<(), String> {
+    let script = format!(
+        "set png to (the clipboard as «class PNGf»)\n\
+         set f to open for access (POSIX file \"{}\") with write permission\n\
+         set eof f to 0\n\
+         write png to f\n\
+         close access f",
+        path.display()
+    );
+    let output = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| format!("osascript failed: {}", e))?;
+    if !output.status.success() {
+        info!(
+            "[Clipboard] 剪贴板中没有图片: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return Err("剪贴板中没有图片".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn export_clipboard_image(path: &std::path::Path) -> Result<(), String> {
+    let ps_script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; Add-Type -AssemblyName System.Drawing; \
+         $img = [System.Windows.Forms.Clipboard]::GetImage(); \
+         if ($img -eq $null) {{ exit 2 }}; \
+         $img.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        path.display()
+    );
+    let output = powershell_command(&ps_script)
+        .output()
+        .map_err(|e| format!("powershell failed: {}", e))?;
+    if output.status.code() == Some(2) {
+        return Err("剪贴板中没有图片".to_string());
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("powershell error: {}", stderr));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn export_clipboard_image(_path: &std::path::Path) -> Result<(), String> {
+    Err("Clipboard not supported on this platform".to_string())
+}
+
+/// Put PNG bytes on the system clipboard through a temp file of its own,
+/// removed once the clipboard has read it. Blocking.
+pub(crate) fn write_clipboard_image(png_bytes: &[u8]) -> Result<(), String> {
+    let tmp_path = unique_temp_path("write");
+    std::fs::write(&tmp_path, png_bytes)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    info!(
+        "[Clipboard] 临时文件已写入: {:?}, size={}",
+        tmp_path,
+        png_bytes.len()
+    );
+
+    let result = import_clipboard_image(&tmp_path);
+    let _ = std::fs::remove_file(&tmp_path);
+    result
+}
+
+#[cfg(target_os = "macos")]
+fn import_clipboard_image(path: &std::path::Path) -> Result<(), String> {
+    let script = format!(
+        "set the clipboard to (read (POSIX file \"{}\") as «class PNGf»)",
+        path.display()
+    );
+    let output = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| format!("osascript failed: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("osascript error: {}", stderr));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn import_clipboard_image(path: &std::path::Path) -> Result<(), String> {
+    // The image is disposed before powershell exits, so the file is unlocked
+    // by the time it is removed.
+    let ps_script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $img = [System.Drawing.Image]::FromFile('{}'); \
+         [System.Windows.Forms.Clipboard]::SetImage($img); \
+         $img.Dispose()",
+        path.display()
+    );
+    let output = powershell_command(&ps_script)
+        .output()
+        .map_err(|e| format!("powershell failed: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("powershell error: {}", stderr));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn import_clipboard_image(_path: &std::path::Path) -> Result<(), String> {
+    Err("Clipboard not supported on this platform".to_string())
+}
+
 /// Copy an image (base64 PNG) to the system clipboard.
 #[tauri::command]
 pub async fn copy_image_to_clipboard(image_base64: String) -> Result<(), String> {
@@ -442,62 +606,7 @@ pub async fn copy_image_to_clipboard(image_base64: String) -> Result<(), String>
         let png_bytes = base64::engine::general_purpose::STANDARD
             .decode(&image_base64)
             .map_err(|e| format!("base64 decode failed: {}", e))?;
-
-        // Write to temp file
-        let tmp_path = std::env::temp_dir().join("dh_transshot_clipboard.png");
-        std::fs::write(&tmp_path, &png_bytes)
-            .map_err(|e| format!("Failed to write temp file: {}", e))?;
-
-        info!(
-            "[Clipboard] 临时文件已写入: {:?}, size={}",
-            tmp_path,
-            png_bytes.len()
-        );
-
-        #[cfg(target_os = "macos")]
-        {
-            let script = format!(
-                "set the clipboard to (read (POSIX file \"{}\") as «class PNGf»)",
-                tmp_path.display()
-            );
-            let output = std::process::Command::new("osascript")
-                .args(["-e", &script])
-                .output()
-                .map_err(|e| format!("osascript failed: {}", e))?;
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let _ = std::fs::remove_file(&tmp_path);
-                return Err(format!("osascript error: {}", stderr));
-            }
-        }
-
-        #[cfg(target_os = "windows")]
-        {
-            let ps_script = format!(
-                "Add-Type -AssemblyName System.Windows.Forms; \
-                 [System.Windows.Forms.Clipboard]::SetImage(\
-                 [System.Drawing.Image]::FromFile('{}'))",
-                tmp_path.display()
-            );
-            let output = powershell_command(&ps_script)
-                .output()
-                .map_err(|e| format!("powershell failed: {}", e))?;
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let _ = std::fs::remove_file(&tmp_path);
-                return Err(format!("powershell error: {}", stderr));
-            }
-        }
-
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-        {
-            let _ = std::fs::remove_file(&tmp_path);
-            return Err("Clipboard not supported on this platform".to_string());
-        }
-
-        // Clean up temp file
-        let _ = std::fs::remove_file(&tmp_path);
-        Ok(())
+        write_clipboard_image(&png_bytes)
     })
     .await
     .map_err(|e| e.to_string())?;
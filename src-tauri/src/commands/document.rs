@@ -0,0 +1,248 @@
+use crate::config::AppState;
+use crate::document::{self, ParsedDocument, CHUNK_SEPARATOR};
+use crate::jobs::JobControl;
+use crate::translation::{OpenAiCompatProvider, TranslationContext};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{Emitter, Manager, State};
+
+#[derive(Clone, Serialize)]
+struct DocumentProgress {
+    job_id: String,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct DocumentFinished {
+    job_id: String,
+    output_path: Option<String>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/// Resolved translation request parameters, captured once per job.
+struct TranslateParams {
+    source_lang: String,
+    target_lang: String,
+    base_url: String,
+    api_key: String,
+    model: String,
+    extra: String,
+}
+
+/// Start translating a .txt/.md/.html/.pdf file in the background.
+/// Returns the job id immediately; progress is reported via `document-progress`
+/// events and the result via a single `document-finished` event.
+#[tauri::command]
+pub async fn translate_document(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    input_path: String,
+    output_path: Option<String>,
+    source_lang: String,
+    target_lang: String,
+) -> Result<String, String> {
+    info!(
+        "[Document] translate_document, input={}, output={:?}, {} → {}",
+        input_path, output_path, source_lang, target_lang
+    );
+    let params = {
+        let settings = state.settings.lock().map_err(|e| e.to_string())?;
+        let (b, k, m) = settings
+            .translation
+            .resolved(&settings.base_url, &settings.api_key);
+        TranslateParams {
+            source_lang,
+            target_lang,
+            base_url: b,
+            api_key: k,
+            model: m,
+            extra: settings.translation.extra.clone(),
+        }
+    };
+    let client = state.http_client.clone();
+    let (job_id, control) = state.jobs.start("document");
+    info!("[Document] 任务已创建, job_id={}", job_id);
+
+    let task_job_id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let input = PathBuf::from(&input_path);
+        let result = run_document_job(
+            &app,
+            &task_job_id,
+            &control,
+            client,
+            &params,
+            input,
+            output_path.map(PathBuf::from),
+        )
+        .await;
+
+        let finished = match result {
+            Ok(Some(path)) => {
+                info!(
+                    "[Document] 任务完成, job_id={}, output={}",
+                    task_job_id, path
+                );
+                DocumentFinished {
+                    job_id: task_job_id.clone(),
+                    output_path: Some(path),
+                    cancelled: false,
+                    error: None,
+                }
+            }
+            Ok(None) => {
+                info!("[Document] 任务已取消, job_id={}", task_job_id);
+                DocumentFinished {
+                    job_id: task_job_id.clone(),
+                    output_path: None,
+                    cancelled: true,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                error!("[Document] 任务失败, job_id={}: {}", task_job_id, e);
+                DocumentFinished {
+                    job_id: task_job_id.clone(),
+                    output_path: None,
+                    cancelled: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        app.state::<AppState>().jobs.finish(&task_job_id);
+        let _ = app.emit("document-finished", finished);
+    });
+
+    Ok(job_id)
+}
+
+/// Request cancellation of a running document job. The job stops before its
+/// next chunk and reports `cancelled: true`.
+#[tauri::command]
+pub async fn cancel_document_job(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    info!("[Document] cancel_document_job, job_id={}", job_id);
+    if state.jobs.cancel(&job_id) {
+        Ok(())
+    } else {
+        Err(format!("No running job {}", job_id))
+    }
+}
+
+/// Returns `Ok(None)` when cancelled.
+async fn run_document_job(
+    app: &tauri::AppHandle,
+    job_id: &str,
+    control: &JobControl,
+    client: reqwest::Client,
+    params: &TranslateParams,
+    input: PathBuf,
+    output: Option<PathBuf>,
+) -> anyhow::Result<Option<String>> {
+    let load_path = input.clone();
+    let doc = tokio::task::spawn_blocking(move || document::load(&load_path)).await??;
+    let chunks = doc.chunks();
+    let total = chunks.len();
+    info!(
+        "[Document] 解析完成, kind={:?}, segments={}, chunks={}",
+        doc.kind,
+        doc.segments.len(),
+        total
+    );
+
+    let provider = OpenAiCompatProvider::new(client);
+    let mut translations: Vec<Option<String>> = vec![None; doc.segments.len()];
+    let _ = app.emit(
+        "document-progress",
+        DocumentProgress {
+            job_id: job_id.to_string(),
+            completed: 0,
+            total,
+        },
+    );
+
+    for (n, chunk) in chunks.iter().enumerate() {
+        if control.is_cancelled() {
+            return Ok(None);
+        }
+        translate_chunk(&provider, params, &doc, chunk, &mut translations).await?;
+        let _ = app.emit(
+            "document-progress",
+            DocumentProgress {
+                job_id: job_id.to_string(),
+                completed: n + 1,
+                total,
+            },
+        );
+    }
+
+    let output = output
+        .unwrap_or_else(|| document::default_output_path(&input, doc.kind, &params.target_lang));
+    let rendered = doc.render(&translations);
+    let write_path = output.clone();
+    tokio::task::spawn_blocking(move || {
+        crate::output::write_file(&write_path, rendered.as_bytes())
+    })
+    .await??;
+    Ok(Some(output.display().to_string()))
+}
+
+/// Translate one chunk. Batched chunks are split back on the separator; if the
+/// model merged or dropped paragraphs, retranslate the segments one by one.
+async fn translate_chunk(
+    provider: &OpenAiCompatProvider,
+    params: &TranslateParams,
+    doc: &ParsedDocument,
+    chunk: &[usize],
+    translations: &mut [Option<String>],
+) -> anyhow::Result<()> {
+    let translated = translate(provider, params, &doc.chunk_text(chunk)).await?;
+    if chunk.len() == 1 {
+        translations[chunk[0]] = Some(translated);
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = translated
+        .split(CHUNK_SEPARATOR)
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.len() == chunk.len() {
+        for (&i, part) in chunk.iter().zip(parts) {
+            translations[i] = Some(part.to_string());
+        }
+        return Ok(());
+    }
+
+    warn!(
+        "[Document] 分段数不一致 (期望 {}, 实际 {})，逐段重译",
+        chunk.len(),
+        parts.len()
+    );
+    for &i in chunk {
+        let text = doc.chunk_text(&[i]);
+        translations[i] = Some(translate(provider, params, &text).await?);
+    }
+    Ok(())
+}
+
+async fn translate(
+    provider: &OpenAiCompatProvider,
+    params: &TranslateParams,
+    text: &str,
+) -> anyhow::Result<String> {
+    provider
+        .translate(
+            text,
+            &params.source_lang,
+            &params.target_lang,
+            &params.base_url,
+            &params.api_key,
+            &params.model,
+            &params.extra,
+            &TranslationContext::default(),
+        )
+        .await
+}
@@ -0,0 +1,186 @@
+use super::clipboard::read_clipboard_image;
+use super::ocr::OcrService;
+use crate::config::AppState;
+use crate::ocr::OcrMode;
+use crate::translation::{OpenAiCompatProvider, TranslationContext};
+use image::{ImageFormat, ImageReader};
+use log::{error, info};
+use serde::Serialize;
+use std::io::Cursor;
+use tauri::State;
+
+#[derive(Debug, Serialize)]
+pub struct ImageOcrResult {
+    pub text: String,
+    /// Present when a target language was requested and the text is not empty.
+    pub translation: Option<String>,
+}
+
+/// PNG and JPEG bytes are passed through; other formats (WebP, BMP, TIFF) are
+/// re-encoded as PNG so every OCR engine and preprocessing step can read them.
+pub(crate) fn normalize_image(bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    let format = image::guess_format(&bytes)
+        .map_err(|_| anyhow::anyhow!("无法识别的图片格式（支持 PNG、JPEG、WebP、BMP、TIFF）"))?;
+    if matches!(format, ImageFormat::Png | ImageFormat::Jpeg) {
+        return Ok(bytes);
+    }
+    let img = ImageReader::with_format(Cursor::new(&bytes), format).decode()?;
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, ImageFormat::Png)?;
+    info!(
+        "[ImageOcr] {:?} 已转为 PNG, {}x{}, size={} -> {}",
+        format,
+        img.width(),
+        img.height(),
+        bytes.len(),
+        buf.get_ref().len()
+    );
+    Ok(buf.into_inner())
+}
+
+/// OCR image bytes and, when `target_lang` is set, translate the result with
+/// the translation service.
+async fn recognize_and_translate(
+    state: &AppState,
+    image_bytes: Vec<u8>,
+    language: &str,
+    mode: OcrMode,
+    target_lang: Option<String>,
+) -> Result<ImageOcrResult, String> {
+    let service = OcrService::from_state(state)?;
+    let client = state.http_client.clone();
+    let image_bytes = tokio::task::spawn_blocking(move || normalize_image(image_bytes))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let text = service
+        .recognize(&client, &image_bytes, language, mode)
+        .await
+        .map_err(|e| {
+            error!("[ImageOcr] OCR 失败: {}", e);
+            e.to_string()
+        })?;
+    info!("[ImageOcr] OCR 完成, 结果长度={}", text.len());
+
+    let Some(target_lang) = target_lang.filter(|_| !text.trim().is_empty()) else {
+        return Ok(ImageOcrResult {
+            text,
+            translation: None,
+        });
+    };
+    let (base_url, api_key, model, extra) = {
+        let settings = state.settings.lock().map_err(|e| e.to_string())?;
+        let (b, k, m) = settings
+            .translation
+            .resolved(&settings.base_url, &settings.api_key);
+        (b, k, m, settings.translation.extra.clone())
+    };
+    let translation = OpenAiCompatProvider::new(client)
+        .translate(
+            &text,
+            language,
+            &target_lang,
+            &base_url,
+            &api_key,
+            &model,
+            &extra,
+            &TranslationContext::default(),
+        )
+        .await
+        .map_err(|e| {
+            error!("[ImageOcr] 翻译失败: {}", e);
+            e.to_string()
+        })?;
+    info!("[ImageOcr] 翻译完成, 结果长度={}", translation.len());
+    state
+        .translation_history
+        .lock()
+        .map_err(|e| e.to_string())?
+        .push(text.clone(), translation.clone());
+    Ok(ImageOcrResult {
+        text,
+        translation: Some(translation),
+    })
+}
+
+/// OCR an image file (PNG, JPEG, WebP, BMP, TIFF), e.g. one dropped onto the
+/// main window, optionally translating it into `target_lang`.
+#[tauri::command]
+pub async fn ocr_image_file(
+    state: State<'_, AppState>,
+    path: String,
+    language: String,
+    mode: Option<OcrMode>,
+    target_lang: Option<String>,
+) -> Result<ImageOcrResult, String> {
+    info!(
+        "[ImageOcr] ocr_image_file 开始, path={}, language={}, mode={:?}, target={:?}",
+        path, language, mode, target_lang
+    );
+    let bytes = tokio::task::spawn_blocking(move || std::fs::read(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("读取图片失败: {}", e))?;
+    recognize_and_translate(
+        &state,
+        bytes,
+        &language,
+        mode.unwrap_or_default(),
+        target_lang,
+    )
+    .await
+}
+
+/// OCR the image currently on the clipboard, optionally translating it.
+#[tauri::command]
+pub async fn ocr_clipboard_image(
+    state: State<'_, AppState>,
+    language: String,
+    mode: Option<OcrMode>,
+    target_lang: Option<String>,
+) -> Result<ImageOcrResult, String> {
+    info!(
+        "[ImageOcr] ocr_clipboard_image 开始, language={}, mode={:?}, target={:?}",
+        language, mode, target_lang
+    );
+    let bytes = tokio::task::spawn_blocking(read_clipboard_image)
+        .await
+        .map_err(|e| e.to_string())??;
+    recognize_and_translate(
+        &state,
+        bytes,
+        &language,
+        mode.unwrap_or_default(),
+        target_lang,
+    )
+    .await
+}
+
+/// OCR the last scrolling capture (see `scroll_capture`), optionally
+/// translating it.
+#[tauri::command]
+pub async fn ocr_scroll_capture(
+    state: State<'_, AppState>,
+    language: String,
+    mode: Option<OcrMode>,
+    target_lang: Option<String>,
+) -> Result<ImageOcrResult, String> {
+    info!(
+        "[ImageOcr] ocr_scroll_capture 开始, language={}, mode={:?}, target={:?}",
+        language, mode, target_lang
+    );
+    let img = super::scroll_capture::scroll_result(&state)?;
+    let bytes = tokio::task::spawn_blocking(move || crate::screenshot::encode_png(&img))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    recognize_and_translate(
+        &state,
+        bytes,
+        &language,
+        mode.unwrap_or_default(),
+        target_lang,
+    )
+    .await
+}
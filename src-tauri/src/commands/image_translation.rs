@@ -0,0 +1,175 @@
+use super::ocr::{crop_frozen_image, OcrService};
+use crate::config::AppState;
+use crate::ocr::structured::BoundingBox;
+use crate::render::{self, RenderBlock};
+use crate::translation::{OpenAiCompatProvider, TranslationContext};
+use base64::Engine;
+use image::ImageFormat;
+use log::{error, info, warn};
+use serde::Serialize;
+use std::io::Cursor;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Block translations in flight at the same time, so a dense screenshot
+/// doesn't fire dozens of requests at the provider at once.
+const MAX_CONCURRENT_TRANSLATIONS: usize = 4;
+
+#[derive(Debug, Serialize)]
+pub struct TranslatedBlock {
+    pub bbox: BoundingBox,
+    pub source: String,
+    /// `None` when this block's translation failed; it is left unpainted.
+    pub translation: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageTranslation {
+    /// Base64 PNG with translations painted in place, ready for
+    /// `copy_image_to_clipboard` / `save_file`.
+    pub image: String,
+    pub blocks: Vec<TranslatedBlock>,
+}
+
+/// Capture a region, OCR it with text boxes, translate every block and paint
+/// the translations over the original pixels.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn translate_image_region(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    source_lang: String,
+    target_lang: String,
+) -> Result<ImageTranslation, String> {
+    info!(
+        "[ImageTranslation] 开始, monitor={}, region=({},{},{}x{}), {} → {}",
+        monitor_index, x, y, width, height, source_lang, target_lang
+    );
+
+    let ocr = OcrService::from_state(&state)?;
+    let (base_url, api_key, model, extra) = {
+        let settings = state.settings.lock().map_err(|e| e.to_string())?;
+        let (b, k, m) = settings
+            .translation
+            .resolved(&settings.base_url, &settings.api_key);
+        (b, k, m, settings.translation.extra.clone())
+    };
+    let client = state.http_client.clone();
+
+    let crop = crop_frozen_image(&state, monitor_index, x, y, width, height).await?;
+    let jpeg = crate::screenshot::encode_jpeg(&crop).map_err(|e| e.to_string())?;
+    let layout = ocr
+        .recognize_structured(&client, &jpeg, &source_lang)
+        .await
+        .map_err(|e| {
+            error!("[ImageTranslation] OCR 失败: {}", e);
+            e.to_string()
+        })?;
+    if layout.blocks.is_empty() {
+        return Err("未识别到文字".to_string());
+    }
+
+    // Blocks are translated concurrently (at most
+    // `MAX_CONCURRENT_TRANSLATIONS` at a time); the full OCR text is passed as
+    // context so short labels are translated consistently with their page.
+    let surrounding = layout.text();
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_TRANSLATIONS));
+    let mut tasks = JoinSet::new();
+    for (index, block) in layout.blocks.iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let provider = OpenAiCompatProvider::new(client.clone());
+        let text = block.text.clone();
+        let context = TranslationContext {
+            surrounding: Some(surrounding.clone()),
+            ..Default::default()
+        };
+        let (source_lang, target_lang) = (source_lang.clone(), target_lang.clone());
+        let (base_url, api_key, model, extra) = (
+            base_url.clone(),
+            api_key.clone(),
+            model.clone(),
+            extra.clone(),
+        );
+        tasks.spawn(async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return (index, Err(anyhow::anyhow!("翻译任务已取消")));
+            };
+            let result = provider
+                .translate(
+                    &text,
+                    &source_lang,
+                    &target_lang,
+                    &base_url,
+                    &api_key,
+                    &model,
+                    &extra,
+                    &context,
+                )
+                .await;
+            (index, result)
+        });
+    }
+
+    let mut translations: Vec<Option<String>> = vec![None; layout.blocks.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, Ok(translated))) => {
+                translations[index] = Some(translated.trim().to_string())
+            }
+            Ok((index, Err(e))) => warn!("[ImageTranslation] 第 {} 块翻译失败: {}", index, e),
+            Err(e) => warn!("[ImageTranslation] 翻译任务异常: {}", e),
+        }
+    }
+    if translations.iter().all(Option::is_none) {
+        return Err("所有文本块翻译失败".to_string());
+    }
+
+    let render_blocks: Vec<RenderBlock> = layout
+        .blocks
+        .iter()
+        .zip(&translations)
+        .filter_map(|(block, translation)| {
+            translation.as_ref().map(|text| RenderBlock {
+                bbox: block.bbox,
+                text: text.clone(),
+                line_count: block.lines.len().max(1),
+            })
+        })
+        .collect();
+    let image = tokio::task::spawn_blocking(move || -> anyhow::Result<String> {
+        let mut rgba = crop.to_rgba8();
+        render::render_translations(&mut rgba, &render_blocks)?;
+        let mut buf = Cursor::new(Vec::new());
+        rgba.write_to(&mut buf, ImageFormat::Png)?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(buf.into_inner()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[ImageTranslation] 渲染失败: {}", e);
+        e.to_string()
+    })?;
+
+    let blocks: Vec<TranslatedBlock> = layout
+        .blocks
+        .into_iter()
+        .zip(translations)
+        .map(|(block, translation)| TranslatedBlock {
+            bbox: block.bbox,
+            source: block.text,
+            translation,
+        })
+        .collect();
+    info!(
+        "[ImageTranslation] 完成, blocks={}, PNG base64 size={}",
+        blocks.len(),
+        image.len()
+    );
+    Ok(ImageTranslation { image, blocks })
+}
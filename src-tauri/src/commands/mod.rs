@@ -1,6 +1,15 @@
+pub mod actions;
+pub mod annotate;
+pub mod barcode;
+pub mod batch_ocr;
 pub mod clipboard;
+pub mod document;
+pub mod image_ocr;
+pub mod image_translation;
 pub mod ocr;
+pub mod pin;
 pub mod screenshot;
+pub mod scroll_capture;
 pub mod settings;
 pub mod translation;
 pub mod tts;
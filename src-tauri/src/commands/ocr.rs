@@ -1,9 +1,309 @@
-use crate::config::AppState;
-use log::{error, info};
+use crate::config::{AppState, OcrEngine};
+use crate::ocr::cache::OcrCacheStats;
+use crate::ocr::one_shot::VisionTranslation;
+use crate::ocr::preprocess::{self, PreprocessTransform};
+use crate::ocr::{prompt, tiling, OcrCache, OcrLayout, OcrMode, OcrTiling, PreprocessProfile};
+use base64::Engine;
+use image::{DynamicImage, RgbaImage};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
 use tauri::State;
 
+/// OCR settings resolved from `Settings.ocr`, dispatching to the configured
+/// engine.
+#[derive(Clone)]
+pub(crate) struct OcrService {
+    pub engine: OcrEngine,
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+    pub extra: String,
+    pub tesseract_path: String,
+    pub prompts: BTreeMap<OcrMode, String>,
+    /// Active preprocessing profile, `None` when disabled or not found.
+    pub preprocess: Option<PreprocessProfile>,
+    pub tiling: OcrTiling,
+    pub cache: Arc<OcrCache>,
+}
+
+impl OcrService {
+    pub(crate) fn from_state(state: &AppState) -> Result<Self, String> {
+        let guard = state.settings.lock().map_err(|e| e.to_string())?;
+        let (base_url, api_key, model) =
+            guard.ocr.service.resolved(&guard.base_url, &guard.api_key);
+        let profile_name = guard.ocr.preprocess_profile.trim();
+        let preprocess = if profile_name.is_empty() {
+            None
+        } else {
+            let found = preprocess::find_profile(&guard.ocr.preprocess_profiles, profile_name);
+            if found.is_none() {
+                warn!("[OCR] 预处理 profile 不存在: {}，跳过预处理", profile_name);
+            }
+            found
+        };
+        Ok(Self {
+            engine: guard.ocr.engine,
+            base_url,
+            api_key,
+            model,
+            extra: guard.ocr.service.extra.clone(),
+            tesseract_path: guard.ocr.tesseract_path.clone(),
+            prompts: guard.ocr.prompts.clone(),
+            preprocess,
+            tiling: guard.ocr.tiling,
+            cache: state.ocr_cache.clone(),
+        })
+    }
+
+    /// Run the active preprocessing profile. Returns the bytes to recognize
+    /// and, when preprocessing ran, the transform back to crop coordinates.
+    async fn preprocessed(
+        &self,
+        image_bytes: &[u8],
+    ) -> anyhow::Result<(Vec<u8>, Option<PreprocessTransform>)> {
+        match &self.preprocess {
+            Some(profile) => {
+                let processed = preprocess::run(image_bytes, profile).await?;
+                Ok((processed.png_bytes, Some(processed.transform)))
+            }
+            None => Ok((image_bytes.to_vec(), None)),
+        }
+    }
+
+    /// Tesseract has no upload limit, so it always gets the whole image.
+    fn effective_tiling(&self) -> OcrTiling {
+        match self.engine {
+            OcrEngine::Vision => self.tiling,
+            OcrEngine::Tesseract => OcrTiling::Off,
+        }
+    }
+
+    /// Plain-text OCR: preprocessing, then tiled recognition for large crops
+    /// (see `ocr::tiling`) or a single request.
+    pub(crate) async fn recognize(
+        &self,
+        client: &reqwest::Client,
+        image_bytes: &[u8],
+        language: &str,
+        mode: OcrMode,
+    ) -> anyhow::Result<String> {
+        let (image_bytes, _) = self.preprocessed(image_bytes).await?;
+        let Some(plan) = tiling::split(&image_bytes, self.effective_tiling()).await? else {
+            return self
+                .recognize_image(client, &image_bytes, language, mode)
+                .await;
+        };
+        let started = Instant::now();
+        let texts = tiling::recognize_tiles(&plan.tiles, |bytes| {
+            let (service, client) = (self.clone(), client.clone());
+            let language = language.to_string();
+            async move {
+                service
+                    .recognize_image(&client, &bytes, &language, mode)
+                    .await
+            }
+        })
+        .await?;
+        info!(
+            "[OCR] 分块识别完成, tiles={}, 耗时 {}ms",
+            texts.len(),
+            started.elapsed().as_millis()
+        );
+        Ok(tiling::merge_text(&plan, &texts))
+    }
+
+    async fn recognize_image(
+        &self,
+        client: &reqwest::Client,
+        image_bytes: &[u8],
+        language: &str,
+        mode: OcrMode,
+    ) -> anyhow::Result<String> {
+        match self.engine {
+            OcrEngine::Vision => {
+                let prompt = prompt::recognition_prompt(
+                    language,
+                    mode,
+                    self.prompts.get(&mode).map(String::as_str),
+                );
+                let text = crate::ocr::recognize(
+                    client,
+                    image_bytes,
+                    &prompt,
+                    &self.base_url,
+                    &self.api_key,
+                    &self.model,
+                    &self.extra,
+                    Some(&self.cache),
+                )
+                .await?;
+                Ok(prompt::clean_output(mode, &text))
+            }
+            OcrEngine::Tesseract => {
+                if mode != OcrMode::Plain {
+                    info!("[OCR] tesseract 不支持输出模式 {:?}，按纯文本识别", mode);
+                }
+                crate::ocr::tesseract::recognize(image_bytes, language, &self.tesseract_path).await
+            }
+        }
+    }
+
+    /// Structured OCR with the same preprocessing and tiling as `recognize`;
+    /// boxes are returned in crop pixel coordinates.
+    pub(crate) async fn recognize_structured(
+        &self,
+        client: &reqwest::Client,
+        image_bytes: &[u8],
+        language: &str,
+    ) -> anyhow::Result<OcrLayout> {
+        let (image_bytes, transform) = self.preprocessed(image_bytes).await?;
+        let layout = match tiling::split(&image_bytes, self.effective_tiling()).await? {
+            Some(plan) => {
+                let started = Instant::now();
+                let layouts = tiling::recognize_tiles(&plan.tiles, |bytes| {
+                    let (service, client) = (self.clone(), client.clone());
+                    let language = language.to_string();
+                    async move {
+                        service
+                            .recognize_image_structured(&client, &bytes, &language)
+                            .await
+                    }
+                })
+                .await?;
+                info!(
+                    "[OCR] 分块结构化识别完成, tiles={}, 耗时 {}ms",
+                    layouts.len(),
+                    started.elapsed().as_millis()
+                );
+                tiling::merge_layouts(&plan, layouts)
+            }
+            None => {
+                self.recognize_image_structured(client, &image_bytes, language)
+                    .await?
+            }
+        };
+        Ok(match transform {
+            Some(transform) => transform.unmap_layout(layout),
+            None => layout,
+        })
+    }
+
+    async fn recognize_image_structured(
+        &self,
+        client: &reqwest::Client,
+        image_bytes: &[u8],
+        language: &str,
+    ) -> anyhow::Result<OcrLayout> {
+        match self.engine {
+            OcrEngine::Vision => {
+                crate::ocr::recognize_structured(
+                    client,
+                    image_bytes,
+                    language,
+                    &self.base_url,
+                    &self.api_key,
+                    &self.model,
+                    &self.extra,
+                    Some(&self.cache),
+                )
+                .await
+            }
+            OcrEngine::Tesseract => {
+                crate::ocr::tesseract::recognize_structured(
+                    image_bytes,
+                    language,
+                    &self.tesseract_path,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Transcription and translation in one vision request (after
+    /// preprocessing; no tiling). The tesseract engine cannot translate.
+    pub(crate) async fn recognize_and_translate(
+        &self,
+        client: &reqwest::Client,
+        image_bytes: &[u8],
+        source_lang: &str,
+        target_lang: &str,
+    ) -> anyhow::Result<VisionTranslation> {
+        if matches!(self.engine, OcrEngine::Tesseract) {
+            anyhow::bail!("tesseract 引擎不支持一次请求识别+翻译，请切换到两步流程");
+        }
+        let (image_bytes, _) = self.preprocessed(image_bytes).await?;
+        crate::ocr::one_shot::recognize_and_translate(
+            client,
+            &image_bytes,
+            source_lang,
+            target_lang,
+            &self.base_url,
+            &self.api_key,
+            &self.model,
+            &self.extra,
+            Some(&self.cache),
+        )
+        .await
+    }
+}
+
+/// Shared handle to a frozen monitor screenshot; cloning does not copy pixels.
+pub(crate) fn frozen_screenshot(
+    state: &AppState,
+    monitor_index: usize,
+) -> Result<Arc<RgbaImage>, String> {
+    let guard = state.frozen_screenshots.lock().map_err(|e| e.to_string())?;
+    guard
+        .get(monitor_index)
+        .cloned()
+        .ok_or_else(|| format!("No frozen screenshot for monitor {}", monitor_index))
+}
+
+/// Crop a region of a frozen monitor screenshot without re-encoding.
+pub(crate) async fn crop_frozen_image(
+    state: &AppState,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<DynamicImage, String> {
+    let frozen = frozen_screenshot(state, monitor_index)?;
+
+    tokio::task::spawn_blocking(move || {
+        crate::screenshot::crop_region(&frozen, x, y, width, height)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Crop a region of a frozen monitor screenshot and return it as JPEG bytes.
+pub(crate) async fn crop_frozen_region(
+    state: &AppState,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, String> {
+    let frozen = frozen_screenshot(state, monitor_index)?;
+
+    // Crop the decoded screenshot and encode only the crop as JPEG
+    tokio::task::spawn_blocking(move || {
+        crate::screenshot::capture_region_bytes(&frozen, x, y, width, height)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
 /// Combined capture + OCR: crop region from frozen screenshot and recognize text in a single step.
+/// `mode` selects the output format (plain text by default).
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn capture_and_ocr(
     state: State<'_, AppState>,
     monitor_index: usize,
@@ -12,55 +312,218 @@ pub async fn capture_and_ocr(
     width: u32,
     height: u32,
     language: String,
+    mode: Option<OcrMode>,
 ) -> Result<String, String> {
+    let mode = mode.unwrap_or_default();
     info!(
-        "[OCR] capture_and_ocr 开始, monitor={}, region=({},{},{}x{}), language={}",
-        monitor_index, x, y, width, height, language
+        "[OCR] capture_and_ocr 开始, monitor={}, region=({},{},{}x{}), language={}, mode={:?}",
+        monitor_index, x, y, width, height, language, mode
     );
 
-    let base64 = {
-        let guard = state.frozen_screenshots.lock().map_err(|e| e.to_string())?;
-        guard
-            .get(monitor_index)
-            .cloned()
-            .ok_or_else(|| format!("No frozen screenshot for monitor {}", monitor_index))?
-    };
+    let started = Instant::now();
+    let service = OcrService::from_state(&state)?;
+    let client = state.http_client.clone();
+    let image_bytes = crop_frozen_region(&state, monitor_index, x, y, width, height).await?;
 
-    let (base_url, api_key, model, extra) = {
-        let guard = state.settings.lock().map_err(|e| e.to_string())?;
-        let (b, k, m) = guard.ocr.resolved(&guard.base_url, &guard.api_key);
-        (b, k, m, guard.ocr.extra.clone())
-    };
+    info!(
+        "[OCR] 裁切完成, JPEG bytes={}, 开始 OCR 识别, engine={:?}",
+        image_bytes.len(),
+        service.engine
+    );
+
+    let result = service
+        .recognize(&client, &image_bytes, &language, mode)
+        .await
+        .map_err(|e| e.to_string());
+
+    match &result {
+        Ok(text) => info!(
+            "[OCR] capture_and_ocr 完成, 结果长度={}, 耗时 {}ms",
+            text.len(),
+            started.elapsed().as_millis()
+        ),
+        Err(e) => error!("[OCR] capture_and_ocr 失败: {}", e),
+    }
+    result
+}
+
+/// Structured capture + OCR: text blocks/lines with bounding boxes in crop
+/// pixel coordinates, reading order and per-block language. The tesseract
+/// engine also reports word boxes. `language` defaults to `"auto"`.
+#[tauri::command]
+pub async fn capture_and_ocr_structured(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    language: Option<String>,
+) -> Result<OcrLayout, String> {
+    let language = language.unwrap_or_else(|| "auto".to_string());
+    info!(
+        "[OCR] capture_and_ocr_structured 开始, monitor={}, region=({},{},{}x{}), language={}",
+        monitor_index, x, y, width, height, language
+    );
+
+    let service = OcrService::from_state(&state)?;
     let client = state.http_client.clone();
+    let image_bytes = crop_frozen_region(&state, monitor_index, x, y, width, height).await?;
 
-    // Crop region and get raw JPEG bytes in one step (no intermediate base64/PNG)
-    let image_bytes = tokio::task::spawn_blocking(move || {
-        crate::screenshot::capture_region_bytes(&base64, x, y, width, height)
-    })
-    .await
-    .map_err(|e| e.to_string())?
-    .map_err(|e| e.to_string())?;
+    let result = service
+        .recognize_structured(&client, &image_bytes, &language)
+        .await
+        .map_err(|e| e.to_string());
+
+    match &result {
+        Ok(layout) => info!(
+            "[OCR] capture_and_ocr_structured 完成, blocks={}",
+            layout.blocks.len()
+        ),
+        Err(e) => error!("[OCR] capture_and_ocr_structured 失败: {}", e),
+    }
+    result
+}
 
+/// One-shot region translation: the vision model transcribes the crop and
+/// translates it in a single request, instead of `capture_and_ocr` followed
+/// by `translate_text`. Selected by `ocr.translate_pipeline = "one_shot"`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn capture_and_translate(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    source_lang: String,
+    target_lang: String,
+) -> Result<VisionTranslation, String> {
     info!(
-        "[OCR] 裁切完成, JPEG bytes={}, 开始 OCR 识别",
-        image_bytes.len()
+        "[OCR] capture_and_translate 开始, monitor={}, region=({},{},{}x{}), {} → {}",
+        monitor_index, x, y, width, height, source_lang, target_lang
     );
 
-    let result = crate::ocr::recognize(
-        &client,
-        &image_bytes,
-        &language,
-        &base_url,
-        &api_key,
-        &model,
-        &extra,
-    )
-    .await
-    .map_err(|e| e.to_string());
+    let started = Instant::now();
+    let service = OcrService::from_state(&state)?;
+    let client = state.http_client.clone();
+    let image_bytes = crop_frozen_region(&state, monitor_index, x, y, width, height).await?;
+
+    let result = service
+        .recognize_and_translate(&client, &image_bytes, &source_lang, &target_lang)
+        .await
+        .map_err(|e| e.to_string());
 
     match &result {
-        Ok(text) => info!("[OCR] capture_and_ocr 完成, 结果长度={}", text.len()),
-        Err(e) => error!("[OCR] capture_and_ocr 失败: {}", e),
+        Ok(result) => {
+            // Compare with "capture_and_ocr 完成 ... 耗时" + "翻译完成" of the two-step pipeline.
+            info!(
+                "[OCR] capture_and_translate 完成, 原文长度={}, 译文长度={}, 一次请求总耗时 {}ms",
+                result.source.len(),
+                result.translation.len(),
+                started.elapsed().as_millis()
+            );
+            if !result.source.is_empty() {
+                state
+                    .translation_history
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .push(result.source.clone(), result.translation.clone());
+            }
+        }
+        Err(e) => error!("[OCR] capture_and_translate 失败: {}", e),
     }
     result
 }
+
+#[derive(Debug, Serialize)]
+pub struct OcrModeInfo {
+    pub mode: OcrMode,
+    /// Built-in output instruction, shown as the placeholder of the override.
+    pub default_prompt: &'static str,
+}
+
+/// List OCR output modes with their built-in prompts (for the settings UI).
+#[tauri::command]
+pub async fn list_ocr_modes() -> Result<Vec<OcrModeInfo>, String> {
+    Ok(OcrMode::ALL
+        .into_iter()
+        .map(|mode| OcrModeInfo {
+            mode,
+            default_prompt: mode.default_instruction(),
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreprocessPreview {
+    /// Base64 PNG exactly as it would be sent to the OCR engine.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub transform: PreprocessTransform,
+}
+
+/// Debug helper for tuning preprocessing: crop a region and return it after
+/// running `profile` (default: the active profile).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn preview_ocr_preprocess(
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    profile: Option<String>,
+) -> Result<PreprocessPreview, String> {
+    let profile = {
+        let guard = state.settings.lock().map_err(|e| e.to_string())?;
+        let name = profile.unwrap_or_else(|| guard.ocr.preprocess_profile.clone());
+        preprocess::find_profile(&guard.ocr.preprocess_profiles, name.trim())
+            .ok_or_else(|| format!("预处理 profile 不存在: {}", name))?
+    };
+    let image_bytes = crop_frozen_region(&state, monitor_index, x, y, width, height).await?;
+    let processed = preprocess::run(&image_bytes, &profile).await.map_err(|e| {
+        error!("[OCR] 预处理预览失败: {}", e);
+        e.to_string()
+    })?;
+    Ok(PreprocessPreview {
+        image: base64::engine::general_purpose::STANDARD.encode(&processed.png_bytes),
+        width: processed.width,
+        height: processed.height,
+        transform: processed.transform,
+    })
+}
+
+/// Built-in and custom preprocessing profiles (for the settings UI).
+#[tauri::command]
+pub async fn list_preprocess_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<PreprocessProfile>, String> {
+    let guard = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(preprocess::all_profiles(&guard.ocr.preprocess_profiles))
+}
+
+/// OCR cache hit/miss counters and sizes.
+#[tauri::command]
+pub async fn get_ocr_cache_stats(state: State<'_, AppState>) -> Result<OcrCacheStats, String> {
+    let cache = state.ocr_cache.clone();
+    tokio::task::spawn_blocking(move || cache.stats())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove all cached OCR results (memory and disk) and reset the counters.
+#[tauri::command]
+pub async fn clear_ocr_cache(state: State<'_, AppState>) -> Result<(), String> {
+    let cache = state.ocr_cache.clone();
+    tokio::task::spawn_blocking(move || cache.clear())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("[OCR] 清空缓存失败: {}", e);
+            e.to_string()
+        })
+}
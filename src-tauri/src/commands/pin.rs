@@ -0,0 +1,185 @@
+use crate::config::AppState;
+use crate::screenshot::pin::PinnedImage;
+use crate::screenshot::save::{self, SaveFormat};
+use image::{DynamicImage, RgbaImage};
+use log::{error, info};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{State, WebviewUrl, WebviewWindowBuilder};
+
+/// Label prefix of pin windows (`pin-1`, `pin-2`, ...).
+pub const PIN_LABEL_PREFIX: &str = "pin-";
+
+/// Source of pin window numbers; labels are never reused within a run.
+static PIN_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// What a pin window needs to display its image.
+#[derive(Debug, Serialize)]
+pub struct PinInfo {
+    pub image_url: String,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+pub(crate) fn pinned_image(state: &AppState, label: &str) -> Result<Arc<RgbaImage>, String> {
+    let guard = state.pins.lock().map_err(|e| e.to_string())?;
+    guard
+        .get(label)
+        .map(|pin| pin.image.clone())
+        .ok_or_else(|| format!("贴图不存在: {}", label))
+}
+
+/// Drop the image of a destroyed pin window.
+pub fn forget_pin(state: &AppState, label: &str) {
+    if let Ok(mut guard) = state.pins.lock() {
+        if guard.remove(label).is_some() {
+            info!("[Pin] 贴图已释放: {}, 剩余 {}", label, guard.len());
+        }
+    }
+}
+
+/// Pin an image (base64 PNG, usually the annotated selection) to the screen
+/// as a borderless, always-on-top window at the selection's position.
+/// `left` / `top` are CSS pixels inside the overlay of `monitor_index`.
+/// Returns the new window's label.
+#[tauri::command]
+pub async fn pin_image(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    left: f64,
+    top: f64,
+    image_base64: String,
+) -> Result<String, String> {
+    let monitor = {
+        let guard = state.frozen_monitors.lock().map_err(|e| e.to_string())?;
+        guard
+            .get(monitor_index)
+            .cloned()
+            .ok_or_else(|| format!("显示器不存在: {}", monitor_index))?
+    };
+    let image = tokio::task::spawn_blocking(move || -> Result<RgbaImage, String> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&image_base64)
+            .map_err(|e| format!("base64 decode failed: {}", e))?;
+        let img = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+        Ok(img.to_rgba8())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let scale = monitor.scale_factor;
+    let (width, height) = image.dimensions();
+    let label = format!(
+        "{}{}",
+        PIN_LABEL_PREFIX,
+        PIN_SEQ.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    {
+        let mut guard = state.pins.lock().map_err(|e| e.to_string())?;
+        guard.insert(
+            label.clone(),
+            PinnedImage {
+                image: Arc::new(image),
+                scale_factor: scale,
+            },
+        );
+    }
+
+    let x = monitor.x as f64 / scale + left;
+    let y = monitor.y as f64 / scale + top;
+    let logical_w = width as f64 / scale;
+    let logical_h = height as f64 / scale;
+    info!(
+        "[Pin] 创建贴图窗口: {}, pos=({:.0},{:.0}), logical={:.0}x{:.0}, image={}x{}",
+        label, x, y, logical_w, logical_h, width, height
+    );
+
+    let built = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("pin.html".into()))
+        .title("Pin")
+        .inner_size(logical_w, logical_h)
+        .position(x, y)
+        .decorations(false)
+        .resizable(false)
+        .transparent(true)
+        .shadow(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .accept_first_mouse(true)
+        .build();
+    if let Err(e) = built {
+        error!("[Pin] 贴图窗口创建失败: {}", e);
+        forget_pin(&state, &label);
+        return Err(e.to_string());
+    }
+    Ok(label)
+}
+
+/// Image URL and size for the pin window `label`.
+#[tauri::command]
+pub async fn get_pin(state: State<'_, AppState>, label: String) -> Result<PinInfo, String> {
+    let guard = state.pins.lock().map_err(|e| e.to_string())?;
+    let pin = guard
+        .get(&label)
+        .ok_or_else(|| format!("贴图不存在: {}", label))?;
+    Ok(PinInfo {
+        image_url: crate::protocol::pin_image_url(&label),
+        width: pin.image.width(),
+        height: pin.image.height(),
+        scale_factor: pin.scale_factor,
+    })
+}
+
+/// Copy a pinned image to the clipboard.
+#[tauri::command]
+pub async fn copy_pin(state: State<'_, AppState>, label: String) -> Result<(), String> {
+    let image = pinned_image(&state, &label)?;
+    tokio::task::spawn_blocking(move || {
+        let png = crate::screenshot::encode_png(&image).map_err(|e| e.to_string())?;
+        super::clipboard::write_clipboard_image(&png)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    info!("[Pin] 贴图已复制到剪贴板: {}", label);
+    Ok(())
+}
+
+/// Save a pinned image to `path`; the format follows the extension (PNG when
+/// it is not one of png / jpg / jpeg / webp).
+#[tauri::command]
+pub async fn save_pin(
+    state: State<'_, AppState>,
+    label: String,
+    path: String,
+) -> Result<(), String> {
+    let image = pinned_image(&state, &label)?;
+    let jpeg_quality = state
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .screenshot
+        .jpeg_quality;
+    let path_for_write = path.clone();
+    tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+        let path = Path::new(&path_for_write);
+        let format = SaveFormat::from_path(path).unwrap_or_default();
+        let bytes = save::encode_image(
+            &DynamicImage::ImageRgba8((*image).clone()),
+            format,
+            jpeg_quality,
+        )?;
+        crate::output::write_file(path, &bytes)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[Pin] 贴图保存失败: {}", e);
+        e.to_string()
+    })?;
+    info!("[Pin] 贴图已保存: {} -> {}", label, path);
+    Ok(())
+}
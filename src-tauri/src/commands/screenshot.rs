@@ -1,8 +1,44 @@
+use super::annotate::EditSource;
 use crate::config::AppState;
 use crate::config::MonitorInfo;
+use crate::render::annotate::{Annotation, FrozenEdit};
+use crate::screenshot::save::{self, SaveFormat, SavedScreenshot};
+use image::{DynamicImage, RgbaImage};
 use log::{error, info};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 use tauri::{Manager, State, WebviewUrl, WebviewWindowBuilder};
 
+fn monitor_info(mon: &tauri::Monitor) -> MonitorInfo {
+    let pos = mon.position();
+    let size = mon.size();
+    MonitorInfo {
+        name: mon.name().cloned().unwrap_or_default(),
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+        scale_factor: mon.scale_factor(),
+    }
+}
+
+/// Index of the monitor under the cursor, or 0 when it cannot be told.
+fn monitor_at_cursor(app: &tauri::AppHandle, monitors: &[MonitorInfo]) -> usize {
+    app.cursor_position()
+        .ok()
+        .and_then(|cursor| {
+            monitors.iter().position(|m| {
+                cursor.x >= m.x as f64
+                    && cursor.x < m.x as f64 + m.width as f64
+                    && cursor.y >= m.y as f64
+                    && cursor.y < m.y as f64 + m.height as f64
+            })
+        })
+        .unwrap_or(0)
+}
+
 /// Close all existing screenshot overlay windows (labels matching "screenshot-overlay-*").
 pub fn close_all_overlays(app: &tauri::AppHandle) {
     for win in app.webview_windows().values() {
@@ -31,7 +67,23 @@ pub async fn start_region_select(
     state: State<'_, AppState>,
     mode: String,
 ) -> Result<(), String> {
-    info!("[Screenshot] start_region_select, mode={}", mode);
+    open_overlays(&app, &state, mode, None).await
+}
+
+/// Body of `start_region_select`. With `edit`, the reopened capture is pasted
+/// into the screenshot of the monitor under the cursor before the overlays
+/// load it (see `open_annotation`).
+pub(crate) async fn open_overlays(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    mode: String,
+    edit: Option<EditSource>,
+) -> Result<(), String> {
+    info!(
+        "[Screenshot] start_region_select, mode={}, edit={}",
+        mode,
+        edit.is_some()
+    );
 
     // Guard: close any existing overlay windows
     let has_existing = app
@@ -40,7 +92,7 @@ pub async fn start_region_select(
         .any(|k| k.starts_with("screenshot-overlay"));
     if has_existing {
         info!("[Screenshot] 覆盖层窗口已存在，先关闭旧窗口");
-        close_all_overlays(&app);
+        close_all_overlays(app);
         tokio::time::sleep(std::time::Duration::from_millis(200)).await;
     }
 
@@ -83,29 +135,7 @@ pub async fn start_region_select(
         return Err("No monitors found".to_string());
     }
 
-    let mut monitor_infos: Vec<MonitorInfo> = Vec::new();
-    let mut logical_rects: Vec<(f64, f64, f64, f64)> = Vec::new();
-    for mon in &monitors {
-        let pos = mon.position();
-        let size = mon.size();
-        let scale = mon.scale_factor();
-        let name = mon.name().cloned().unwrap_or_default();
-        monitor_infos.push(MonitorInfo {
-            name,
-            x: pos.x,
-            y: pos.y,
-            width: size.width,
-            height: size.height,
-            scale_factor: scale,
-        });
-        // Logical coordinates for per-monitor capture
-        logical_rects.push((
-            pos.x as f64 / scale,
-            pos.y as f64 / scale,
-            size.width as f64 / scale,
-            size.height as f64 / scale,
-        ));
-    }
+    let monitor_infos: Vec<MonitorInfo> = monitors.iter().map(monitor_info).collect();
 
     info!("[Screenshot] 检测到 {} 个显示器", monitor_infos.len());
     for (i, m) in monitor_infos.iter().enumerate() {
@@ -117,17 +147,20 @@ pub async fn start_region_select(
 
     // 4. Capture each monitor individually (native resolution per monitor)
     info!("[Screenshot] 开始逐显示器截图...");
+    let capture_started = Instant::now();
+    let capture_monitors = monitor_infos.clone();
     let capture_result =
-        tokio::task::spawn_blocking(move || crate::screenshot::capture_monitors(&logical_rects))
+        tokio::task::spawn_blocking(move || crate::screenshot::capture_monitors(&capture_monitors))
             .await
             .map_err(|e| e.to_string())?;
 
-    let screenshots = match capture_result {
+    let mut screenshots = match capture_result {
         Ok(data) => {
-            info!("[Screenshot] 逐显示器截图完成, count={}", data.len());
-            for (i, s) in data.iter().enumerate() {
-                info!("[Screenshot] 显示器[{}] base64 size={}", i, s.len());
-            }
+            info!(
+                "[Screenshot] 逐显示器截图完成, count={}, 耗时 {}ms",
+                data.len(),
+                capture_started.elapsed().as_millis()
+            );
             data
         }
         Err(e) => {
@@ -136,10 +169,21 @@ pub async fn start_region_select(
         }
     };
 
+    let frozen_edit = match edit {
+        Some(edit) => Some(place_edit(
+            edit,
+            monitor_at_cursor(app, &monitor_infos),
+            &mut screenshots,
+        )?),
+        None => None,
+    };
+    let screenshots = screenshots.into_iter().map(Arc::new).collect::<Vec<_>>();
+
     // Store per-monitor screenshots
     {
         let mut guard = state.frozen_screenshots.lock().map_err(|e| e.to_string())?;
         *guard = screenshots;
+        state.frozen_generation.fetch_add(1, Ordering::AcqRel);
     }
     {
         let mut guard = state.frozen_mode.lock().map_err(|e| e.to_string())?;
@@ -151,6 +195,10 @@ pub async fn start_region_select(
         let mut guard = state.frozen_monitors.lock().map_err(|e| e.to_string())?;
         *guard = monitor_infos.clone();
     }
+    {
+        let mut guard = state.frozen_edit.lock().map_err(|e| e.to_string())?;
+        *guard = frozen_edit;
+    }
 
     // 5. Create overlay windows for each monitor
     for (i, mon) in monitors.iter().enumerate() {
@@ -167,8 +215,8 @@ pub async fn start_region_select(
         );
 
         let build_overlay = || {
-            WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("screenshot.html".into()))
-                .title("Screenshot")
+            WebviewWindowBuilder::new(app, &label, WebviewUrl::App("screenshot.html".into()))
+                .title(crate::screenshot::capture::OVERLAY_WINDOW_TITLE)
                 .inner_size(logical_w, logical_h)
                 .position(pos.x as f64 / scale, pos.y as f64 / scale)
                 .decorations(false)
@@ -200,7 +248,100 @@ pub async fn start_region_select(
     Ok(())
 }
 
-/// Get the frozen screenshot data for a specific monitor's overlay window.
+/// Paste a reopened capture into the middle of monitor `index`'s screenshot.
+fn place_edit(
+    edit: EditSource,
+    index: usize,
+    screenshots: &mut [RgbaImage],
+) -> Result<FrozenEdit, String> {
+    let screen = screenshots
+        .get_mut(index)
+        .ok_or_else(|| format!("显示器不存在: {}", index))?;
+    let (width, height) = edit.base.dimensions();
+    if width > screen.width() || height > screen.height() {
+        return Err(format!(
+            "图片 {}x{} 大于显示器 {}x{}，无法在覆盖层中编辑",
+            width,
+            height,
+            screen.width(),
+            screen.height()
+        ));
+    }
+    let x = (screen.width() - width) / 2;
+    let y = (screen.height() - height) / 2;
+    image::imageops::replace(screen, &edit.base, x as i64, y as i64);
+    info!(
+        "[Screenshot] 重新编辑: monitor={}, region=({},{},{}x{}), shapes={}",
+        index,
+        x,
+        y,
+        width,
+        height,
+        edit.annotation.shapes.len()
+    );
+    Ok(FrozenEdit {
+        monitor_index: index,
+        x,
+        y,
+        width,
+        height,
+        annotation: edit.annotation,
+        path: edit.path.to_string_lossy().into_owned(),
+    })
+}
+
+/// Source of delayed-capture countdown tokens (0 is reserved for "none").
+static COUNTDOWN_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Start region selection after a countdown, so hover menus and tooltips can
+/// be opened first. `delay_secs` defaults to `screenshot.capture_delay_secs`
+/// and is clamped to 1–10 s. Triggering again during a countdown cancels it.
+#[tauri::command]
+pub async fn start_delayed_region_select(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    mode: String,
+    delay_secs: Option<u8>,
+) -> Result<(), String> {
+    if state.capture_countdown.swap(0, Ordering::AcqRel) != 0 {
+        info!("[Screenshot] 延时截图已取消");
+        crate::tray::set_countdown(&app, None);
+        return Ok(());
+    }
+
+    let delay = match delay_secs {
+        Some(secs) => secs,
+        None => {
+            let guard = state.settings.lock().map_err(|e| e.to_string())?;
+            guard.screenshot.capture_delay_secs
+        }
+    }
+    .clamp(1, 10) as u64;
+    let token = COUNTDOWN_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+    state.capture_countdown.store(token, Ordering::Release);
+    info!("[Screenshot] 延时截图开始, mode={}, delay={}s", mode, delay);
+
+    for remaining in (1..=delay).rev() {
+        if state.capture_countdown.load(Ordering::Acquire) != token {
+            return Ok(());
+        }
+        crate::tray::set_countdown(&app, Some(remaining));
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    if state
+        .capture_countdown
+        .compare_exchange(token, 0, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return Ok(());
+    }
+    crate::tray::set_countdown(&app, None);
+    info!("[Screenshot] 延时截图倒计时结束");
+    start_region_select(app, state, mode).await
+}
+
+/// Get the frozen screenshot metadata for a specific monitor's overlay window.
+/// The image itself is loaded from `image_url` (see `protocol`).
 #[tauri::command]
 pub async fn get_frozen_screenshot(
     state: State<'_, AppState>,
@@ -210,13 +351,11 @@ pub async fn get_frozen_screenshot(
         "[Screenshot] get_frozen_screenshot 请求, monitor_index={}",
         monitor_index
     );
-    let image = {
-        let guard = state.frozen_screenshots.lock().map_err(|e| e.to_string())?;
-        guard
-            .get(monitor_index)
-            .cloned()
-            .ok_or_else(|| format!("No frozen screenshot for monitor {}", monitor_index))?
-    };
+    let frozen = super::ocr::frozen_screenshot(&state, monitor_index)?;
+    let image_url = crate::protocol::frozen_screenshot_url(
+        monitor_index,
+        state.frozen_generation.load(Ordering::Acquire),
+    );
     let mode = {
         let guard = state.frozen_mode.lock().map_err(|e| e.to_string())?;
         guard.clone()
@@ -232,14 +371,24 @@ pub async fn get_frozen_screenshot(
         let guard = state.frozen_monitors.lock().map_err(|e| e.to_string())?;
         guard.clone()
     };
+    let edit = {
+        let guard = state.frozen_edit.lock().map_err(|e| e.to_string())?;
+        guard
+            .as_ref()
+            .filter(|edit| edit.monitor_index == monitor_index)
+            .cloned()
+    };
 
-    info!("[Screenshot] get_frozen_screenshot 返回, monitor_index={}, mode={}, image size={}, monitors={}", monitor_index, mode, image.len(), monitors.len());
+    info!("[Screenshot] get_frozen_screenshot 返回, monitor_index={}, mode={}, image={}x{}, url={}, monitors={}", monitor_index, mode, frozen.width(), frozen.height(), image_url, monitors.len());
 
     Ok(serde_json::json!({
-        "image": image,
+        "image_url": image_url,
+        "image_width": frozen.width(),
+        "image_height": frozen.height(),
         "mode": mode,
         "window_rects": window_rects,
         "monitors": monitors,
+        "edit": edit,
     }))
 }
 
@@ -257,16 +406,10 @@ pub async fn capture_region(
         "[Screenshot] capture_region, monitor_index={}, region=({},{},{}x{})",
         monitor_index, x, y, width, height
     );
-    let base64 = {
-        let guard = state.frozen_screenshots.lock().map_err(|e| e.to_string())?;
-        guard
-            .get(monitor_index)
-            .cloned()
-            .ok_or_else(|| format!("No frozen screenshot for monitor {}", monitor_index))?
-    };
+    let frozen = super::ocr::frozen_screenshot(&state, monitor_index)?;
 
     let result = tokio::task::spawn_blocking(move || {
-        crate::screenshot::capture_region_from_full(&base64, x, y, width, height)
+        crate::screenshot::capture_region_from_full(&frozen, x, y, width, height)
     })
     .await
     .map_err(|e| e.to_string())?
@@ -280,3 +423,388 @@ pub async fn capture_region(
     }
     result
 }
+
+/// Image handed to `auto_save`; decoded / cropped on the blocking pool.
+enum SaveSource {
+    /// Crop of a frozen monitor screenshot.
+    Region {
+        full: Arc<RgbaImage>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// Crop of a frozen monitor screenshot with annotations drawn over it;
+    /// also writes the sidecar.
+    Annotated {
+        full: Arc<RgbaImage>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        annotation: Annotation,
+    },
+    /// A whole capture (monitor / window).
+    Full(Arc<RgbaImage>),
+}
+
+/// Write a screenshot through the auto-save pipeline; `None` when
+/// `screenshot.auto_save` is off.
+async fn auto_save(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    source: SaveSource,
+    monitor_index: usize,
+    mode: &str,
+) -> Result<Option<SavedScreenshot>, String> {
+    let config = {
+        let guard = state.settings.lock().map_err(|e| e.to_string())?;
+        guard.screenshot.clone()
+    };
+    if !config.auto_save {
+        return Ok(None);
+    }
+
+    let dir = if config.save_dir.trim().is_empty() {
+        app.path()
+            .picture_dir()
+            .map_err(|e| e.to_string())?
+            .join(save::DEFAULT_SAVE_DIR_NAME)
+    } else {
+        PathBuf::from(config.save_dir.trim())
+    };
+    let counter = state
+        .recent_saves
+        .lock()
+        .map_err(|e| e.to_string())?
+        .next_counter();
+    let now = chrono::Local::now();
+    let stem = save::render_filename(
+        &config.filename_template,
+        &save::FilenameContext {
+            now,
+            monitor_index,
+            mode,
+            counter,
+        },
+    );
+    info!(
+        "[Screenshot] 自动保存, dir={}, stem={}, format={:?}",
+        dir.display(),
+        stem,
+        config.format
+    );
+
+    let started = Instant::now();
+    let saved = tokio::task::spawn_blocking(move || -> anyhow::Result<SavedScreenshot> {
+        let path = save::resolve_path(&dir, &stem, config.format, config.collision)?;
+        let img = match source {
+            SaveSource::Region {
+                full,
+                x,
+                y,
+                width,
+                height,
+            } => crate::screenshot::crop_region(&full, x, y, width, height),
+            SaveSource::Annotated {
+                full,
+                x,
+                y,
+                width,
+                height,
+                annotation,
+            } => {
+                let base = crate::screenshot::crop_region(&full, x, y, width, height).to_rgba8();
+                let written = super::annotate::write_annotated(
+                    &path,
+                    &base,
+                    &annotation,
+                    config.format,
+                    config.jpeg_quality,
+                )?;
+                return Ok(SavedScreenshot {
+                    path: path.to_string_lossy().into_owned(),
+                    format: config.format,
+                    width: written.width,
+                    height: written.height,
+                    bytes: written.bytes,
+                    saved_at: now.timestamp_millis(),
+                    annotations: Some(written.sidecar.to_string_lossy().into_owned()),
+                });
+            }
+            SaveSource::Full(full) => DynamicImage::ImageRgba8((*full).clone()),
+        };
+        let bytes = save::encode_image(&img, config.format, config.jpeg_quality)?;
+        crate::output::write_file(&path, &bytes)?;
+        Ok(SavedScreenshot {
+            path: path.to_string_lossy().into_owned(),
+            format: config.format,
+            width: img.width(),
+            height: img.height(),
+            bytes: bytes.len(),
+            saved_at: now.timestamp_millis(),
+            annotations: None,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[Screenshot] 自动保存失败: {}", e);
+        e.to_string()
+    })?;
+
+    info!(
+        "[Screenshot] 截图已自动保存: {}, size={}, 耗时 {}ms",
+        saved.path,
+        saved.bytes,
+        started.elapsed().as_millis()
+    );
+    state
+        .recent_saves
+        .lock()
+        .map_err(|e| e.to_string())?
+        .push(saved.clone());
+    Ok(Some(saved))
+}
+
+/// Write a region screenshot to the configured directory when
+/// `screenshot.auto_save` is on; returns `None` when it is off.
+/// With `annotation`, the shapes are rendered over the crop and a sidecar is
+/// written next to the image. A capture reopened with `open_annotation` is
+/// written back to its own file instead, whether auto-save is on or not.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_screenshot(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    mode: String,
+    annotation: Option<Annotation>,
+) -> Result<Option<SavedScreenshot>, String> {
+    if let Some(annotation) = &annotation {
+        let edit = {
+            let mut guard = state.frozen_edit.lock().map_err(|e| e.to_string())?;
+            match guard.as_ref() {
+                Some(edit) if edit.monitor_index == monitor_index => {
+                    guard.take().map(|edit| edit.path)
+                }
+                _ => None,
+            }
+        };
+        if let Some(path) = edit {
+            let full = super::ocr::frozen_screenshot(&state, monitor_index)?;
+            let saved =
+                save_edit(&state, full, x, y, width, height, annotation.clone(), path).await?;
+            return Ok(Some(saved));
+        }
+    }
+
+    let auto_save_enabled = {
+        let guard = state.settings.lock().map_err(|e| e.to_string())?;
+        guard.screenshot.auto_save
+    };
+    if !auto_save_enabled {
+        return Ok(None);
+    }
+    let source = match annotation {
+        Some(annotation) => SaveSource::Annotated {
+            full: super::ocr::frozen_screenshot(&state, monitor_index)?,
+            x,
+            y,
+            width,
+            height,
+            annotation,
+        },
+        None => SaveSource::Region {
+            full: super::ocr::frozen_screenshot(&state, monitor_index)?,
+            x,
+            y,
+            width,
+            height,
+        },
+    };
+    auto_save(&app, &state, source, monitor_index, &mode).await
+}
+
+/// Write an edited, reopened capture back over its original image.
+#[allow(clippy::too_many_arguments)]
+async fn save_edit(
+    state: &AppState,
+    full: Arc<RgbaImage>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    annotation: Annotation,
+    path: String,
+) -> Result<SavedScreenshot, String> {
+    let jpeg_quality = {
+        let guard = state.settings.lock().map_err(|e| e.to_string())?;
+        guard.screenshot.jpeg_quality
+    };
+    let target = PathBuf::from(&path);
+    let format = SaveFormat::from_path(&target).unwrap_or_default();
+    let written = tokio::task::spawn_blocking(move || {
+        let base = crate::screenshot::crop_region(&full, x, y, width, height).to_rgba8();
+        super::annotate::write_annotated(&target, &base, &annotation, format, jpeg_quality)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("[Screenshot] 标注截图写回失败: {}", e);
+        e.to_string()
+    })?;
+    info!("[Screenshot] 标注截图已写回: {}", path);
+
+    let saved = SavedScreenshot {
+        path,
+        format,
+        width: written.width,
+        height: written.height,
+        bytes: written.bytes,
+        saved_at: chrono::Local::now().timestamp_millis(),
+        annotations: Some(written.sidecar.to_string_lossy().into_owned()),
+    };
+    state
+        .recent_saves
+        .lock()
+        .map_err(|e| e.to_string())?
+        .push(saved.clone());
+    Ok(saved)
+}
+
+/// Result of a capture that skips the overlay.
+#[derive(serde::Serialize)]
+pub struct DirectCapture {
+    pub width: u32,
+    pub height: u32,
+    /// False when the clipboard is unavailable but the file was saved.
+    pub copied: bool,
+    pub saved: Option<SavedScreenshot>,
+}
+
+/// Copy a capture to the clipboard and run it through auto-save, like a
+/// region screenshot. Fails only if neither succeeded.
+pub(crate) async fn deliver_capture(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    img: Arc<RgbaImage>,
+    monitor_index: usize,
+    mode: &str,
+) -> Result<DirectCapture, String> {
+    let (width, height) = img.dimensions();
+    let for_clipboard = img.clone();
+    let copy_result = tokio::task::spawn_blocking(move || {
+        let png = crate::screenshot::encode_png(&for_clipboard).map_err(|e| e.to_string())?;
+        super::clipboard::write_clipboard_image(&png)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let saved = auto_save(app, state, SaveSource::Full(img), monitor_index, mode).await?;
+
+    let copied = copy_result.is_ok();
+    match copy_result {
+        Ok(()) => info!("[Screenshot] {} 截图已复制到剪贴板", mode),
+        Err(e) if saved.is_some() => {
+            error!("[Screenshot] {} 截图复制失败（已保存到文件）: {}", mode, e)
+        }
+        Err(e) => {
+            error!("[Screenshot] {} 截图复制失败: {}", mode, e);
+            return Err(e);
+        }
+    }
+    Ok(DirectCapture {
+        width,
+        height,
+        copied,
+        saved,
+    })
+}
+
+/// Screenshots auto-saved in this session, newest first.
+#[tauri::command]
+pub async fn get_recent_saves(state: State<'_, AppState>) -> Result<Vec<SavedScreenshot>, String> {
+    let guard = state.recent_saves.lock().map_err(|e| e.to_string())?;
+    Ok(guard.list())
+}
+
+/// Forget the recent-saves list (files are kept).
+#[tauri::command]
+pub async fn clear_recent_saves(state: State<'_, AppState>) -> Result<(), String> {
+    let mut guard = state.recent_saves.lock().map_err(|e| e.to_string())?;
+    guard.clear();
+    info!("[Screenshot] 最近保存列表已清空");
+    Ok(())
+}
+
+/// Capture a whole monitor straight to the clipboard / auto-save, without
+/// the overlay. `monitor` indexes `available_monitors()`; `None` = the
+/// monitor under the cursor.
+#[tauri::command]
+pub async fn capture_fullscreen(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor: Option<usize>,
+) -> Result<DirectCapture, String> {
+    let monitors: Vec<MonitorInfo> = app
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(monitor_info)
+        .collect();
+    if monitors.is_empty() {
+        return Err("No monitors found".to_string());
+    }
+    let index = match monitor {
+        Some(index) if index < monitors.len() => index,
+        Some(index) => return Err(format!("显示器不存在: {}", index)),
+        None => monitor_at_cursor(&app, &monitors),
+    };
+    info!(
+        "[Screenshot] capture_fullscreen, monitor={}, name={}",
+        index, monitors[index].name
+    );
+
+    let target = [monitors[index].clone()];
+    let started = Instant::now();
+    let img = tokio::task::spawn_blocking(move || crate::screenshot::capture_monitors(&target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("[Screenshot] 全屏截图失败: {}", e);
+            e.to_string()
+        })?
+        .pop()
+        .ok_or_else(|| "截图结果为空".to_string())?;
+    info!(
+        "[Screenshot] 全屏截图完成, size={}x{}, 耗时 {}ms",
+        img.width(),
+        img.height(),
+        started.elapsed().as_millis()
+    );
+    deliver_capture(&app, &state, Arc::new(img), index, "fullscreen").await
+}
+
+/// Capture one window straight to the clipboard / auto-save, without the
+/// overlay. `window_id` is the native window id; `None` = the active window.
+#[tauri::command]
+pub async fn capture_window(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    window_id: Option<u32>,
+) -> Result<DirectCapture, String> {
+    info!("[Screenshot] capture_window, window_id={:?}", window_id);
+    let img =
+        tokio::task::spawn_blocking(move || crate::screenshot::window::capture_window(window_id))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| {
+                error!("[Screenshot] 窗口截图失败: {}", e);
+                e.to_string()
+            })?;
+    deliver_capture(&app, &state, Arc::new(img), 0, "window").await
+}
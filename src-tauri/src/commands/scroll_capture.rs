@@ -0,0 +1,170 @@
+use super::screenshot::{deliver_capture, DirectCapture};
+use crate::config::AppState;
+use crate::screenshot::scroll::{PushOutcome, ScrollStitcher};
+use image::RgbaImage;
+use log::{error, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// Source of scrolling-capture tokens (0 is reserved for "none").
+static SCROLL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Time between two captures of the region.
+const FRAME_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Wait before the first live frame so the overlay windows are gone.
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Stop on its own when nothing new has been appended for this long.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Scrolling capture: after region selection, capture the region repeatedly
+/// while the user scrolls and stitch the frames into one tall image.
+///
+/// Runs until `stop_scroll_capture`, the height limit, or `IDLE_TIMEOUT`,
+/// then copies / auto-saves the result like a full-screen capture and keeps
+/// it for `ocr_scroll_capture`. The region is in image pixels of the frozen
+/// screenshot of `monitor_index`, which is also the first frame.
+#[tauri::command]
+pub async fn start_scroll_capture(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<DirectCapture, String> {
+    let monitor = {
+        let guard = state.frozen_monitors.lock().map_err(|e| e.to_string())?;
+        guard
+            .get(monitor_index)
+            .cloned()
+            .ok_or_else(|| format!("显示器不存在: {}", monitor_index))?
+    };
+    let frozen = super::ocr::frozen_screenshot(&state, monitor_index)?;
+    let first = crate::screenshot::crop_region(&frozen, x, y, width, height).to_rgba8();
+    // Same clamping as `crop_region`, reused for every live frame.
+    let crop_x = x.min(frozen.width().saturating_sub(1));
+    let crop_y = y.min(frozen.height().saturating_sub(1));
+    let (crop_width, crop_height) = first.dimensions();
+    drop(frozen);
+
+    let token = SCROLL_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+    state.scroll_capture.store(token, Ordering::Release);
+    info!(
+        "[ScrollCapture] 开始, monitor={}, region=({},{},{}x{})",
+        monitor_index, crop_x, crop_y, crop_width, crop_height
+    );
+
+    let mut stitcher = ScrollStitcher::new(first);
+    crate::tray::set_scroll_progress(&app, Some(stitcher.height()));
+    tokio::time::sleep(SETTLE_DELAY).await;
+
+    let started = Instant::now();
+    let mut last_change = Instant::now();
+    loop {
+        if state.scroll_capture.load(Ordering::Acquire) != token {
+            info!("[ScrollCapture] 已停止");
+            break;
+        }
+        if last_change.elapsed() >= IDLE_TIMEOUT {
+            info!(
+                "[ScrollCapture] {}s 内没有新内容，自动结束",
+                IDLE_TIMEOUT.as_secs()
+            );
+            break;
+        }
+
+        let target = [monitor.clone()];
+        let (returned, outcome) = tokio::task::spawn_blocking(move || {
+            let outcome = crate::screenshot::capture_monitors(&target).and_then(|mut images| {
+                let full = images
+                    .pop()
+                    .ok_or_else(|| anyhow::anyhow!("截图结果为空"))?;
+                let frame =
+                    image::imageops::crop_imm(&full, crop_x, crop_y, crop_width, crop_height)
+                        .to_image();
+                Ok(stitcher.push(frame))
+            });
+            (stitcher, outcome)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        stitcher = returned;
+
+        match outcome {
+            Ok(PushOutcome::Appended(rows)) => {
+                last_change = Instant::now();
+                info!(
+                    "[ScrollCapture] 拼接 {} 行, 帧数={}, 总高度={}",
+                    rows,
+                    stitcher.frames(),
+                    stitcher.height()
+                );
+                crate::tray::set_scroll_progress(&app, Some(stitcher.height()));
+            }
+            Ok(PushOutcome::Unchanged) => {}
+            Ok(PushOutcome::NoMatch) => {
+                warn!("[ScrollCapture] 与上一帧没有重叠（回滚或跳转过快），已丢弃");
+            }
+            Ok(PushOutcome::LimitReached) => {
+                info!("[ScrollCapture] 已达到高度上限，自动结束");
+                break;
+            }
+            Err(e) => {
+                error!("[ScrollCapture] 截图失败，结束: {}", e);
+                break;
+            }
+        }
+        tokio::time::sleep(FRAME_INTERVAL).await;
+    }
+
+    // A newer session may have taken over; only clear our own token.
+    // `stop_scroll_capture` restores the tray itself.
+    if state
+        .scroll_capture
+        .compare_exchange(token, 0, Ordering::AcqRel, Ordering::Acquire)
+        .is_ok()
+    {
+        crate::tray::set_scroll_progress(&app, None);
+    }
+
+    let frames = stitcher.frames();
+    let img = Arc::new(stitcher.finish());
+    info!(
+        "[ScrollCapture] 完成, 帧数={}, size={}x{}, 耗时 {}ms",
+        frames,
+        img.width(),
+        img.height(),
+        started.elapsed().as_millis()
+    );
+    {
+        let mut guard = state.scroll_result.lock().map_err(|e| e.to_string())?;
+        *guard = Some(img.clone());
+    }
+    deliver_capture(&app, &state, img, monitor_index, "scroll").await
+}
+
+/// Stop the running scrolling capture; its `start_scroll_capture` call then
+/// returns the stitched result. Returns whether one was running.
+#[tauri::command]
+pub async fn stop_scroll_capture(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let running = state.scroll_capture.swap(0, Ordering::AcqRel) != 0;
+    if running {
+        info!("[ScrollCapture] 收到停止请求");
+        crate::tray::set_scroll_progress(&app, None);
+    }
+    Ok(running)
+}
+
+/// Last stitched scrolling capture, if any.
+pub(crate) fn scroll_result(state: &AppState) -> Result<Arc<RgbaImage>, String> {
+    let guard = state.scroll_result.lock().map_err(|e| e.to_string())?;
+    guard.clone().ok_or_else(|| "没有长截图结果".to_string())
+}
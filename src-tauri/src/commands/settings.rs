@@ -1,5 +1,6 @@
 use crate::config::{AppState, Settings};
 use crate::hotkey;
+use crate::tray;
 use log::{error, info};
 use tauri::State;
 
@@ -10,7 +11,7 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String
     let settings = state.settings.lock().map_err(|e| e.to_string())?;
     info!(
         "[Settings] 返回配置, translation.model={}, ocr.model={}",
-        settings.translation.model, settings.ocr.model
+        settings.translation.model, settings.ocr.service.model
     );
     Ok(settings.clone())
 }
@@ -24,7 +25,7 @@ pub async fn save_settings(
 ) -> Result<(), String> {
     info!(
         "[Settings] save_settings, translation.model={}, ocr.model={}, tts.model={}",
-        settings.translation.model, settings.ocr.model, settings.tts.model
+        settings.translation.model, settings.ocr.service.model, settings.tts.model
     );
     // Update in-memory state
     {
@@ -48,5 +49,7 @@ pub async fn save_settings(
     info!("[Settings] 配置保存成功");
     // 应用新快捷键（立即生效）
     hotkey::reload_hotkeys(&app);
+    // 托盘菜单中的快捷键标注与自定义文本动作随之更新
+    tray::refresh_tray_menu(&app);
     Ok(())
 }
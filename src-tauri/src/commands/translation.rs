@@ -1,21 +1,34 @@
 use crate::config::AppState;
-use crate::translation::OpenAiCompatProvider;
+use crate::translation::{OpenAiCompatProvider, TranslationContext};
 use log::{error, info};
 use tauri::State;
 
 /// Translate text using the configured translation service.
+///
+/// Optional disambiguation hints:
+/// - `context`: surrounding text (neighbouring OCR text, source window title...)
+/// - `domain`: domain hint such as "finance" or "software UI"
+/// - `history_turns`: include the last N translations of this session
+///
+/// Hints are injected into the prompt as non-translatable reference material.
 #[tauri::command]
 pub async fn translate_text(
     state: State<'_, AppState>,
     text: String,
     source_lang: String,
     target_lang: String,
+    context: Option<String>,
+    domain: Option<String>,
+    history_turns: Option<usize>,
 ) -> Result<String, String> {
     info!(
-        "[Translation] translate_text 开始, {} → {}, 文本长度={}",
+        "[Translation] translate_text 开始, {} → {}, 文本长度={}, context长度={}, domain={:?}, history_turns={:?}",
         source_lang,
         target_lang,
-        text.len()
+        text.len(),
+        context.as_deref().map_or(0, str::len),
+        domain,
+        history_turns
     );
     let (base_url, api_key, model, extra) = {
         let settings = state.settings.lock().map_err(|e| e.to_string())?;
@@ -24,8 +37,22 @@ pub async fn translate_text(
             .resolved(&settings.base_url, &settings.api_key);
         (b, k, m, settings.translation.extra.clone())
     };
+    let history = match history_turns {
+        Some(n) if n > 0 => state
+            .translation_history
+            .lock()
+            .map_err(|e| e.to_string())?
+            .recent(n),
+        _ => Vec::new(),
+    };
+    let translation_context = TranslationContext {
+        domain,
+        surrounding: context,
+        history,
+    };
     let client = state.http_client.clone();
     info!("[Translation] 使用 model={}, base_url={}", model, base_url);
+    let started = std::time::Instant::now();
 
     let provider = OpenAiCompatProvider::new(client);
     let result = provider
@@ -37,11 +64,23 @@ pub async fn translate_text(
             &api_key,
             &model,
             &extra,
+            &translation_context,
         )
         .await
         .map_err(|e| e.to_string());
     match &result {
-        Ok(translated) => info!("[Translation] 翻译完成, 结果长度={}", translated.len()),
+        Ok(translated) => {
+            info!(
+                "[Translation] 翻译完成, 结果长度={}, 耗时 {}ms",
+                translated.len(),
+                started.elapsed().as_millis()
+            );
+            state
+                .translation_history
+                .lock()
+                .map_err(|e| e.to_string())?
+                .push(text, translated.clone());
+        }
         Err(e) => error!("[Translation] 翻译失败: {}", e),
     }
     result
@@ -1,3 +1,3 @@
 pub mod settings;
 
-pub use settings::{merge_extra, AppState, HotkeyConfig, MonitorInfo, Settings};
+pub use settings::{merge_extra, AppState, HotkeyConfig, MonitorInfo, OcrEngine, Settings};
@@ -1,8 +1,16 @@
+use crate::actions::TextAction;
+use crate::jobs::JobRegistry;
+use crate::ocr::{OcrCache, OcrMode, OcrTiling, PreprocessProfile};
+use crate::render::annotate::FrozenEdit;
+use crate::screenshot::pin::PinnedImage;
+use crate::screenshot::save::{CollisionPolicy, SaveFormat, SavedScreenshot};
+use image::RgbaImage;
 use log::warn;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, VecDeque};
-use std::sync::Mutex;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ExtraProvider {
@@ -84,6 +92,132 @@ impl ServiceConfig {
     }
 }
 
+/// OCR backend. `Vision` sends the image to the configured chat model;
+/// `Tesseract` runs the local `tesseract` CLI fully offline.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrEngine {
+    #[default]
+    Vision,
+    Tesseract,
+}
+
+/// Region translation pipeline.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslatePipeline {
+    /// `capture_and_ocr` then `translate_text` with the translation model.
+    #[default]
+    TwoStep,
+    /// `capture_and_translate`: one vision request transcribes and translates.
+    OneShot,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OcrConfig {
+    /// Vision model settings, stored flat so existing `ocr` entries still load.
+    #[serde(flatten)]
+    pub service: ServiceConfig,
+    #[serde(default)]
+    pub engine: OcrEngine,
+    /// Path to the tesseract executable; empty = `tesseract` on PATH.
+    #[serde(default)]
+    pub tesseract_path: String,
+    /// Per-mode prompt overrides replacing the built-in output instruction.
+    /// Missing or empty entries use the built-in prompt.
+    #[serde(default)]
+    pub prompts: BTreeMap<OcrMode, String>,
+    /// Name of the preprocessing profile applied before recognition;
+    /// empty = send the crop as is.
+    #[serde(default)]
+    pub preprocess_profile: String,
+    /// User-defined preprocessing profiles (built-ins are not stored here).
+    #[serde(default)]
+    pub preprocess_profiles: Vec<PreprocessProfile>,
+    /// Split large crops into tiles recognized at native resolution.
+    #[serde(default)]
+    pub tiling: OcrTiling,
+    /// How region translation combines OCR and translation.
+    #[serde(default)]
+    pub translate_pipeline: TranslatePipeline,
+}
+
+impl OcrConfig {
+    fn vision(model: &str, extra: &str) -> Self {
+        Self {
+            service: ServiceConfig::with_model_and_extra(model, extra),
+            engine: OcrEngine::Vision,
+            tesseract_path: String::new(),
+            prompts: BTreeMap::new(),
+            preprocess_profile: String::new(),
+            preprocess_profiles: Vec::new(),
+            tiling: OcrTiling::Auto,
+            translate_pipeline: TranslatePipeline::TwoStep,
+        }
+    }
+}
+
+/// Screenshot output options. With `auto_save` on, every region screenshot
+/// is also written to `save_dir` besides going to the clipboard.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScreenshotConfig {
+    #[serde(default)]
+    pub auto_save: bool,
+    /// Empty = `<Pictures>/DH-TransShot`.
+    #[serde(default)]
+    pub save_dir: String,
+    /// See `screenshot::save::render_filename` for placeholders.
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    #[serde(default)]
+    pub format: SaveFormat,
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    #[serde(default)]
+    pub collision: CollisionPolicy,
+    /// Countdown (1–10 s) of delayed capture before the screen is frozen.
+    #[serde(default = "default_capture_delay_secs")]
+    pub capture_delay_secs: u8,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            auto_save: false,
+            save_dir: String::new(),
+            filename_template: default_filename_template(),
+            format: SaveFormat::Png,
+            jpeg_quality: default_jpeg_quality(),
+            collision: CollisionPolicy::Suffix,
+            capture_delay_secs: default_capture_delay_secs(),
+        }
+    }
+}
+
+fn default_filename_template() -> String {
+    crate::screenshot::save::DEFAULT_FILENAME_TEMPLATE.to_string()
+}
+
+fn default_jpeg_quality() -> u8 {
+    90
+}
+
+fn default_capture_delay_secs() -> u8 {
+    3
+}
+
+/// Context the main window sends with manual translations (see
+/// `translation::TranslationContext`); empty / 0 sends none.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TranslationContextConfig {
+    /// Domain hint, e.g. "finance", "software UI".
+    #[serde(default)]
+    pub domain: String,
+    /// Previous translations of this session included as context.
+    #[serde(default)]
+    pub history_turns: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HotkeyConfig {
     #[serde(default = "default_hotkey_screenshot")]
@@ -92,6 +226,27 @@ pub struct HotkeyConfig {
     pub ocr_translate: String,
     #[serde(default = "default_hotkey_clipboard_translate")]
     pub clipboard_translate: String,
+    /// Text action id → shortcut. Actions without an entry have no hotkey.
+    #[serde(default)]
+    pub actions: BTreeMap<String, String>,
+    /// Region OCR in a given output mode (no translation). Empty = unbound.
+    #[serde(default)]
+    pub ocr_modes: BTreeMap<OcrMode, String>,
+    /// Region screenshot after the `screenshot.capture_delay_secs` countdown.
+    /// Empty = unbound.
+    #[serde(default)]
+    pub delayed_screenshot: String,
+    /// Full-screen capture of the monitor under the cursor, no overlay.
+    /// Empty = unbound.
+    #[serde(default)]
+    pub fullscreen_screenshot: String,
+    /// Capture of the active window, no overlay. Empty = unbound.
+    #[serde(default)]
+    pub window_screenshot: String,
+    /// Scrolling capture of a selected region; pressing it again while
+    /// capturing stops. Empty = unbound.
+    #[serde(default)]
+    pub scroll_screenshot: String,
 }
 
 impl Default for HotkeyConfig {
@@ -100,6 +255,12 @@ impl Default for HotkeyConfig {
             screenshot: default_hotkey_screenshot(),
             ocr_translate: default_hotkey_ocr_translate(),
             clipboard_translate: default_hotkey_clipboard_translate(),
+            actions: BTreeMap::new(),
+            ocr_modes: BTreeMap::new(),
+            delayed_screenshot: String::new(),
+            fullscreen_screenshot: String::new(),
+            window_screenshot: String::new(),
+            scroll_screenshot: String::new(),
         }
     }
 }
@@ -123,10 +284,17 @@ pub struct Settings {
     #[serde(default = "default_api_key")]
     pub api_key: String,
     pub translation: ServiceConfig,
-    pub ocr: ServiceConfig,
+    #[serde(default)]
+    pub translation_context: TranslationContextConfig,
+    pub ocr: OcrConfig,
     pub tts: ServiceConfig,
     #[serde(default)]
     pub hotkeys: HotkeyConfig,
+    /// User-defined text actions (built-in actions are not stored here).
+    #[serde(default)]
+    pub actions: Vec<TextAction>,
+    #[serde(default)]
+    pub screenshot: ScreenshotConfig,
 }
 
 fn default_base_url() -> String {
@@ -151,7 +319,8 @@ impl Default for Settings {
   "enable_thinking": false
 }"#,
             ),
-            ocr: ServiceConfig::with_model_and_extra(
+            translation_context: TranslationContextConfig::default(),
+            ocr: OcrConfig::vision(
                 "Qwen/Qwen3.5-4B",
                 r#"{
   "temperature": 0.1,
@@ -171,6 +340,8 @@ impl Default for Settings {
 }"#,
             ),
             hotkeys: HotkeyConfig::default(),
+            actions: Vec::new(),
+            screenshot: ScreenshotConfig::default(),
         }
     }
 }
@@ -213,11 +384,31 @@ pub struct MonitorInfo {
 
 pub struct AppState {
     pub settings: Mutex<Settings>,
-    pub frozen_screenshots: Mutex<Vec<String>>,
+    /// Decoded per-monitor screenshots of the current region selection;
+    /// encoded only when a consumer needs bytes.
+    pub frozen_screenshots: Mutex<Vec<Arc<RgbaImage>>>,
+    /// Incremented per capture session; part of the frozen screenshot URLs.
+    pub frozen_generation: AtomicU64,
     pub frozen_mode: Mutex<String>,
     pub frozen_window_rects: Mutex<serde_json::Value>,
     pub frozen_monitors: Mutex<Vec<MonitorInfo>>,
+    /// Token of the running delayed-capture countdown; 0 = none.
+    pub capture_countdown: AtomicU64,
+    /// Token of the running scrolling capture; 0 = none.
+    pub scroll_capture: AtomicU64,
+    /// Last stitched scrolling capture, kept for `ocr_scroll_capture`.
+    pub scroll_result: Mutex<Option<Arc<RgbaImage>>>,
+    /// Reopened annotated capture placed in the current frozen screenshots;
+    /// consumed by `save_screenshot`.
+    pub frozen_edit: Mutex<Option<FrozenEdit>>,
+    /// Images of open pin windows, by window label.
+    pub pins: Mutex<HashMap<String, PinnedImage>>,
     pub tts_cache: Mutex<TtsCache>,
+    /// Shared with `OcrService` clones, hence the `Arc`.
+    pub ocr_cache: Arc<OcrCache>,
+    pub translation_history: Mutex<TranslationHistory>,
+    pub recent_saves: Mutex<RecentSaves>,
+    pub jobs: JobRegistry,
     pub http_client: reqwest::Client,
 }
 
@@ -226,10 +417,20 @@ impl Default for AppState {
         Self {
             settings: Mutex::new(Settings::default()),
             frozen_screenshots: Mutex::new(Vec::new()),
+            frozen_generation: AtomicU64::new(0),
             frozen_mode: Mutex::new(String::new()),
             frozen_window_rects: Mutex::new(serde_json::Value::Array(vec![])),
             frozen_monitors: Mutex::new(Vec::new()),
+            capture_countdown: AtomicU64::new(0),
+            scroll_capture: AtomicU64::new(0),
+            scroll_result: Mutex::new(None),
+            frozen_edit: Mutex::new(None),
+            pins: Mutex::new(HashMap::new()),
             tts_cache: Mutex::new(TtsCache::default()),
+            ocr_cache: Arc::new(OcrCache::default()),
+            translation_history: Mutex::new(TranslationHistory::default()),
+            recent_saves: Mutex::new(RecentSaves::default()),
+            jobs: JobRegistry::default(),
             http_client: reqwest::Client::new(),
         }
     }
@@ -267,3 +468,59 @@ impl TtsCache {
         self.order.clear();
     }
 }
+
+const TRANSLATION_HISTORY_MAX_ENTRIES: usize = 20;
+
+/// Recent (source, translation) pairs of this session, used as optional
+/// context for follow-up translations. Not persisted.
+#[derive(Debug, Default)]
+pub struct TranslationHistory {
+    entries: VecDeque<(String, String)>,
+}
+
+impl TranslationHistory {
+    pub fn push(&mut self, source: String, translated: String) {
+        self.entries.push_back((source, translated));
+        while self.entries.len() > TRANSLATION_HISTORY_MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    /// The last `n` entries, oldest first.
+    pub fn recent(&self, n: usize) -> Vec<(String, String)> {
+        let skip = self.entries.len().saturating_sub(n);
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+const RECENT_SAVES_MAX_ENTRIES: usize = 20;
+
+/// Screenshots auto-saved in this session, plus the `{counter}` placeholder
+/// value. Not persisted.
+#[derive(Debug, Default)]
+pub struct RecentSaves {
+    counter: u64,
+    entries: VecDeque<SavedScreenshot>,
+}
+
+impl RecentSaves {
+    /// Counter value for the next file name, starting at 1.
+    pub fn next_counter(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    pub fn push(&mut self, saved: SavedScreenshot) {
+        self.entries.push_front(saved);
+        self.entries.truncate(RECENT_SAVES_MAX_ENTRIES);
+    }
+
+    /// Newest first.
+    pub fn list(&self) -> Vec<SavedScreenshot> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
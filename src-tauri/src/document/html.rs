@@ -0,0 +1,201 @@
+use super::{push_text, Segment};
+
+/// Elements whose content is never translated (code, scripts, styles...).
+const RAW_TEXT_TAGS: [&str; 7] = ["script", "style", "pre", "code", "textarea", "kbd", "samp"];
+
+/// Minimal HTML splitter: tags, comments and doctype are kept verbatim, text
+/// nodes between them become translatable segments with their entities
+/// decoded (`ParsedDocument::render` escapes them again). The content of
+/// `RAW_TEXT_TAGS` elements is copied through untouched.
+pub fn parse(source: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            push_text_node(&mut segments, rest);
+            break;
+        };
+        if lt > 0 {
+            push_text_node(&mut segments, &rest[..lt]);
+            rest = &rest[lt..];
+            continue;
+        }
+
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|p| p + 3)
+        } else {
+            rest.find('>').map(|p| p + 1)
+        }
+        .unwrap_or(rest.len());
+        let tag = &rest[..end];
+        segments.push(Segment::Keep(tag.to_string()));
+        rest = &rest[end..];
+
+        if let Some(name) = raw_text_tag(tag) {
+            // Copy everything up to the matching close tag verbatim.
+            let close = format!("</{}", name);
+            let close_at = find_ignore_ascii_case(rest, &close).unwrap_or(rest.len());
+            if close_at > 0 {
+                segments.push(Segment::Keep(rest[..close_at].to_string()));
+            }
+            rest = &rest[close_at..];
+        }
+    }
+    segments
+}
+
+/// If `tag` opens one of `RAW_TEXT_TAGS` (and is not self-closing), return its name.
+fn raw_text_tag(tag: &str) -> Option<&'static str> {
+    let inner = tag.strip_prefix('<')?;
+    if inner.starts_with('/') || inner.starts_with('!') || tag.ends_with("/>") {
+        return None;
+    }
+    let name: String = inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    RAW_TEXT_TAGS.iter().copied().find(|t| *t == name)
+}
+
+/// Byte offset of the first ASCII-case-insensitive match of `needle`,
+/// without lowercasing a copy of the whole remaining document.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// `push_text` for a text node, with entities in the translatable part
+/// decoded so the model sees `a < b` rather than `a &lt; b`.
+fn push_text_node(segments: &mut Vec<Segment>, text: &str) {
+    let start = segments.len();
+    push_text(segments, text);
+    for segment in &mut segments[start..] {
+        if let Segment::Translate(text) = segment {
+            *text = decode_entities(text);
+        }
+    }
+}
+
+/// Decode numeric and common named character references. Unknown names are
+/// left as written, which `escape_text` turns into the literal text a browser
+/// would have shown for them.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        // Names are short; `;` is ASCII, so slicing up to it is safe.
+        let decoded = rest.as_bytes()[1..rest.len().min(12)]
+            .iter()
+            .position(|&b| b == b';')
+            .and_then(|semi| decode_entity(&rest[1..semi + 1]).map(|c| (c, semi + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "middot" => '·',
+        _ => return None,
+    })
+}
+
+/// Escape text for an HTML text node. A translation containing `<` or `&`
+/// (or a `<script>` the model was tricked into writing) stays text.
+pub fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{DocumentKind, ParsedDocument};
+
+    #[test]
+    fn decodes_text_and_escapes_translations() {
+        let segments = parse("<p>Fish &amp; chips &lt;3 &unknown; &#x4E2D;</p>");
+        let texts: Vec<&str> = segments
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Translate(t) => Some(t.as_str()),
+                Segment::Keep(_) => None,
+            })
+            .collect();
+        assert_eq!(texts, vec!["Fish & chips <3 &unknown; 中"]);
+
+        let doc = ParsedDocument {
+            kind: DocumentKind::Html,
+            segments,
+        };
+        let untranslated: Vec<Option<String>> = vec![None; doc.segments.len()];
+        assert_eq!(
+            doc.render(&untranslated),
+            "<p>Fish &amp; chips &lt;3 &amp;unknown; 中</p>"
+        );
+        let translations: Vec<Option<String>> = doc
+            .segments
+            .iter()
+            .map(|s| matches!(s, Segment::Translate(_)).then(|| "<script>x</script> & y".into()))
+            .collect();
+        assert_eq!(
+            doc.render(&translations),
+            "<p>&lt;script&gt;x&lt;/script&gt; &amp; y</p>"
+        );
+    }
+
+    #[test]
+    fn raw_text_close_tag_is_case_insensitive() {
+        let segments = parse("<SCRIPT>if (a < b) {}</Script><b>Hello</b>");
+        assert!(matches!(&segments[1], Segment::Keep(t) if t == "if (a < b) {}"));
+        assert!(matches!(&segments[2], Segment::Keep(t) if t == "</Script>"));
+        assert!(matches!(&segments[4], Segment::Translate(t) if t == "Hello"));
+    }
+}
@@ -0,0 +1,177 @@
+use super::{push_text, Segment};
+
+/// Line-based Markdown splitter. Structure (heading/list/quote markers, table
+/// pipes, code blocks, front matter, raw HTML) is kept verbatim; only the
+/// prose is emitted as translatable segments. Consecutive plain lines form one
+/// paragraph so wrapped sentences are translated together.
+pub fn parse(source: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut paragraph = String::new();
+    let mut fence: Option<&str> = None;
+    let mut in_front_matter = false;
+
+    for (line_no, line) in source.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches('\n');
+        let trimmed = content.trim();
+
+        if line_no == 0 && trimmed == "---" {
+            in_front_matter = true;
+            segments.push(Segment::Keep(line.to_string()));
+            continue;
+        }
+        if in_front_matter {
+            if trimmed == "---" || trimmed == "..." {
+                in_front_matter = false;
+            }
+            segments.push(Segment::Keep(line.to_string()));
+            continue;
+        }
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            segments.push(Segment::Keep(line.to_string()));
+            continue;
+        }
+
+        if is_plain_line(content, paragraph.is_empty()) {
+            paragraph.push_str(line);
+            continue;
+        }
+
+        flush_paragraph(&mut segments, &mut paragraph);
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            segments.push(Segment::Keep(line.to_string()));
+        } else if is_verbatim_line(content) {
+            segments.push(Segment::Keep(line.to_string()));
+        } else if trimmed.starts_with('|') {
+            push_table_row(&mut segments, line);
+        } else {
+            let prefix_len = prefix_len(content);
+            segments.push(Segment::Keep(line[..prefix_len].to_string()));
+            push_text(&mut segments, &line[prefix_len..]);
+        }
+    }
+    flush_paragraph(&mut segments, &mut paragraph);
+    segments
+}
+
+fn flush_paragraph(segments: &mut Vec<Segment>, paragraph: &mut String) {
+    push_text(segments, paragraph);
+    paragraph.clear();
+}
+
+/// A line that continues (or starts) a plain paragraph.
+fn is_plain_line(content: &str, paragraph_empty: bool) -> bool {
+    let trimmed = content.trim();
+    if trimmed.is_empty() || trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+        return false;
+    }
+    // Indented code only starts after a blank line; inside a paragraph an
+    // indented line is a lazy continuation.
+    if paragraph_empty && (content.starts_with("    ") || content.starts_with('\t')) {
+        return false;
+    }
+    !is_verbatim_line(content) && !trimmed.starts_with('|') && prefix_len(content) == 0
+}
+
+/// Lines copied through untouched: blanks, rules, raw HTML, table separators
+/// and link reference definitions.
+fn is_verbatim_line(content: &str) -> bool {
+    let trimmed = content.trim();
+    if trimmed.is_empty() || trimmed.starts_with('<') {
+        return true;
+    }
+    if content.starts_with("    ") || content.starts_with('\t') {
+        return true;
+    }
+    let rule: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    let is_rule = rule.len() >= 3 && matches!(rule[0], '-' | '*' | '_');
+    if is_rule && rule.iter().all(|&c| c == rule[0]) {
+        return true;
+    }
+    if trimmed.contains('-')
+        && trimmed.contains('|')
+        && trimmed.chars().all(|c| matches!(c, '|' | ':' | '-' | ' '))
+    {
+        return true;
+    }
+    trimmed.starts_with('[') && trimmed.contains("]:")
+}
+
+/// Length in bytes of the block marker prefix: indentation, any number of
+/// `> ` quote markers, then a heading / bullet / ordered-list / task marker.
+fn prefix_len(content: &str) -> usize {
+    let bytes = content.as_bytes();
+    let mut i = 0;
+    let skip_spaces = |i: &mut usize| {
+        while *i < bytes.len() && (bytes[*i] == b' ' || bytes[*i] == b'\t') {
+            *i += 1;
+        }
+    };
+
+    skip_spaces(&mut i);
+    while i < bytes.len() && bytes[i] == b'>' {
+        i += 1;
+        skip_spaces(&mut i);
+    }
+
+    let marker_start = i;
+    if i < bytes.len() && bytes[i] == b'#' {
+        while i < bytes.len() && bytes[i] == b'#' {
+            i += 1;
+        }
+        if i - marker_start <= 6 && (i == bytes.len() || bytes[i] == b' ') {
+            skip_spaces(&mut i);
+            return i;
+        }
+        i = marker_start;
+    } else if i + 1 < bytes.len() && matches!(bytes[i], b'-' | b'*' | b'+') && bytes[i + 1] == b' '
+    {
+        i += 2;
+        skip_spaces(&mut i);
+        if content[i..].starts_with("[ ] ")
+            || content[i..].starts_with("[x] ")
+            || content[i..].starts_with("[X] ")
+        {
+            i += 4;
+        }
+        return i;
+    } else {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i > marker_start
+            && i - marker_start <= 9
+            && i + 1 < bytes.len()
+            && matches!(bytes[i], b'.' | b')')
+            && bytes[i + 1] == b' '
+        {
+            i += 2;
+            skip_spaces(&mut i);
+            return i;
+        }
+        i = marker_start;
+    }
+
+    // Only quote markers / indentation: treat them as prefix when quoted.
+    if content[..i].contains('>') {
+        i
+    } else {
+        0
+    }
+}
+
+/// Translate each table cell separately, keeping the pipes and padding.
+fn push_table_row(segments: &mut Vec<Segment>, line: &str) {
+    let mut rest = line;
+    while let Some(pos) = rest.find('|') {
+        push_text(segments, &rest[..pos]);
+        segments.push(Segment::Keep("|".to_string()));
+        rest = &rest[pos + 1..];
+    }
+    push_text(segments, rest);
+}
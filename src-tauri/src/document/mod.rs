@@ -0,0 +1,262 @@
+pub mod html;
+pub mod markdown;
+
+use std::path::{Path, PathBuf};
+
+/// Upper bound (in chars) for one translation request. Long paragraphs are
+/// split at sentence boundaries and short ones are batched up to this size.
+pub const MAX_CHUNK_CHARS: usize = 1500;
+
+/// Separator used to batch several short segments into one request. The
+/// translated response is split on the same separator; on mismatch the caller
+/// falls back to translating the segments one by one.
+pub const CHUNK_SEPARATOR: &str = "\n\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Text,
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl DocumentKind {
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "txt" | "text" => Ok(Self::Text),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" | "xhtml" => Ok(Self::Html),
+            "pdf" => Ok(Self::Pdf),
+            other => anyhow::bail!("不支持的文档类型: .{}", other),
+        }
+    }
+
+    /// Extension of the translated output. PDFs are written as plain text.
+    pub fn output_extension(&self) -> &'static str {
+        match self {
+            Self::Text | Self::Pdf => "txt",
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// A piece of the source document: either copied verbatim (markup, code,
+/// whitespace) or sent to the translator.
+#[derive(Debug, Clone)]
+pub enum Segment {
+    Keep(String),
+    Translate(String),
+}
+
+#[derive(Debug)]
+pub struct ParsedDocument {
+    pub kind: DocumentKind,
+    pub segments: Vec<Segment>,
+}
+
+impl ParsedDocument {
+    /// Group translatable segments into request-sized chunks.
+    /// Each chunk is a list of indices into `segments`.
+    pub fn chunks(&self) -> Vec<Vec<usize>> {
+        let mut chunks = Vec::new();
+        let mut current: Vec<usize> = Vec::new();
+        let mut current_len = 0;
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let Segment::Translate(text) = segment else {
+                continue;
+            };
+            let len = text.chars().count();
+            // Segments containing the separator cannot be batched safely.
+            let batchable = !text.contains(CHUNK_SEPARATOR);
+            if !current.is_empty()
+                && (!batchable || current_len + CHUNK_SEPARATOR.len() + len > MAX_CHUNK_CHARS)
+            {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            current.push(i);
+            current_len += len + CHUNK_SEPARATOR.len();
+            if !batchable {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+        }
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+        chunks
+    }
+
+    /// Join the source text of a chunk into one request body.
+    pub fn chunk_text(&self, chunk: &[usize]) -> String {
+        chunk
+            .iter()
+            .filter_map(|&i| match &self.segments[i] {
+                Segment::Translate(text) => Some(text.as_str()),
+                Segment::Keep(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join(CHUNK_SEPARATOR)
+    }
+
+    /// Reassemble the document, substituting `translations[i]` for every
+    /// translatable segment `i` (untranslated segments keep their source).
+    /// HTML text is escaped, since its segments hold decoded text.
+    pub fn render(&self, translations: &[Option<String>]) -> String {
+        let mut out = String::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Keep(text) => out.push_str(text),
+                Segment::Translate(text) => {
+                    let text = translations
+                        .get(i)
+                        .and_then(|t| t.as_deref())
+                        .unwrap_or(text);
+                    match self.kind {
+                        DocumentKind::Html => out.push_str(&html::escape_text(text)),
+                        _ => out.push_str(text),
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Read and parse a document from disk. Blocking (file IO + PDF extraction).
+pub fn load(path: &Path) -> anyhow::Result<ParsedDocument> {
+    let kind = DocumentKind::from_path(path)?;
+    let bytes = std::fs::read(path)?;
+    let segments = match kind {
+        DocumentKind::Text => parse_plain(&decode_text(&bytes)),
+        DocumentKind::Markdown => markdown::parse(&decode_text(&bytes)),
+        DocumentKind::Html => html::parse(&decode_text(&bytes)),
+        DocumentKind::Pdf => parse_plain(&extract_pdf_text(&bytes)?),
+    };
+    Ok(ParsedDocument {
+        kind,
+        segments: split_long_segments(segments),
+    })
+}
+
+/// Default output path: `<dir>/<stem>.<target_lang>.<ext>` next to the input.
+pub fn default_output_path(input: &Path, kind: DocumentKind, target_lang: &str) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("document");
+    input.with_file_name(format!(
+        "{}.{}.{}",
+        stem,
+        target_lang,
+        kind.output_extension()
+    ))
+}
+
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).replace("\r\n", "\n")
+}
+
+/// Extract the text layer of a PDF. Scanned PDFs without a text layer yield an
+/// error instead of an empty translation.
+fn extract_pdf_text(bytes: &[u8]) -> anyhow::Result<String> {
+    let text = pdf_extract::extract_text_from_mem(bytes)
+        .map_err(|e| anyhow::anyhow!("PDF 文本提取失败: {}", e))?;
+    if text.trim().is_empty() {
+        anyhow::bail!("PDF 不含文本层（可能是扫描件），请先使用 OCR 识别");
+    }
+    Ok(text.replace("\r\n", "\n"))
+}
+
+/// Plain text: paragraphs separated by blank lines are translated as units.
+pub fn parse_plain(source: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut paragraph = String::new();
+    for line in source.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            push_text(&mut segments, &paragraph);
+            paragraph.clear();
+            segments.push(Segment::Keep(line.to_string()));
+        } else {
+            paragraph.push_str(line);
+        }
+    }
+    push_text(&mut segments, &paragraph);
+    segments
+}
+
+/// Push a text run, keeping leading/trailing whitespace verbatim. Runs without
+/// any letters (numbers, punctuation, `&nbsp;`) are kept untranslated.
+pub(crate) fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let trimmed_start = text.trim_start();
+    let leading = &text[..text.len() - trimmed_start.len()];
+    let core = trimmed_start.trim_end();
+    let trailing = &trimmed_start[core.len()..];
+
+    let is_entity = core.starts_with('&') && core.ends_with(';') && !core.contains(' ');
+    if is_entity || !core.chars().any(char::is_alphabetic) {
+        segments.push(Segment::Keep(text.to_string()));
+        return;
+    }
+    if !leading.is_empty() {
+        segments.push(Segment::Keep(leading.to_string()));
+    }
+    segments.push(Segment::Translate(core.to_string()));
+    if !trailing.is_empty() {
+        segments.push(Segment::Keep(trailing.to_string()));
+    }
+}
+
+/// Split translatable segments longer than `MAX_CHUNK_CHARS` at sentence
+/// boundaries so every request stays within the model's comfortable size.
+fn split_long_segments(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut out = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match segment {
+            Segment::Translate(text) if text.chars().count() > MAX_CHUNK_CHARS => {
+                for piece in split_at_sentences(&text, MAX_CHUNK_CHARS) {
+                    push_text(&mut out, piece);
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn split_at_sentences(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars_in_piece = 0;
+    let mut last_boundary: Option<usize> = None;
+
+    for (idx, c) in text.char_indices() {
+        chars_in_piece += 1;
+        let end = idx + c.len_utf8();
+        if matches!(c, '.' | '!' | '?' | ';' | '\n' | '。' | '！' | '？' | '；') {
+            last_boundary = Some(end);
+        }
+        if chars_in_piece >= max_chars {
+            let cut = last_boundary.filter(|&b| b > start).unwrap_or(end);
+            pieces.push(&text[start..cut]);
+            start = cut;
+            chars_in_piece = text[start..end].chars().count();
+            last_boundary = None;
+        }
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
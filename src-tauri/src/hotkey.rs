@@ -1,4 +1,6 @@
+use crate::actions::ACTION_EVENT_PREFIX;
 use crate::config::{AppState, HotkeyConfig};
+use crate::ocr::OCR_MODE_EVENT_PREFIX;
 use log::{info, warn};
 use std::collections::HashSet;
 use std::str::FromStr;
@@ -70,11 +72,42 @@ fn parse_shortcut(value: &str) -> Result<Shortcut, String> {
 /// Register each shortcut independently. A conflict in one combination must
 /// not prevent the other configured actions from being registered.
 fn apply_hotkeys(app: &AppHandle, cfg: &HotkeyConfig) {
-    let entries: [(&str, &str); 3] = [
-        ("screenshot", cfg.screenshot.as_str()),
-        ("ocr_translate", cfg.ocr_translate.as_str()),
-        ("clipboard_translate", cfg.clipboard_translate.as_str()),
+    let mut entries: Vec<(String, &str)> = vec![
+        ("screenshot".to_string(), cfg.screenshot.as_str()),
+        ("ocr_translate".to_string(), cfg.ocr_translate.as_str()),
+        (
+            "clipboard_translate".to_string(),
+            cfg.clipboard_translate.as_str(),
+        ),
     ];
+    for (name, raw) in [
+        ("delayed_screenshot", &cfg.delayed_screenshot),
+        ("fullscreen_screenshot", &cfg.fullscreen_screenshot),
+        ("window_screenshot", &cfg.window_screenshot),
+        ("scroll_screenshot", &cfg.scroll_screenshot),
+    ] {
+        if !raw.trim().is_empty() {
+            entries.push((name.to_string(), raw.as_str()));
+        }
+    }
+    // Text actions are optional: an empty binding simply means "no hotkey".
+    entries.extend(
+        cfg.actions
+            .iter()
+            .filter(|(_, raw)| !raw.trim().is_empty())
+            .map(|(id, raw)| (format!("{}{}", ACTION_EVENT_PREFIX, id), raw.as_str())),
+    );
+    entries.extend(
+        cfg.ocr_modes
+            .iter()
+            .filter(|(_, raw)| !raw.trim().is_empty())
+            .map(|(mode, raw)| {
+                (
+                    format!("{}{}", OCR_MODE_EVENT_PREFIX, mode.id()),
+                    raw.as_str(),
+                )
+            }),
+    );
 
     let global_shortcut = app.global_shortcut();
     let mut seen = HashSet::new();
@@ -96,7 +129,6 @@ fn apply_hotkeys(app: &AppHandle, cfg: &HotkeyConfig) {
         }
         valid += 1;
 
-        let action = action.to_string();
         match global_shortcut.on_shortcut(shortcut, move |app, shortcut, event| {
             // Dispatch on Pressed. Waiting for Released is unreliable when an
             // action immediately moves keyboard focus to a new window.
@@ -115,8 +147,8 @@ fn apply_hotkeys(app: &AppHandle, cfg: &HotkeyConfig) {
     }
 
     info!(
-        "[Hotkey] 已注册 {}/{} 个快捷键: screenshot={}, ocr_translate={}, clipboard_translate={}",
-        registered, valid, cfg.screenshot, cfg.ocr_translate, cfg.clipboard_translate
+        "[Hotkey] 已注册 {}/{} 个快捷键: screenshot={}, ocr_translate={}, clipboard_translate={}, delayed_screenshot={}, fullscreen_screenshot={}, window_screenshot={}, scroll_screenshot={}, actions={:?}, ocr_modes={:?}",
+        registered, valid, cfg.screenshot, cfg.ocr_translate, cfg.clipboard_translate, cfg.delayed_screenshot, cfg.fullscreen_screenshot, cfg.window_screenshot, cfg.scroll_screenshot, cfg.actions, cfg.ocr_modes
     );
 }
 
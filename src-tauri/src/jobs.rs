@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often a paused job checks whether it was resumed or cancelled.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Control flags shared between a running background job and the commands
+/// that steer it. The job polls these between units of work.
+#[derive(Debug, Default)]
+pub struct JobControl {
+    cancelled: AtomicBool,
+    paused: AtomicBool,
+}
+
+impl JobControl {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Stop starting new units of work; units already running finish.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    /// Wait until the job is resumed or cancelled.
+    pub async fn wait_while_paused(&self) {
+        while self.is_paused() && !self.is_cancelled() {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Registry of running background jobs (document translation, batch OCR, ...),
+/// keyed by a generated job id such as `"document-3"`.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<String, Arc<JobControl>>>,
+}
+
+impl JobRegistry {
+    /// Register a new job and return its id together with its control handle.
+    pub fn start(&self, kind: &str) -> (String, Arc<JobControl>) {
+        let id = format!(
+            "{}-{}",
+            kind,
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        let control = Arc::new(JobControl::default());
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.insert(id.clone(), control.clone());
+        }
+        (id, control)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<JobControl>> {
+        self.jobs.lock().ok()?.get(id).cloned()
+    }
+
+    /// Request cancellation. Returns `false` if no such job is running.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.get(id) {
+            Some(control) => {
+                control.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pause a job. Returns `false` if no such job is running.
+    pub fn pause(&self, id: &str) -> bool {
+        match self.get(id) {
+            Some(control) => {
+                control.pause();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Resume a paused job. Returns `false` if no such job is running.
+    pub fn resume(&self, id: &str) -> bool {
+        match self.get(id) {
+            Some(control) => {
+                control.resume();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop a finished job from the registry.
+    pub fn finish(&self, id: &str) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.remove(id);
+        }
+    }
+}
@@ -1,13 +1,28 @@
+mod actions;
 mod api_client;
+mod barcode;
+mod batch_ocr;
 mod commands;
 mod config;
+mod document;
 mod hotkey;
+mod jobs;
 mod ocr;
+mod output;
+mod protocol;
+mod render;
 mod screenshot;
 mod translation;
 mod tray;
 mod tts;
 
+/// Frozen-screenshot helpers used by `benches/frozen_screenshot.rs`; not an
+/// API for anything else.
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::screenshot::{crop_region, encode_jpeg, encode_png, encode_png_fast};
+}
+
 use config::{AppState, Settings};
 use log::{info, warn};
 use tauri::{Listener, Manager, RunEvent, WindowEvent};
@@ -33,19 +48,55 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
         .manage(app_state)
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, protocol::handle)
         .invoke_handler(tauri::generate_handler![
             commands::screenshot::start_region_select,
+            commands::screenshot::start_delayed_region_select,
+            commands::screenshot::capture_fullscreen,
+            commands::screenshot::capture_window,
+            commands::scroll_capture::start_scroll_capture,
+            commands::scroll_capture::stop_scroll_capture,
+            commands::pin::pin_image,
+            commands::pin::get_pin,
+            commands::pin::copy_pin,
+            commands::pin::save_pin,
+            commands::annotate::render_annotation,
+            commands::annotate::save_annotated,
+            commands::annotate::open_annotation,
             commands::screenshot::capture_region,
             commands::screenshot::get_frozen_screenshot,
             commands::ocr::capture_and_ocr,
+            commands::ocr::capture_and_ocr_structured,
+            commands::ocr::capture_and_translate,
+            commands::ocr::list_ocr_modes,
+            commands::ocr::preview_ocr_preprocess,
+            commands::ocr::list_preprocess_profiles,
+            commands::ocr::get_ocr_cache_stats,
+            commands::ocr::clear_ocr_cache,
+            commands::image_translation::translate_image_region,
+            commands::barcode::decode_codes,
+            commands::image_ocr::ocr_image_file,
+            commands::image_ocr::ocr_clipboard_image,
+            commands::image_ocr::ocr_scroll_capture,
             commands::translation::translate_text,
+            commands::actions::list_text_actions,
+            commands::actions::run_text_action,
             commands::settings::get_settings,
             commands::settings::save_settings,
             commands::clipboard::read_clipboard,
             commands::clipboard::copy_image_to_clipboard,
             commands::clipboard::read_selected_text,
             commands::clipboard::save_file,
+            commands::screenshot::save_screenshot,
+            commands::screenshot::get_recent_saves,
+            commands::screenshot::clear_recent_saves,
             commands::tts::synthesize_speech,
+            commands::document::translate_document,
+            commands::document::cancel_document_job,
+            commands::batch_ocr::start_batch_ocr,
+            commands::batch_ocr::pause_batch_ocr,
+            commands::batch_ocr::resume_batch_ocr,
+            commands::batch_ocr::cancel_batch_ocr,
             hotkey::suspend_hotkeys,
             hotkey::resume_hotkeys,
         ])
@@ -59,7 +110,7 @@ pub fn run() {
                     if let Ok(settings) = serde_json::from_value::<Settings>(value) {
                         info!(
                             "[Setup] 配置加载成功, translation.model={}, ocr.model={}",
-                            settings.translation.model, settings.ocr.model
+                            settings.translation.model, settings.ocr.service.model
                         );
                         let app_state = app.state::<AppState>();
                         let mut guard = app_state.settings.lock().unwrap();
@@ -73,6 +124,13 @@ pub fn run() {
             } else {
                 warn!("[Setup] 无法打开 settings.json store");
             }
+            match app.path().app_cache_dir() {
+                Ok(dir) => app
+                    .state::<AppState>()
+                    .ocr_cache
+                    .set_disk_dir(dir.join("ocr-cache")),
+                Err(e) => warn!("[Setup] 无法获取缓存目录，OCR 仅使用内存缓存: {}", e),
+            }
             tray::setup_tray(app)?;
             info!("[Setup] 系统托盘初始化完成");
             hotkey::setup_hotkeys(app)?;
@@ -116,6 +174,12 @@ pub fn run() {
                     let app = app_handle.clone();
                     std::thread::spawn(move || hotkey::restore_hotkeys(&app));
                 }
+                // Pin images live exactly as long as their window.
+                if label.starts_with(commands::pin::PIN_LABEL_PREFIX)
+                    && matches!(event, WindowEvent::Destroyed)
+                {
+                    commands::pin::forget_pin(&app_handle.state::<AppState>(), label);
+                }
             }
 
             // macOS: 点击 Dock 图标时显示主窗口
@@ -0,0 +1,224 @@
+use super::PreparedOcrImage;
+use log::{info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+const MEMORY_MAX_ENTRIES: usize = 128;
+const DISK_MAX_ENTRIES: usize = 1000;
+const DISK_EXTENSION: &str = "txt";
+
+#[derive(Debug, Default)]
+struct MemoryCache {
+    entries: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl MemoryCache {
+    fn insert(&mut self, key: String, value: String) {
+        if self.entries.contains_key(&key) {
+            self.order.retain(|existing| existing != &key);
+        }
+        self.entries.insert(key.clone(), value);
+        self.order.push_back(key);
+
+        while self.order.len() > MEMORY_MAX_ENTRIES {
+            if let Some(oldest_key) = self.order.pop_front() {
+                self.entries.remove(&oldest_key);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.entries.remove(key).is_some() {
+            self.order.retain(|existing| existing != key);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct OcrCacheStats {
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    pub memory_entries: usize,
+    pub disk_entries: usize,
+    pub disk_bytes: u64,
+}
+
+/// Raw vision-model replies keyed by a SHA-256 of the uploaded payload,
+/// endpoint, model, prompt and extra parameters. Kept in an in-memory LRU
+/// (like `TtsCache`) and mirrored to one file per entry under the app cache
+/// directory, so repeated OCR of the same crop survives restarts.
+/// Disk reads run on the blocking pool; disk writes and pruning run in the
+/// background, so only the in-memory map is touched on the async path.
+#[derive(Debug, Default)]
+pub struct OcrCache {
+    memory: Mutex<MemoryCache>,
+    disk_dir: OnceLock<PathBuf>,
+    memory_hits: AtomicU64,
+    disk_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl OcrCache {
+    /// Enable the disk layer; called once during setup.
+    pub fn set_disk_dir(&self, dir: PathBuf) {
+        info!("[OCR] 缓存目录: {}", dir.display());
+        let _ = self.disk_dir.set(dir);
+    }
+
+    pub(crate) fn key(
+        prepared: &PreparedOcrImage,
+        base_url: &str,
+        model: &str,
+        prompt: &str,
+        extra: &str,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            prepared.media_type,
+            prepared.base64_data.as_str(),
+            base_url,
+            model,
+            prompt,
+            extra,
+        ] {
+            hasher.update(part.as_bytes());
+            // Separator so ("ab", "c") and ("a", "bc") hash differently.
+            hasher.update([0u8]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        self.disk_dir
+            .get()
+            .map(|dir| dir.join(format!("{}.{}", key, DISK_EXTENSION)))
+    }
+
+    pub async fn get(&self, key: &str) -> Option<String> {
+        if let Some(value) = self
+            .memory
+            .lock()
+            .ok()
+            .and_then(|memory| memory.entries.get(key).cloned())
+        {
+            self.memory_hits.fetch_add(1, Ordering::Relaxed);
+            return Some(value);
+        }
+        let disk_value = match self.disk_path(key) {
+            Some(path) => tokio::task::spawn_blocking(move || std::fs::read_to_string(path).ok())
+                .await
+                .ok()
+                .flatten(),
+            None => None,
+        };
+        if let Some(value) = disk_value {
+            self.disk_hits.fetch_add(1, Ordering::Relaxed);
+            if let Ok(mut memory) = self.memory.lock() {
+                memory.insert(key.to_string(), value.clone());
+            }
+            return Some(value);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Store a reply the caller has already parsed successfully. The disk
+    /// copy is written by a background blocking task.
+    pub fn insert(&self, key: String, value: String) {
+        if let (Some(path), Some(dir)) = (self.disk_path(&key), self.disk_dir.get().cloned()) {
+            let bytes = value.clone().into_bytes();
+            tokio::task::spawn_blocking(move || match crate::output::write_file(&path, &bytes) {
+                Ok(()) => prune_disk(&dir),
+                Err(e) => warn!("[OCR] 缓存写入磁盘失败: {}", e),
+            });
+        }
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.insert(key, value);
+        }
+    }
+
+    /// Drop one entry, e.g. a cached reply that no longer parses.
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.remove(key);
+        }
+        if let Some(path) = self.disk_path(key) {
+            tokio::task::spawn_blocking(move || {
+                let _ = std::fs::remove_file(path);
+            });
+        }
+    }
+
+    /// Drop all entries and reset the hit/miss counters.
+    pub fn clear(&self) -> anyhow::Result<()> {
+        if let Ok(mut memory) = self.memory.lock() {
+            *memory = MemoryCache::default();
+        }
+        if let Some(dir) = self.disk_dir.get() {
+            for (path, _, _) in disk_entries(dir) {
+                std::fs::remove_file(path)?;
+            }
+        }
+        self.memory_hits.store(0, Ordering::Relaxed);
+        self.disk_hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        info!("[OCR] 缓存已清空");
+        Ok(())
+    }
+
+    pub fn stats(&self) -> OcrCacheStats {
+        let memory_entries = self
+            .memory
+            .lock()
+            .map(|memory| memory.entries.len())
+            .unwrap_or(0);
+        let disk = self.disk_dir.get().map(|dir| disk_entries(dir));
+        OcrCacheStats {
+            memory_hits: self.memory_hits.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            memory_entries,
+            disk_entries: disk.as_ref().map_or(0, Vec::len),
+            disk_bytes: disk
+                .as_ref()
+                .map_or(0, |entries| entries.iter().map(|(_, size, _)| size).sum()),
+        }
+    }
+}
+
+/// Cache files in `dir` with their size and modification time.
+fn disk_entries(dir: &Path) -> Vec<(PathBuf, u64, std::time::SystemTime)> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .filter(|entry| {
+            entry.path().extension().and_then(|ext| ext.to_str()) == Some(DISK_EXTENSION)
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some((entry.path(), meta.len(), meta.modified().ok()?))
+        })
+        .collect()
+}
+
+/// Remove the oldest files beyond `DISK_MAX_ENTRIES`.
+fn prune_disk(dir: &Path) {
+    let mut entries = disk_entries(dir);
+    if entries.len() <= DISK_MAX_ENTRIES {
+        return;
+    }
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    let surplus = entries.len() - DISK_MAX_ENTRIES;
+    for (path, _, _) in entries.into_iter().take(surplus) {
+        let _ = std::fs::remove_file(path);
+    }
+    info!("[OCR] 磁盘缓存超出上限，已删除 {} 个最旧条目", surplus);
+}
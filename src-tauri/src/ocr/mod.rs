@@ -1,20 +1,42 @@
+pub mod cache;
+pub mod one_shot;
+pub mod preprocess;
+pub mod prompt;
+pub mod structured;
+pub mod tesseract;
+pub mod tiling;
+
 use base64::Engine;
 use image::codecs::jpeg::JpegEncoder;
 use image::imageops::FilterType;
 use image::{DynamicImage, GenericImageView, ImageFormat};
-use log::info;
+use log::{info, warn};
 use std::io::Cursor;
 
+pub use cache::OcrCache;
+pub use preprocess::PreprocessProfile;
+pub use prompt::{OcrMode, OCR_MODE_EVENT_PREFIX};
+pub use structured::{recognize_structured, OcrLayout};
+pub use tiling::OcrTiling;
+
 const OCR_MAX_LONGEST_EDGE: u32 = 2048;
 const OCR_JPEG_QUALITY: u8 = 90;
 
-struct PreparedOcrImage {
-    media_type: &'static str,
-    base64_data: String,
-    width: u32,
-    height: u32,
-    original_width: u32,
-    original_height: u32,
+/// OCR payload after downscaling/re-encoding. `width`/`height` are the
+/// dimensions the model sees; `original_*` are the crop's dimensions.
+pub(crate) struct PreparedOcrImage {
+    pub media_type: &'static str,
+    pub base64_data: String,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+impl PreparedOcrImage {
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.media_type, self.base64_data)
+    }
 }
 
 fn prepare_ocr_image_from_bytes(bytes: &[u8]) -> anyhow::Result<PreparedOcrImage> {
@@ -62,34 +84,45 @@ fn prepare_ocr_image_from_bytes(bytes: &[u8]) -> anyhow::Result<PreparedOcrImage
     })
 }
 
-/// Perform OCR using a vision-language model via OpenAI-compatible API.
-/// Accepts raw image bytes (any format supported by `image::load_from_memory`).
-pub async fn recognize(
+/// Decode, downscale and re-encode image bytes for upload, off the async runtime.
+pub(crate) async fn prepare(image_bytes: &[u8]) -> anyhow::Result<PreparedOcrImage> {
+    let owned_bytes = image_bytes.to_vec();
+    tokio::task::spawn_blocking(move || prepare_ocr_image_from_bytes(&owned_bytes)).await?
+}
+
+/// Send one image + text prompt to the vision model and parse its reply.
+/// With a `cache`, an identical payload/model/prompt/extra is answered from
+/// it instead of re-uploading the image. Only replies that `parse` accepts are
+/// cached, so a malformed answer is retried rather than replayed; a cached
+/// reply that no longer parses is evicted.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn send_vision_request<T>(
     client: &reqwest::Client,
-    image_bytes: &[u8],
-    _language: &str,
+    prepared: &PreparedOcrImage,
+    prompt: &str,
     base_url: &str,
     api_key: &str,
     model: &str,
     extra: &str,
-) -> anyhow::Result<String> {
-    let url = crate::api_client::chat_completions_url(base_url);
-    let original_size = image_bytes.len();
-    let owned_bytes = image_bytes.to_vec();
-    let prepared =
-        tokio::task::spawn_blocking(move || prepare_ocr_image_from_bytes(&owned_bytes)).await??;
-    info!(
-        "[OCR] 发送请求到 {}, model={}, image {}x{} -> {}x{}, media_type={}, size={} -> {}",
-        url,
-        model,
-        prepared.original_width,
-        prepared.original_height,
-        prepared.width,
-        prepared.height,
-        prepared.media_type,
-        original_size,
-        prepared.base64_data.len()
-    );
+    tag: &str,
+    cache: Option<&OcrCache>,
+    parse: impl Fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let cache_key = cache.map(|_| OcrCache::key(prepared, base_url, model, prompt, extra));
+    if let (Some(cache), Some(key)) = (cache, &cache_key) {
+        if let Some(cached) = cache.get(key).await {
+            match parse(&cached) {
+                Ok(parsed) => {
+                    info!("[{}] 命中 OCR 缓存, 结果长度={}", tag, cached.len());
+                    return Ok(parsed);
+                }
+                Err(e) => {
+                    warn!("[{}] OCR 缓存内容无法解析，已丢弃: {}", tag, e);
+                    cache.remove(key);
+                }
+            }
+        }
+    }
 
     let request_body = serde_json::json!({
         "model": model,
@@ -100,12 +133,12 @@ pub async fn recognize(
                     {
                         "type": "image_url",
                         "image_url": {
-                            "url": format!("data:{};base64,{}", prepared.media_type, prepared.base64_data)
+                            "url": prepared.data_url()
                         }
                     },
                     {
                         "type": "text",
-                        "text": "请识别图片中的所有文字，只输出纯文本。不要使用Markdown、HTML或其他标记语言，表格内容按行列用纯文本输出。"
+                        "text": prompt
                     }
                 ]
             }
@@ -113,6 +146,75 @@ pub async fn recognize(
         "temperature": 0.1
     });
 
-    crate::api_client::send_chat_completion(client, base_url, api_key, extra, request_body, "OCR")
-        .await
+    let reply = crate::api_client::send_chat_completion(
+        client,
+        base_url,
+        api_key,
+        extra,
+        request_body,
+        tag,
+    )
+    .await?;
+    let parsed = parse(&reply)?;
+    if let (Some(cache), Some(key)) = (cache, cache_key) {
+        if !reply.trim().is_empty() {
+            cache.insert(key, reply);
+        }
+    }
+    Ok(parsed)
+}
+
+fn log_prepared(
+    tag: &str,
+    url: &str,
+    model: &str,
+    original_size: usize,
+    prepared: &PreparedOcrImage,
+) {
+    info!(
+        "[{}] 发送请求到 {}, model={}, image {}x{} -> {}x{}, media_type={}, size={} -> {}",
+        tag,
+        url,
+        model,
+        prepared.original_width,
+        prepared.original_height,
+        prepared.width,
+        prepared.height,
+        prepared.media_type,
+        original_size,
+        prepared.base64_data.len()
+    );
+}
+
+/// Perform OCR using a vision-language model via OpenAI-compatible API.
+/// Accepts raw image bytes (any format supported by `image::load_from_memory`).
+/// `prompt` comes from `prompt::recognition_prompt` (language + output mode).
+#[allow(clippy::too_many_arguments)]
+pub async fn recognize(
+    client: &reqwest::Client,
+    image_bytes: &[u8],
+    prompt: &str,
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    extra: &str,
+    cache: Option<&OcrCache>,
+) -> anyhow::Result<String> {
+    let url = crate::api_client::chat_completions_url(base_url);
+    let prepared = prepare(image_bytes).await?;
+    log_prepared("OCR", &url, model, image_bytes.len(), &prepared);
+
+    send_vision_request(
+        client,
+        &prepared,
+        prompt,
+        base_url,
+        api_key,
+        model,
+        extra,
+        "OCR",
+        cache,
+        |reply| Ok(reply.to_string()),
+    )
+    .await
 }
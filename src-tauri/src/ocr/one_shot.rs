@@ -0,0 +1,74 @@
+use super::structured::extract_json_object;
+use super::{prepare, prompt, send_vision_request, OcrCache};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Transcription and translation returned by a single vision request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionTranslation {
+    pub source: String,
+    pub translation: String,
+}
+
+fn one_shot_prompt(source_lang: &str, target_lang: &str) -> String {
+    let hint = prompt::language_hint(source_lang)
+        .map(|hint| format!("\n{}", hint))
+        .unwrap_or_default();
+    format!(
+        "Transcribe all text in this image exactly as it appears, then translate the transcription \
+         into {target_lang}.{hint}\n\
+         Respond with ONLY a JSON object of this exact shape, no Markdown fences or commentary:\n\
+         {{\"source\":\"the transcribed text\",\"translation\":\"the {target_lang} translation\"}}\n\
+         Keep line breaks as \\n in both fields. If the image contains no text, use empty strings."
+    )
+}
+
+/// OCR + translation in one round trip: the vision model transcribes the
+/// image and translates it into `target_lang`, answering with JSON.
+#[allow(clippy::too_many_arguments)]
+pub async fn recognize_and_translate(
+    client: &reqwest::Client,
+    image_bytes: &[u8],
+    source_lang: &str,
+    target_lang: &str,
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    extra: &str,
+    cache: Option<&OcrCache>,
+) -> anyhow::Result<VisionTranslation> {
+    let prepared = prepare(image_bytes).await?;
+    info!(
+        "[OCR] 一次请求识别+翻译, image {}x{} -> {}x{}, {} → {}",
+        prepared.original_width,
+        prepared.original_height,
+        prepared.width,
+        prepared.height,
+        source_lang,
+        target_lang
+    );
+    let prompt = one_shot_prompt(source_lang, target_lang);
+    let result = send_vision_request(
+        client,
+        &prepared,
+        &prompt,
+        base_url,
+        api_key,
+        model,
+        extra,
+        "OCR",
+        cache,
+        parse_reply,
+    )
+    .await?;
+    Ok(VisionTranslation {
+        source: result.source.trim().to_string(),
+        translation: result.translation.trim().to_string(),
+    })
+}
+
+fn parse_reply(reply: &str) -> anyhow::Result<VisionTranslation> {
+    let json = extract_json_object(reply)
+        .ok_or_else(|| anyhow::anyhow!("识别+翻译结果不是 JSON: {}", reply))?;
+    serde_json::from_str(json).map_err(|e| anyhow::anyhow!("识别+翻译结果解析失败: {}", e))
+}
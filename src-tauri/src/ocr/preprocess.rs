@@ -0,0 +1,481 @@
+use super::structured::{BoundingBox, OcrLayout};
+use super::OCR_MAX_LONGEST_EDGE;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, GrayImage, ImageFormat, Luma, Rgba, RgbaImage};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Largest upscale factor applied by `Upscale`; beyond this the model sees
+/// interpolation blur rather than more detail.
+const MAX_UPSCALE: f64 = 4.0;
+/// Mean luminance below which `InvertDark` treats the crop as dark mode.
+const DARK_MODE_LUMA: f64 = 110.0;
+/// A row belongs to a text line when at least this share of its pixels is ink.
+const INK_ROW_RATIO: f64 = 0.005;
+/// Largest border `Pad` adds on each side. Custom profiles are user-edited,
+/// and a few hundred pixels is already far more margin than OCR needs.
+const MAX_PAD: u32 = 256;
+
+/// One image operation. Steps run in order, so e.g. `invert_dark` should come
+/// before `binarize`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreprocessStep {
+    /// Upscale so the estimated text line height reaches `min_text_height` px.
+    Upscale {
+        min_text_height: u32,
+    },
+    Grayscale,
+    /// Stretch the luminance range, clipping `cutoff` percent at each end.
+    AutoContrast {
+        cutoff: f32,
+    },
+    /// Otsu threshold to black text on a white background.
+    Binarize,
+    /// Invert dark-mode crops (light text on dark background).
+    InvertDark,
+    /// Add a border of the surrounding background colour, at most
+    /// `MAX_PAD` px per side.
+    Pad {
+        pixels: u32,
+    },
+}
+
+/// Named chain of steps, selected by `OcrConfig.preprocess_profile`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreprocessProfile {
+    pub name: String,
+    pub steps: Vec<PreprocessStep>,
+    /// Built-in profiles are supplied by the app and not persisted.
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl PreprocessProfile {
+    fn builtin(name: &str, steps: Vec<PreprocessStep>) -> Self {
+        Self {
+            name: name.to_string(),
+            steps,
+            builtin: true,
+        }
+    }
+}
+
+pub fn builtin_profiles() -> Vec<PreprocessProfile> {
+    use PreprocessStep::*;
+    vec![
+        PreprocessProfile::builtin(
+            "small_text",
+            vec![
+                Upscale {
+                    min_text_height: 24,
+                },
+                AutoContrast { cutoff: 1.0 },
+            ],
+        ),
+        PreprocessProfile::builtin(
+            "dark_mode",
+            vec![InvertDark, Grayscale, AutoContrast { cutoff: 1.0 }],
+        ),
+        PreprocessProfile::builtin(
+            "low_contrast",
+            vec![Grayscale, AutoContrast { cutoff: 2.0 }, Pad { pixels: 8 }],
+        ),
+        PreprocessProfile::builtin(
+            "tesseract",
+            vec![
+                InvertDark,
+                Upscale {
+                    min_text_height: 32,
+                },
+                Grayscale,
+                AutoContrast { cutoff: 1.0 },
+                Binarize,
+                Pad { pixels: 16 },
+            ],
+        ),
+    ]
+}
+
+/// Built-in profiles followed by custom ones; a custom profile with the same
+/// name replaces the built-in.
+pub fn all_profiles(custom: &[PreprocessProfile]) -> Vec<PreprocessProfile> {
+    let mut profiles: Vec<PreprocessProfile> = builtin_profiles()
+        .into_iter()
+        .filter(|b| !custom.iter().any(|c| c.name == b.name))
+        .collect();
+    profiles.extend(custom.iter().cloned().map(|mut c| {
+        c.builtin = false;
+        c
+    }));
+    profiles
+}
+
+pub fn find_profile(custom: &[PreprocessProfile], name: &str) -> Option<PreprocessProfile> {
+    all_profiles(custom).into_iter().find(|p| p.name == name)
+}
+
+/// Geometry change of the pipeline: `processed = original * scale + offset`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PreprocessTransform {
+    pub scale: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+impl PreprocessTransform {
+    fn identity(width: u32, height: u32) -> Self {
+        Self {
+            scale: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            original_width: width,
+            original_height: height,
+        }
+    }
+
+    fn map_box(&self, bbox: BoundingBox) -> BoundingBox {
+        let x1 = ((bbox.x - self.offset_x) / self.scale).clamp(0.0, self.original_width as f64);
+        let y1 = ((bbox.y - self.offset_y) / self.scale).clamp(0.0, self.original_height as f64);
+        let x2 = ((bbox.x + bbox.width - self.offset_x) / self.scale)
+            .clamp(0.0, self.original_width as f64);
+        let y2 = ((bbox.y + bbox.height - self.offset_y) / self.scale)
+            .clamp(0.0, self.original_height as f64);
+        BoundingBox {
+            x: x1,
+            y: y1,
+            width: x2 - x1,
+            height: y2 - y1,
+        }
+    }
+
+    /// Map a layout recognized on the processed image back to original crop
+    /// pixels.
+    pub fn unmap_layout(&self, mut layout: OcrLayout) -> OcrLayout {
+        for block in &mut layout.blocks {
+            block.bbox = self.map_box(block.bbox);
+            for line in &mut block.lines {
+                line.bbox = self.map_box(line.bbox);
+                for word in &mut line.words {
+                    word.bbox = self.map_box(word.bbox);
+                }
+            }
+        }
+        layout.width = self.original_width;
+        layout.height = self.original_height;
+        layout
+    }
+}
+
+/// Result of running a profile: PNG bytes (lossless, so binarized edges stay
+/// sharp) plus the geometry needed to map boxes back.
+pub struct PreprocessedImage {
+    pub png_bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub transform: PreprocessTransform,
+}
+
+/// Run `profile` off the async runtime.
+pub async fn run(
+    image_bytes: &[u8],
+    profile: &PreprocessProfile,
+) -> anyhow::Result<PreprocessedImage> {
+    let owned_bytes = image_bytes.to_vec();
+    let profile = profile.clone();
+    tokio::task::spawn_blocking(move || apply_profile(&owned_bytes, &profile)).await?
+}
+
+/// Decode, run every step of `profile` and re-encode as PNG. Blocking.
+pub fn apply_profile(
+    image_bytes: &[u8],
+    profile: &PreprocessProfile,
+) -> anyhow::Result<PreprocessedImage> {
+    let mut img = image::load_from_memory(image_bytes)?;
+    let (width, height) = img.dimensions();
+    let mut transform = PreprocessTransform::identity(width, height);
+
+    for step in &profile.steps {
+        img = apply_step(img, step, &mut transform);
+    }
+
+    let (out_width, out_height) = img.dimensions();
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, ImageFormat::Png)?;
+    info!(
+        "[OCR] 预处理 profile={}, {}x{} -> {}x{}, scale={:.2}, steps={}",
+        profile.name,
+        width,
+        height,
+        out_width,
+        out_height,
+        transform.scale,
+        profile.steps.len()
+    );
+    Ok(PreprocessedImage {
+        png_bytes: buf.into_inner(),
+        width: out_width,
+        height: out_height,
+        transform,
+    })
+}
+
+fn apply_step(
+    img: DynamicImage,
+    step: &PreprocessStep,
+    transform: &mut PreprocessTransform,
+) -> DynamicImage {
+    match step {
+        PreprocessStep::Upscale { min_text_height } => upscale(img, *min_text_height, transform),
+        PreprocessStep::Grayscale => DynamicImage::ImageLuma8(img.to_luma8()),
+        PreprocessStep::AutoContrast { cutoff } => auto_contrast(img, *cutoff),
+        PreprocessStep::Binarize => DynamicImage::ImageLuma8(binarize(&img.to_luma8())),
+        PreprocessStep::InvertDark => invert_dark(img),
+        PreprocessStep::Pad { pixels } => {
+            let pixels = (*pixels).min(MAX_PAD);
+            match pad(img, pixels) {
+                Ok(padded) => {
+                    transform.offset_x += pixels as f64;
+                    transform.offset_y += pixels as f64;
+                    padded
+                }
+                Err(img) => img,
+            }
+        }
+    }
+}
+
+fn upscale(
+    img: DynamicImage,
+    min_text_height: u32,
+    transform: &mut PreprocessTransform,
+) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let text_height = estimate_text_height(&img.to_luma8()).unwrap_or(height as f64);
+    // Never upscale past the upload limit; `prepare` would only shrink it again.
+    let max_factor = OCR_MAX_LONGEST_EDGE as f64 / width.max(height).max(1) as f64;
+    let factor = (min_text_height as f64 / text_height.max(1.0))
+        .min(MAX_UPSCALE)
+        .min(max_factor);
+    if factor <= 1.05 {
+        return img;
+    }
+    info!(
+        "[OCR] 预处理 upscale, 估计文字高度={:.1}px, 放大 {:.2}x",
+        text_height, factor
+    );
+    let new_width = ((width as f64 * factor).round() as u32).max(1);
+    let new_height = ((height as f64 * factor).round() as u32).max(1);
+    transform.scale *= factor;
+    transform.offset_x *= factor;
+    transform.offset_y *= factor;
+    img.resize_exact(new_width, new_height, FilterType::CatmullRom)
+}
+
+/// Median height of horizontal ink bands (text lines), from the row
+/// projection of the Otsu-binarized image.
+fn estimate_text_height(gray: &GrayImage) -> Option<f64> {
+    let threshold = otsu_threshold(gray);
+    let (width, height) = gray.dimensions();
+    let dark = gray.pixels().filter(|p| p[0] <= threshold).count();
+    // Ink is the minority class.
+    let ink_is_dark = dark * 2 <= (width * height) as usize;
+    let min_ink = ((width as f64 * INK_ROW_RATIO).ceil() as usize).max(1);
+
+    let mut runs = Vec::new();
+    let mut run = 0u32;
+    for y in 0..height {
+        let ink = (0..width)
+            .filter(|&x| (gray.get_pixel(x, y)[0] <= threshold) == ink_is_dark)
+            .count();
+        if ink >= min_ink {
+            run += 1;
+        } else if run > 0 {
+            runs.push(run);
+            run = 0;
+        }
+    }
+    if run > 0 {
+        runs.push(run);
+    }
+    runs.retain(|&r| r >= 2);
+    if runs.is_empty() {
+        return None;
+    }
+    runs.sort_unstable();
+    Some(runs[runs.len() / 2] as f64)
+}
+
+fn auto_contrast(img: DynamicImage, cutoff: f32) -> DynamicImage {
+    let gray = img.to_luma8();
+    let mut histogram = [0u64; 256];
+    for p in gray.pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+    let total = gray.pixels().len() as f64;
+    let clip = (total * cutoff.clamp(0.0, 49.0) as f64 / 100.0) as u64;
+    let percentile = |from_top: bool| {
+        let mut acc = 0u64;
+        let indices: Box<dyn Iterator<Item = usize>> = if from_top {
+            Box::new((0..256).rev())
+        } else {
+            Box::new(0..256)
+        };
+        for i in indices {
+            acc += histogram[i];
+            if acc > clip {
+                return i as f64;
+            }
+        }
+        if from_top {
+            255.0
+        } else {
+            0.0
+        }
+    };
+    let (low, high) = (percentile(false), percentile(true));
+    if high - low < 1.0 {
+        return img;
+    }
+    let stretch = |v: u8| (((v as f64 - low) * 255.0 / (high - low)).clamp(0.0, 255.0)) as u8;
+
+    match img {
+        DynamicImage::ImageLuma8(mut gray) => {
+            gray.pixels_mut().for_each(|p| p[0] = stretch(p[0]));
+            DynamicImage::ImageLuma8(gray)
+        }
+        other => {
+            let mut rgba = other.to_rgba8();
+            for p in rgba.pixels_mut() {
+                for c in 0..3 {
+                    p[c] = stretch(p[c]);
+                }
+            }
+            DynamicImage::ImageRgba8(rgba)
+        }
+    }
+}
+
+fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for p in gray.pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+    let total = gray.pixels().len() as f64;
+    let sum_all: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| i as f64 * n as f64)
+        .sum();
+
+    let (mut sum_bg, mut weight_bg) = (0.0, 0.0);
+    let (mut best, mut best_variance) = (128u8, 0.0);
+    for (i, &n) in histogram.iter().enumerate() {
+        weight_bg += n as f64;
+        if weight_bg == 0.0 {
+            continue;
+        }
+        let weight_fg = total - weight_bg;
+        if weight_fg == 0.0 {
+            break;
+        }
+        sum_bg += i as f64 * n as f64;
+        let mean_bg = sum_bg / weight_bg;
+        let mean_fg = (sum_all - sum_bg) / weight_fg;
+        let variance = weight_bg * weight_fg * (mean_bg - mean_fg).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best = i as u8;
+        }
+    }
+    best
+}
+
+/// Otsu binarization, oriented so the minority class (text) ends up black.
+fn binarize(gray: &GrayImage) -> GrayImage {
+    let threshold = otsu_threshold(gray);
+    let dark = gray.pixels().filter(|p| p[0] <= threshold).count();
+    let text_is_dark = dark * 2 <= gray.pixels().len();
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let is_dark = gray.get_pixel(x, y)[0] <= threshold;
+        if is_dark == text_is_dark {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    })
+}
+
+fn invert_dark(mut img: DynamicImage) -> DynamicImage {
+    let gray = img.to_luma8();
+    let mean = gray.pixels().map(|p| p[0] as f64).sum::<f64>() / gray.pixels().len().max(1) as f64;
+    if mean < DARK_MODE_LUMA {
+        info!("[OCR] 预处理 invert_dark, 平均亮度={:.0}, 反色", mean);
+        img.invert();
+    }
+    img
+}
+
+/// Pad with the per-channel median of the outermost pixels, so the border
+/// blends with the background instead of framing the text. Hands the image
+/// back unpadded when the padded size would not fit in `u32`.
+fn pad(img: DynamicImage, pixels: u32) -> Result<DynamicImage, DynamicImage> {
+    if pixels == 0 {
+        return Ok(img);
+    }
+    let (width, height) = img.dimensions();
+    let padded = |side: u32| {
+        pixels
+            .checked_mul(2)
+            .and_then(|border| side.checked_add(border))
+    };
+    let (Some(padded_width), Some(padded_height)) = (padded(width), padded(height)) else {
+        info!(
+            "[OCR] 预处理 pad 跳过, {}x{} 加 {}px 边距超出范围",
+            width, height, pixels
+        );
+        return Err(img);
+    };
+    let rgba = img.to_rgba8();
+    let edge: Vec<Rgba<u8>> = rgba
+        .enumerate_pixels()
+        .filter(|(x, y, _)| *x == 0 || *y == 0 || *x + 1 == width || *y + 1 == height)
+        .map(|(_, _, p)| *p)
+        .collect();
+    let median = |c: usize| {
+        let mut values: Vec<u8> = edge.iter().map(|p| p[c]).collect();
+        values.sort_unstable();
+        values.get(values.len() / 2).copied().unwrap_or(255)
+    };
+    let background = Rgba([median(0), median(1), median(2), 255]);
+
+    let mut canvas = RgbaImage::from_pixel(padded_width, padded_height, background);
+    image::imageops::overlay(&mut canvas, &rgba, pixels as i64, pixels as i64);
+    Ok(match img {
+        DynamicImage::ImageLuma8(_) => {
+            DynamicImage::ImageLuma8(DynamicImage::ImageRgba8(canvas).to_luma8())
+        }
+        _ => DynamicImage::ImageRgba8(canvas),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_is_clamped_from_custom_profiles() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 3, Rgba([255; 4])));
+        let mut transform = PreprocessTransform::identity(4, 3);
+        let step = PreprocessStep::Pad { pixels: u32::MAX };
+        let padded = apply_step(img, &step, &mut transform);
+        assert_eq!(padded.dimensions(), (4 + 2 * MAX_PAD, 3 + 2 * MAX_PAD));
+        assert_eq!(
+            (transform.offset_x, transform.offset_y),
+            (MAX_PAD as f64, MAX_PAD as f64)
+        );
+    }
+}
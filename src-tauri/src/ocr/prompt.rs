@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+/// Id prefix used for OCR mode entries in `hotkey-action` payloads and
+/// region-select modes, e.g. `"ocr:markdown"`.
+pub const OCR_MODE_EVENT_PREFIX: &str = "ocr:";
+
+/// Output format requested from the OCR model.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrMode {
+    /// Plain text, no markup (the original behavior).
+    #[default]
+    Plain,
+    /// Markdown with pipe tables.
+    Markdown,
+    /// LaTeX for formulas, prose as plain text.
+    Latex,
+    /// Source code / terminal output with indentation preserved.
+    Code,
+}
+
+impl OcrMode {
+    pub const ALL: [OcrMode; 4] = [
+        OcrMode::Plain,
+        OcrMode::Markdown,
+        OcrMode::Latex,
+        OcrMode::Code,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            OcrMode::Plain => "plain",
+            OcrMode::Markdown => "markdown",
+            OcrMode::Latex => "latex",
+            OcrMode::Code => "code",
+        }
+    }
+
+    /// Built-in output instruction; user overrides in `OcrConfig.prompts`
+    /// replace it.
+    pub fn default_instruction(self) -> &'static str {
+        match self {
+            OcrMode::Plain => PLAIN_OUTPUT,
+            OcrMode::Markdown => MARKDOWN_OUTPUT,
+            OcrMode::Latex => LATEX_OUTPUT,
+            OcrMode::Code => CODE_OUTPUT,
+        }
+    }
+}
+
+/// Prompt used for `"auto"` and unknown languages (the original fixed prompt).
+const AUTO_PROMPT: &str =
+    "请识别图片中的所有文字，只输出纯文本。不要使用Markdown、HTML或其他标记语言，表格内容按行列用纯文本输出。";
+
+const PLAIN_OUTPUT: &str = "Output ONLY the recognized text as plain text. Do not use Markdown, \
+HTML or other markup; output table content row by row as plain text. Do not translate, \
+explain or add anything.";
+
+const MARKDOWN_OUTPUT: &str = "Output the text as Markdown that mirrors the visual layout: \
+headings, bulleted and numbered lists, bold or italic only where clearly visible, and tables as \
+GitHub-flavored pipe tables with a header row and one row per table row. Output ONLY the \
+Markdown, not wrapped in a code fence, without translating, explaining or adding anything.";
+
+const LATEX_OUTPUT: &str = "Output the content as LaTeX. Write every mathematical expression \
+in LaTeX math: inline formulas in $...$ and displayed equations in $$...$$, using standard \
+commands (\\frac, \\sum, \\int, \\mathbf, matrices with \\begin{pmatrix}...). Keep ordinary \
+prose as plain text. Output ONLY the LaTeX, not wrapped in a code fence, without explanations.";
+
+const CODE_OUTPUT: &str = "The image shows source code or terminal output. Transcribe it \
+exactly: preserve indentation width, line breaks, blank lines, brackets, operators and \
+symbols. Do not fix, reformat, complete or comment the code. Output ONLY the code, not \
+wrapped in a Markdown code fence.";
+
+const MIXED_SCRIPT: &str = "The image may also contain text in other languages or scripts \
+(for example English UI labels, numbers, URLs or code). Transcribe that text exactly as it \
+appears instead of translating or transliterating it.";
+
+/// Language and script specific guidance for the expected source language.
+/// Returns `None` for `"auto"` and unknown codes.
+pub fn language_hint(language: &str) -> Option<&'static str> {
+    Some(match language {
+        "zh-CN" => {
+            "The text is mainly Simplified Chinese. Keep Simplified characters; never convert them \
+             to Traditional. Text may be set vertically: read vertical columns top to bottom, \
+             columns from right to left, and output each column as one line. Use full-width \
+             Chinese punctuation as shown."
+        }
+        "zh-TW" => {
+            "The text is mainly Traditional Chinese. Keep Traditional characters; never convert \
+             them to Simplified. Text may be set vertically: read vertical columns top to bottom, \
+             columns from right to left, and output each column as one line."
+        }
+        "ja" => {
+            "The text is mainly Japanese (kanji, hiragana, katakana). Distinguish similar kana \
+             carefully (e.g. ソ/ン, シ/ツ, ー/一) and keep small kana (ゃ, ッ) small. Text may be \
+             written vertically (tategaki): read columns top to bottom, from right to left, and \
+             output each column as one line. Omit furigana (small ruby readings beside kanji)."
+        }
+        "ko" => {
+            "The text is mainly Korean (Hangul, possibly with Hanja). Keep the original word \
+             spacing. Vertical text reads top to bottom, columns from right to left."
+        }
+        "ar" => {
+            "The text is mainly Arabic, written right to left. Output it in logical reading \
+             order (as it would be typed), never visually reversed. Keep embedded left-to-right \
+             runs such as numbers, Latin words and URLs in their natural order. Preserve \
+             diacritics (harakat) if present and keep Arabic-Indic digits as shown."
+        }
+        "th" => {
+            "The text is mainly Thai. Thai does not put spaces between words; do not insert \
+             spaces that are not in the image. Keep all vowel and tone marks."
+        }
+        "vi" => {
+            "The text is mainly Vietnamese. Preserve every diacritic and tone mark exactly \
+             (e.g. ă, â, ê, ô, ơ, ư, đ and the five tone marks)."
+        }
+        "ru" => {
+            "The text is mainly Russian in Cyrillic script. Do not transliterate to Latin, and do \
+             not confuse Cyrillic letters with similar Latin ones."
+        }
+        "fr" | "de" | "es" | "pt" | "it" => {
+            "The text is mainly in a Latin-script European language. Preserve all accents and \
+             special letters (é, è, ç, ñ, ß, ä, ö, ü, ã, õ...), and keep the language's own \
+             quotation marks and punctuation."
+        }
+        "en" => "The text is mainly English.",
+        _ => return None,
+    })
+}
+
+/// OCR prompt for the expected source language and output mode.
+/// `custom` (the user's override for this mode) replaces the built-in output
+/// instruction; language hints are still added. Plain mode with `"auto"` and
+/// no override keeps the original Chinese instruction.
+pub fn recognition_prompt(language: &str, mode: OcrMode, custom: Option<&str>) -> String {
+    let custom = custom.map(str::trim).filter(|c| !c.is_empty());
+    let hint = language_hint(language);
+    if mode == OcrMode::Plain && hint.is_none() && custom.is_none() {
+        return AUTO_PROMPT.to_string();
+    }
+    let instruction = custom.unwrap_or_else(|| mode.default_instruction());
+    match hint {
+        Some(hint) => format!(
+            "Recognize all text in this image. {}\n{}\n{}",
+            hint, MIXED_SCRIPT, instruction
+        ),
+        None => format!("Recognize all text in this image.\n{}", instruction),
+    }
+}
+
+/// Remove a Markdown code fence wrapping the whole reply, which models add
+/// despite being told not to. Plain mode output is returned unchanged.
+pub fn clean_output(mode: OcrMode, text: &str) -> String {
+    if mode == OcrMode::Plain {
+        return text.to_string();
+    }
+    let trimmed = text.trim();
+    if let Some(rest) = trimmed.strip_prefix("```") {
+        if let Some(body) = rest.strip_suffix("```") {
+            // Drop the info string (e.g. "python") on the opening line.
+            return match body.split_once('\n') {
+                Some((_, code)) => code.trim_end().to_string(),
+                None => body.trim().to_string(),
+            };
+        }
+    }
+    text.to_string()
+}
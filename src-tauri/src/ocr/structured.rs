@@ -0,0 +1,266 @@
+use super::{prepare, prompt, send_vision_request, OcrCache, PreparedOcrImage};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Axis-aligned box in crop pixel coordinates (origin top-left).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    fn from_corners(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        let (left, right) = if x1 <= x2 { (x1, x2) } else { (x2, x1) };
+        let (top, bottom) = if y1 <= y2 { (y1, y2) } else { (y2, y1) };
+        Self {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+
+    /// Smallest box containing all `boxes`.
+    pub(crate) fn union(boxes: impl IntoIterator<Item = BoundingBox>) -> Option<Self> {
+        boxes.into_iter().reduce(|a, b| {
+            let x1 = a.x.min(b.x);
+            let y1 = a.y.min(b.y);
+            let x2 = (a.x + a.width).max(b.x + b.width);
+            let y2 = (a.y + a.height).max(b.y + b.height);
+            BoundingBox::from_corners(x1, y1, x2, y2)
+        })
+    }
+
+    /// Scale by (sx, sy) and clamp into `[0, max_w] x [0, max_h]`.
+    fn scale_clamped(&self, sx: f64, sy: f64, max_w: f64, max_h: f64) -> Self {
+        let x1 = (self.x * sx).clamp(0.0, max_w);
+        let y1 = (self.y * sy).clamp(0.0, max_h);
+        let x2 = ((self.x + self.width) * sx).clamp(0.0, max_w);
+        let y2 = ((self.y + self.height) * sy).clamp(0.0, max_h);
+        BoundingBox::from_corners(x1, y1, x2, y2)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub bbox: BoundingBox,
+    /// Recognition confidence in 0..1.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub bbox: BoundingBox,
+    /// Word boxes; only the tesseract engine reports them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<OcrWord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrBlock {
+    /// 0-based reading order.
+    pub order: usize,
+    pub text: String,
+    /// Language code reported by the model (e.g. "ja", "en"), empty if unknown.
+    pub language: String,
+    pub bbox: BoundingBox,
+    pub lines: Vec<OcrLine>,
+}
+
+/// Structured OCR result. Coordinates are in pixels of the original crop
+/// passed to `recognize_structured`, not of the downscaled upload.
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrLayout {
+    pub width: u32,
+    pub height: u32,
+    pub blocks: Vec<OcrBlock>,
+}
+
+impl OcrLayout {
+    /// Plain text of all blocks in reading order, one block per paragraph.
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(|b| b.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Raw model output, before coordinate mapping.
+#[derive(Debug, Deserialize)]
+struct RawLayout {
+    #[serde(default)]
+    blocks: Vec<RawBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawBlock {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    bbox: Vec<f64>,
+    #[serde(default)]
+    lines: Vec<RawLine>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLine {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    bbox: Vec<f64>,
+}
+
+fn structured_prompt(width: u32, height: u32, language: &str) -> String {
+    let hint = prompt::language_hint(language)
+        .map(|hint| format!("\n{}", hint))
+        .unwrap_or_default();
+    format!(
+        "Recognize all text in this image ({width}x{height} pixels) and return its layout as JSON.\n\
+         Group text into blocks (paragraphs, labels, buttons, table cells) listed in natural reading order.\n\
+         Respond with ONLY a JSON object of this exact shape, no Markdown fences or commentary:\n\
+         {{\"blocks\":[{{\"text\":\"full block text\",\"language\":\"ISO 639-1 code\",\"bbox\":[x1,y1,x2,y2],\
+         \"lines\":[{{\"text\":\"line text\",\"bbox\":[x1,y1,x2,y2]}}]}}]}}\n\
+         bbox values are integer pixel coordinates in this image: (x1,y1) top-left, (x2,y2) bottom-right, \
+         with 0 <= x <= {width} and 0 <= y <= {height}.{hint}"
+    )
+}
+
+/// Structured OCR: text blocks and lines with bounding boxes, reading order and
+/// per-block language, via a JSON-schema prompt to the vision model.
+/// Returned coordinates are mapped back through the upload downscale.
+/// `language` adds the same script hints as plain OCR (`"auto"` adds none).
+#[allow(clippy::too_many_arguments)]
+pub async fn recognize_structured(
+    client: &reqwest::Client,
+    image_bytes: &[u8],
+    language: &str,
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    extra: &str,
+    cache: Option<&OcrCache>,
+) -> anyhow::Result<OcrLayout> {
+    let prepared = prepare(image_bytes).await?;
+    info!(
+        "[OCR] 结构化识别, image {}x{} -> {}x{}",
+        prepared.original_width, prepared.original_height, prepared.width, prepared.height
+    );
+    let prompt = structured_prompt(prepared.width, prepared.height, language);
+    let layout = send_vision_request(
+        client,
+        &prepared,
+        &prompt,
+        base_url,
+        api_key,
+        model,
+        extra,
+        "OCR",
+        cache,
+        |reply| parse_layout(reply, &prepared),
+    )
+    .await?;
+    info!("[OCR] 结构化识别完成, blocks={}", layout.blocks.len());
+    Ok(layout)
+}
+
+/// Parse the model's JSON reply and map boxes to original crop pixels.
+fn parse_layout(reply: &str, prepared: &PreparedOcrImage) -> anyhow::Result<OcrLayout> {
+    let json = extract_json_object(reply)
+        .ok_or_else(|| anyhow::anyhow!("OCR 结构化结果不是 JSON: {}", reply))?;
+    let raw: RawLayout =
+        serde_json::from_str(json).map_err(|e| anyhow::anyhow!("OCR 结构化结果解析失败: {}", e))?;
+
+    let upload_w = prepared.width.max(1) as f64;
+    let upload_h = prepared.height.max(1) as f64;
+    let out_w = prepared.original_width as f64;
+    let out_h = prepared.original_height as f64;
+
+    // Some models answer in 0..1 normalized coordinates despite the prompt.
+    let coords: Vec<f64> = raw
+        .blocks
+        .iter()
+        .flat_map(|b| {
+            b.bbox
+                .iter()
+                .chain(b.lines.iter().flat_map(|l| l.bbox.iter()))
+        })
+        .copied()
+        .collect();
+    let normalized = !coords.is_empty() && coords.iter().all(|&v| v <= 1.0);
+    let (sx, sy) = if normalized {
+        warn!("[OCR] 模型返回了归一化坐标，按 0..1 映射");
+        (out_w, out_h)
+    } else {
+        (out_w / upload_w, out_h / upload_h)
+    };
+    let map = |bbox: &[f64]| -> Option<BoundingBox> {
+        match bbox {
+            [x1, y1, x2, y2] => Some(
+                BoundingBox::from_corners(*x1, *y1, *x2, *y2).scale_clamped(sx, sy, out_w, out_h),
+            ),
+            _ => None,
+        }
+    };
+
+    let blocks = raw
+        .blocks
+        .into_iter()
+        .filter(|b| !b.text.trim().is_empty() || !b.lines.is_empty())
+        .enumerate()
+        .map(|(order, block)| {
+            let lines: Vec<OcrLine> = block
+                .lines
+                .iter()
+                .filter(|l| !l.text.trim().is_empty())
+                .filter_map(|l| {
+                    map(l.bbox.as_slice()).map(|bbox| OcrLine {
+                        text: l.text.trim().to_string(),
+                        bbox,
+                        words: Vec::new(),
+                    })
+                })
+                .collect();
+            let bbox = map(block.bbox.as_slice())
+                .or_else(|| BoundingBox::union(lines.iter().map(|l| l.bbox)))
+                .unwrap_or_default();
+            let text = if block.text.trim().is_empty() {
+                lines
+                    .iter()
+                    .map(|l| l.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                block.text.trim().to_string()
+            };
+            OcrBlock {
+                order,
+                text,
+                language: block.language.trim().to_string(),
+                bbox,
+                lines,
+            }
+        })
+        .collect();
+
+    Ok(OcrLayout {
+        width: prepared.original_width,
+        height: prepared.original_height,
+        blocks,
+    })
+}
+
+/// Strip Markdown fences / chatter around the first top-level JSON object.
+pub(crate) fn extract_json_object(reply: &str) -> Option<&str> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    (end > start).then(|| &reply[start..=end])
+}
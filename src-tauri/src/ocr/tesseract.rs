@@ -0,0 +1,443 @@
+use super::structured::{BoundingBox, OcrBlock, OcrLayout, OcrLine, OcrWord};
+use log::info;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// Executable used when `OcrConfig.tesseract_path` is empty.
+const DEFAULT_BINARY: &str = "tesseract";
+
+/// Languages tried for `"auto"`, filtered by what is installed.
+const AUTO_LANGUAGES: &[&str] = &["chi_sim", "eng"];
+
+/// TSV row level for a single word (1 page, 2 block, 3 paragraph, 4 line).
+const TSV_WORD_LEVEL: u32 = 5;
+
+/// Map an app language code to its traineddata name.
+pub fn traineddata_for(language: &str) -> Option<&'static str> {
+    Some(match language {
+        "zh-CN" => "chi_sim",
+        "zh-TW" => "chi_tra",
+        "en" => "eng",
+        "ja" => "jpn",
+        "ko" => "kor",
+        "fr" => "fra",
+        "de" => "deu",
+        "es" => "spa",
+        "pt" => "por",
+        "ru" => "rus",
+        "ar" => "ara",
+        "it" => "ita",
+        "th" => "tha",
+        "vi" => "vie",
+        _ => return None,
+    })
+}
+
+fn binary(path: &str) -> &str {
+    if path.trim().is_empty() {
+        DEFAULT_BINARY
+    } else {
+        path.trim()
+    }
+}
+
+fn command(binary: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(binary);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+/// Installed traineddata names per binary, so `--list-langs` runs once per
+/// binary instead of on every OCR call.
+static INSTALLED_LANGUAGES: OnceLock<Mutex<HashMap<String, Vec<String>>>> = OnceLock::new();
+
+/// Cached `installed_languages`. `refresh` re-reads the list, e.g. after a
+/// language pack was found missing (the user may have just installed it).
+fn cached_languages(binary: &str, refresh: bool) -> anyhow::Result<Vec<String>> {
+    let cache = INSTALLED_LANGUAGES.get_or_init(Default::default);
+    if !refresh {
+        if let Some(langs) = cache.lock().ok().and_then(|c| c.get(binary).cloned()) {
+            return Ok(langs);
+        }
+    }
+    let langs = installed_languages(binary)?;
+    info!("[Tesseract] 已安装语言包: {}", langs.join(", "));
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(binary.to_string(), langs.clone());
+    }
+    Ok(langs)
+}
+
+/// Installed traineddata names, from `tesseract --list-langs`.
+fn installed_languages(binary: &str) -> anyhow::Result<Vec<String>> {
+    let output = command(binary).arg("--list-langs").output().map_err(|e| {
+        anyhow::anyhow!(
+            "无法运行 tesseract（{}）: {}，请确认已安装或在设置中填写路径",
+            binary,
+            e
+        )
+    })?;
+    // Older versions print the list to stderr.
+    let listing = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    } else {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    Ok(listing
+        .lines()
+        .skip(1) // "List of available languages in ...:"
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Resolve the `-l` argument. Non-English languages also load `eng`, since
+/// screenshots routinely mix English UI strings into other scripts.
+fn language_arg(binary: &str, language: &str) -> anyhow::Result<String> {
+    let mut installed = cached_languages(binary, false)?;
+    let stale = match traineddata_for(language) {
+        Some(primary) => !installed.iter().any(|l| l == primary),
+        None => language == "auto" && installed.is_empty(),
+    };
+    if stale {
+        installed = cached_languages(binary, true)?;
+    }
+    let has = |name: &str| installed.iter().any(|l| l == name);
+
+    let wanted: Vec<&str> = if language == "auto" {
+        AUTO_LANGUAGES.iter().copied().filter(|l| has(l)).collect()
+    } else {
+        let primary = traineddata_for(language)
+            .ok_or_else(|| anyhow::anyhow!("tesseract 不支持语言: {}", language))?;
+        if !has(primary) {
+            anyhow::bail!(
+                "tesseract 缺少语言包 {}.traineddata（{}），请安装后重试",
+                primary,
+                language
+            );
+        }
+        let mut langs = vec![primary];
+        if primary != "eng" && has("eng") {
+            langs.push("eng");
+        }
+        langs
+    };
+    if wanted.is_empty() {
+        anyhow::bail!("tesseract 未安装可用的语言包");
+    }
+    Ok(wanted.join("+"))
+}
+
+/// Run tesseract on image bytes piped through stdin; `output` is the config
+/// name after `stdout` (empty for plain text, `tsv` for boxes).
+fn run(binary: &str, image_bytes: &[u8], languages: &str, output: &str) -> anyhow::Result<String> {
+    let mut cmd = command(binary);
+    // preserve_interword_spaces keeps tesseract from inserting spaces
+    // between CJK characters.
+    cmd.args([
+        "stdin",
+        "stdout",
+        "-l",
+        languages,
+        "--psm",
+        "3",
+        "-c",
+        "preserve_interword_spaces=1",
+    ]);
+    if !output.is_empty() {
+        cmd.arg(output);
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("无法运行 tesseract（{}）: {}", binary, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(image_bytes)?;
+    }
+    let result = child.wait_with_output()?;
+    if !result.status.success() {
+        anyhow::bail!(
+            "tesseract 退出码 {:?}: {}",
+            result.status.code(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&result.stdout).into_owned())
+}
+
+/// Plain-text OCR with the local tesseract CLI.
+pub async fn recognize(
+    image_bytes: &[u8],
+    language: &str,
+    tesseract_path: &str,
+) -> anyhow::Result<String> {
+    let owned_bytes = image_bytes.to_vec();
+    let language = language.to_string();
+    let binary = binary(tesseract_path).to_string();
+    tokio::task::spawn_blocking(move || {
+        let languages = language_arg(&binary, &language)?;
+        info!(
+            "[Tesseract] 识别开始, binary={}, -l {}, image bytes={}",
+            binary,
+            languages,
+            owned_bytes.len()
+        );
+        let text = run(&binary, &owned_bytes, &languages, "")?;
+        let text = text.trim().to_string();
+        info!("[Tesseract] 识别完成, 结果长度={}", text.len());
+        Ok(text)
+    })
+    .await?
+}
+
+/// Structured OCR with word boxes, parsed from tesseract's TSV output.
+/// Blocks are tesseract paragraphs in its reading order.
+pub async fn recognize_structured(
+    image_bytes: &[u8],
+    language: &str,
+    tesseract_path: &str,
+) -> anyhow::Result<OcrLayout> {
+    let owned_bytes = image_bytes.to_vec();
+    let language = language.to_string();
+    let binary = binary(tesseract_path).to_string();
+    tokio::task::spawn_blocking(move || {
+        let (width, height) = image::load_from_memory(&owned_bytes)
+            .map(|img| (img.width(), img.height()))
+            .unwrap_or_default();
+        let languages = language_arg(&binary, &language)?;
+        info!(
+            "[Tesseract] 结构化识别开始, binary={}, -l {}, image {}x{}",
+            binary, languages, width, height
+        );
+        let tsv = run(&binary, &owned_bytes, &languages, "tsv")?;
+        let layout = parse_tsv(&tsv, width, height, &language);
+        info!("[Tesseract] 结构化识别完成, blocks={}", layout.blocks.len());
+        Ok(layout)
+    })
+    .await?
+}
+
+/// Group TSV word rows into paragraphs (blocks) and lines. Rows are
+/// `level page block par line word left top width height conf text`.
+fn parse_tsv(tsv: &str, width: u32, height: u32, language: &str) -> OcrLayout {
+    type LineKey = (u32, u32, u32, u32);
+    let mut lines: BTreeMap<LineKey, Vec<OcrWord>> = BTreeMap::new();
+
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        if cols.len() < 12 {
+            continue;
+        }
+        let int = |i: usize| cols[i].trim().parse::<u32>().ok();
+        let num = |i: usize| {
+            cols[i]
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+        };
+        let text = cols[11].trim();
+        if int(0) != Some(TSV_WORD_LEVEL) || text.is_empty() {
+            continue;
+        }
+        // Rows with unusable ids or geometry are dropped rather than placed
+        // at a made-up position; confidence -1 marks non-word rows.
+        let (Some(page), Some(block), Some(par), Some(line)) = (int(1), int(2), int(3), int(4))
+        else {
+            continue;
+        };
+        let (Some(x), Some(y), Some(width), Some(height), Some(confidence)) =
+            (num(6), num(7), num(8), num(9), num(10))
+        else {
+            continue;
+        };
+        lines
+            .entry((page, block, par, line))
+            .or_default()
+            .push(OcrWord {
+                text: text.to_string(),
+                bbox: BoundingBox {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+                confidence: confidence / 100.0,
+            });
+    }
+
+    let mut paragraphs: BTreeMap<(u32, u32, u32), Vec<OcrLine>> = BTreeMap::new();
+    for ((page, block, par, _), words) in lines {
+        let text = join_words(&words);
+        let bbox = BoundingBox::union(words.iter().map(|w| w.bbox)).unwrap_or_default();
+        paragraphs
+            .entry((page, block, par))
+            .or_default()
+            .push(OcrLine { text, bbox, words });
+    }
+
+    let blocks = paragraphs
+        .into_values()
+        .enumerate()
+        .map(|(order, lines)| OcrBlock {
+            order,
+            text: lines
+                .iter()
+                .map(|l| l.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            language: if language == "auto" {
+                String::new()
+            } else {
+                language.to_string()
+            },
+            bbox: BoundingBox::union(lines.iter().map(|l| l.bbox)).unwrap_or_default(),
+            lines,
+        })
+        .collect();
+
+    OcrLayout {
+        width,
+        height,
+        blocks,
+    }
+}
+
+/// Join a line's words. CJK and Thai text is not space-separated, but
+/// tesseract still splits it into per-character "words", so the space is
+/// dropped between two such words. Decided from the recognized characters
+/// rather than the requested language, so `"auto"` and mixed lines work.
+fn join_words(words: &[OcrWord]) -> String {
+    let mut text = String::new();
+    for word in words {
+        let unspaced = |c: Option<char>| c.is_some_and(is_unspaced_script);
+        let glued = unspaced(text.chars().last()) && unspaced(word.text.chars().next());
+        if !text.is_empty() && !glued {
+            text.push(' ');
+        }
+        text.push_str(&word.text);
+    }
+    text
+}
+
+/// Han, kana, CJK punctuation / full-width forms and Thai.
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0E7F}'   // Thai
+        | '\u{3000}'..='\u{30FF}' // CJK punctuation, hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+        | '\u{FF00}'..='\u{FFEF}' // half-width / full-width forms
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH_TSV: &str = include_str!("../../tests/fixtures/tesseract/english.tsv");
+    const CJK_TSV: &str = include_str!("../../tests/fixtures/tesseract/cjk.tsv");
+
+    #[test]
+    fn parses_words_into_lines_and_paragraphs() {
+        let layout = parse_tsv(ENGLISH_TSV, 520, 140, "en");
+        assert_eq!((layout.width, layout.height), (520, 140));
+        assert_eq!(layout.blocks.len(), 1);
+
+        let block = &layout.blocks[0];
+        assert_eq!(block.order, 0);
+        assert_eq!(block.language, "en");
+        assert_eq!(block.text, "Hello Tesseract\nOffline OCR 2048");
+        assert_eq!(block.lines.len(), 2);
+        assert_eq!(block.lines[1].words.len(), 3);
+
+        let first = &block.lines[0];
+        assert_eq!(first.text, "Hello Tesseract");
+        assert_eq!(first.words[0].text, "Hello");
+        assert!((first.words[0].confidence - 0.9652).abs() < 1e-9);
+        // Line box is the union of its word boxes.
+        assert_eq!(first.bbox.x, 24.0);
+        assert_eq!(first.bbox.y, 20.0);
+        assert_eq!(first.bbox.width, 264.0);
+        assert_eq!(first.bbox.height, 31.0);
+        // Block box covers both lines.
+        assert_eq!(block.bbox.y, 20.0);
+        assert_eq!(block.bbox.y + block.bbox.height, 107.0);
+    }
+
+    #[test]
+    fn joins_cjk_without_spaces_in_auto_mode() {
+        let layout = parse_tsv(CJK_TSV, 600, 200, "auto");
+        assert_eq!(layout.blocks.len(), 2);
+        // Empty / negative-confidence word rows are skipped.
+        assert_eq!(layout.blocks[0].lines[0].words.len(), 4);
+        assert_eq!(layout.blocks[0].text, "你好世界");
+        // Latin words keep their spaces next to CJK; kana joins directly.
+        assert_eq!(layout.blocks[1].text, "使用 Python 编程。テスト");
+        // Auto mode does not claim a language.
+        assert!(layout.blocks.iter().all(|b| b.language.is_empty()));
+        assert_eq!(
+            layout.blocks.iter().map(|b| b.order).collect::<Vec<_>>(),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn ignores_malformed_rows() {
+        let tsv = "level\tpage_num\n\
+                   5\t1\t1\n\
+                   \n\
+                   5\t1\t1\t1\t1\t1\tx\t0\t10\t10\t90\tbroken\n\
+                   5\t1\t1\t1\t1\t2\t4\t0\tNaN\t10\t90\tnan\n\
+                   5\t1\t1\t1\t1\t3\t12\t0\t10\t10\t90\tword\n";
+        let layout = parse_tsv(tsv, 30, 10, "en");
+        assert_eq!(layout.blocks.len(), 1);
+        // Rows whose geometry does not parse are dropped, not placed at -1.
+        assert_eq!(layout.blocks[0].text, "word");
+        assert_eq!(layout.blocks[0].lines[0].words.len(), 1);
+        assert_eq!(layout.blocks[0].bbox.x, 12.0);
+    }
+
+    #[test]
+    fn maps_app_languages_to_traineddata() {
+        assert_eq!(traineddata_for("zh-CN"), Some("chi_sim"));
+        assert_eq!(traineddata_for("ja"), Some("jpn"));
+        assert_eq!(traineddata_for("auto"), None);
+        assert_eq!(traineddata_for("xx"), None);
+    }
+
+    /// Runs the real binary (`TESSERACT_PATH` or `tesseract` on PATH) with
+    /// the English language pack installed:
+    /// `cargo test -- --ignored tesseract`
+    #[tokio::test]
+    #[ignore = "needs a local tesseract install"]
+    async fn recognizes_fixture_with_real_binary() {
+        let binary = std::env::var("TESSERACT_PATH").unwrap_or_default();
+        let png = include_bytes!("../../tests/fixtures/tesseract/hello.png");
+
+        let text = recognize(png, "en", &binary).await.unwrap();
+        assert!(text.contains("Hello Tesseract"), "got {:?}", text);
+        assert!(text.contains("2048"), "got {:?}", text);
+
+        let layout = recognize_structured(png, "en", &binary).await.unwrap();
+        assert_eq!((layout.width, layout.height), (520, 140));
+        let words: Vec<&str> = layout
+            .blocks
+            .iter()
+            .flat_map(|b| b.lines.iter().flat_map(|l| l.words.iter()))
+            .map(|w| w.text.as_str())
+            .collect();
+        assert!(words.contains(&"Tesseract"), "got {:?}", words);
+        assert!(words.contains(&"OCR"), "got {:?}", words);
+    }
+}
@@ -0,0 +1,551 @@
+use super::structured::{BoundingBox, OcrBlock, OcrLayout, OcrLine};
+use super::OCR_MAX_LONGEST_EDGE;
+use image::{GenericImageView, ImageFormat};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::Cursor;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Tile length along each axis; matches the upload limit so tiles are
+/// sent at native resolution.
+const TILE_LENGTH: u32 = OCR_MAX_LONGEST_EDGE;
+/// Pixels shared by neighbouring tiles, enough for a line cut by one seam to
+/// appear whole in the other tile.
+const TILE_OVERLAP: u32 = 160;
+/// `auto` tiles crops whose long/short edge ratio is at least this.
+const AUTO_MIN_ASPECT: f64 = 2.0;
+/// Tiles recognized at the same time.
+const MAX_CONCURRENT_TILES: usize = 3;
+/// Lines compared when removing duplicates at a text seam.
+const MAX_SEAM_LINES: usize = 8;
+
+/// When large crops are split into tiles instead of being downscaled.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrTiling {
+    /// Tile crops over the upload limit that are also long and narrow
+    /// (chat logs, full-page captures).
+    #[default]
+    Auto,
+    /// Tile every crop over the upload limit.
+    Always,
+    /// Never tile; large crops are downscaled.
+    Off,
+}
+
+/// One tile, encoded as PNG. `keep_x` / `keep_y` are the spans (in
+/// full-image pixels) whose results this tile owns; seams sit in the middle
+/// of each overlap.
+pub struct Tile {
+    pub png_bytes: Vec<u8>,
+    x: u32,
+    y: u32,
+    row: usize,
+    column: usize,
+    keep_x: (f64, f64),
+    keep_y: (f64, f64),
+}
+
+pub struct TilePlan {
+    /// Whether the image is taller than wide; text is merged along the long
+    /// axis.
+    pub vertical: bool,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major.
+    pub tiles: Vec<Tile>,
+}
+
+fn should_tile(width: u32, height: u32, tiling: OcrTiling) -> bool {
+    let longest = width.max(height);
+    if longest <= TILE_LENGTH {
+        return false;
+    }
+    match tiling {
+        OcrTiling::Off => false,
+        OcrTiling::Always => true,
+        OcrTiling::Auto => longest as f64 / width.min(height).max(1) as f64 >= AUTO_MIN_ASPECT,
+    }
+}
+
+/// Start offsets of tiles covering `length` pixels: the fewest tiles that
+/// keep at least `TILE_OVERLAP` between neighbours, spread evenly.
+fn tile_starts(length: u32) -> Vec<u32> {
+    if length <= TILE_LENGTH {
+        return vec![0];
+    }
+    let step = TILE_LENGTH - TILE_OVERLAP;
+    let count = (length - TILE_OVERLAP).div_ceil(step);
+    let last = (length - TILE_LENGTH) as u64;
+    (0..count)
+        .map(|i| (last * i as u64 / (count - 1) as u64) as u32)
+        .collect()
+}
+
+/// One axis of the grid: `(start, end, keep)` per tile. An axis that fits in
+/// `TILE_LENGTH` is a single span, so every tile stays within the upload
+/// limit on both sides.
+fn spans(length: u32) -> Vec<(u32, u32, (f64, f64))> {
+    let starts = tile_starts(length);
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = (start + TILE_LENGTH).min(length);
+            let keep_start = match i {
+                0 => 0.0,
+                _ => (start as f64 + (starts[i - 1] + TILE_LENGTH) as f64) / 2.0,
+            };
+            let keep_end = match starts.get(i + 1) {
+                Some(&next) => (next as f64 + end as f64) / 2.0,
+                None => length as f64,
+            };
+            (start, end, (keep_start, keep_end))
+        })
+        .collect()
+}
+
+/// Split image bytes into a grid of tiles, or `None` when `tiling` says the
+/// image should be recognized whole. Blocking.
+fn plan(image_bytes: &[u8], tiling: OcrTiling) -> anyhow::Result<Option<TilePlan>> {
+    let img = image::load_from_memory(image_bytes)?;
+    let (width, height) = img.dimensions();
+    if !should_tile(width, height, tiling) {
+        return Ok(None);
+    }
+    let (rows, columns) = (spans(height), spans(width));
+
+    let mut tiles = Vec::with_capacity(rows.len() * columns.len());
+    for (row, &(y0, y1, keep_y)) in rows.iter().enumerate() {
+        for (column, &(x0, x1, keep_x)) in columns.iter().enumerate() {
+            let mut buf = Cursor::new(Vec::new());
+            img.crop_imm(x0, y0, x1 - x0, y1 - y0)
+                .write_to(&mut buf, ImageFormat::Png)?;
+            tiles.push(Tile {
+                png_bytes: buf.into_inner(),
+                x: x0,
+                y: y0,
+                row,
+                column,
+                keep_x,
+                keep_y,
+            });
+        }
+    }
+    info!(
+        "[OCR] 分块识别, image {}x{}, 切分为 {} 行 × {} 列",
+        width,
+        height,
+        rows.len(),
+        columns.len()
+    );
+    Ok(Some(TilePlan {
+        vertical: height >= width,
+        width,
+        height,
+        tiles,
+    }))
+}
+
+/// `plan` off the async runtime.
+pub async fn split(image_bytes: &[u8], tiling: OcrTiling) -> anyhow::Result<Option<TilePlan>> {
+    if tiling == OcrTiling::Off {
+        return Ok(None);
+    }
+    let owned_bytes = image_bytes.to_vec();
+    tokio::task::spawn_blocking(move || plan(&owned_bytes, tiling)).await?
+}
+
+/// Run `recognize` on every tile, at most `MAX_CONCURRENT_TILES` at a time.
+/// Results come back in tile order; the first failure aborts the rest.
+pub async fn recognize_tiles<T, F, Fut>(tiles: &[Tile], recognize: F) -> anyhow::Result<Vec<T>>
+where
+    T: Send + 'static,
+    F: Fn(Vec<u8>) -> Fut,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_TILES));
+    let mut tasks = JoinSet::new();
+    for (index, tile) in tiles.iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let future = recognize(tile.png_bytes.clone());
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await?;
+            let started = std::time::Instant::now();
+            let result = future.await;
+            info!(
+                "[OCR] 第 {} 块识别完成, 耗时 {}ms",
+                index,
+                started.elapsed().as_millis()
+            );
+            result.map(|value| (index, value))
+        });
+    }
+
+    let mut results: Vec<Option<T>> = (0..tiles.len()).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        let (index, value) = joined??;
+        results[index] = Some(value);
+    }
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Lines compared with whitespace removed, since models space the same text
+/// differently in each tile.
+fn normalize(line: &str) -> String {
+    line.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Whether two seam lines are the same line, allowing one to be a cut-off
+/// part of the other.
+fn same_line(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    let (short, long) = if a.chars().count() <= b.chars().count() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    long.contains(short) && short.chars().count() * 5 >= long.chars().count() * 3
+}
+
+/// Join per-tile texts in tile order. Tiles are chained along the long axis
+/// (each column of a tall image, each row of a wide one) with
+/// `merge_strip`; the chains are then joined in order. The plain-text reply
+/// has no positions, so lines cut by a cross-axis seam stay split.
+pub fn merge_text(plan: &TilePlan, texts: &[String]) -> String {
+    let strip_of = |tile: &Tile| if plan.vertical { tile.column } else { tile.row };
+    let strips = plan
+        .tiles
+        .iter()
+        .map(strip_of)
+        .max()
+        .map_or(0, |last| last + 1);
+    (0..strips)
+        .map(|strip| {
+            let chain: Vec<String> = plan
+                .tiles
+                .iter()
+                .zip(texts)
+                .filter(|(tile, _)| strip_of(tile) == strip)
+                .map(|(_, text)| text.clone())
+                .collect();
+            merge_strip(&chain)
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Join texts of consecutive tiles along one axis, dropping lines at the
+/// start of each tile that repeat the end of the previous one. Of each
+/// duplicate pair the longer copy is kept, since the seam may cut a line in
+/// one of the tiles.
+fn merge_strip(texts: &[String]) -> String {
+    let mut merged: Vec<String> = Vec::new();
+    for text in texts {
+        let lines: Vec<&str> = text.lines().collect();
+        // (index, normalized) of the last non-empty merged lines, in order.
+        let mut tail: Vec<(usize, String)> = merged
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .take(MAX_SEAM_LINES)
+            .map(|(i, l)| (i, normalize(l)))
+            .collect();
+        tail.reverse();
+        let head: Vec<(usize, String)> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .take(MAX_SEAM_LINES)
+            .map(|(i, l)| (i, normalize(l)))
+            .collect();
+
+        // Longest k where the previous tile's last k lines match this tile's
+        // first k lines.
+        let mut skip = 0;
+        for k in (1..=tail.len().min(head.len())).rev() {
+            let shared = &tail[tail.len() - k..];
+            if shared
+                .iter()
+                .zip(&head[..k])
+                .all(|((_, a), (_, b))| same_line(a, b))
+            {
+                for ((merged_index, a), (line_index, b)) in shared.iter().zip(&head[..k]) {
+                    if b.chars().count() > a.chars().count() {
+                        merged[*merged_index] = lines[*line_index].to_string();
+                    }
+                }
+                skip = head[k - 1].0 + 1;
+                break;
+            }
+        }
+        merged.extend(lines[skip..].iter().map(|l| l.to_string()));
+    }
+    merged.join("\n").trim().to_string()
+}
+
+fn shift(bbox: BoundingBox, tile: &Tile) -> BoundingBox {
+    BoundingBox {
+        x: bbox.x + tile.x as f64,
+        y: bbox.y + tile.y as f64,
+        ..bbox
+    }
+}
+
+fn center(bbox: &BoundingBox) -> (f64, f64) {
+    (bbox.x + bbox.width / 2.0, bbox.y + bbox.height / 2.0)
+}
+
+/// Merge per-tile layouts into one in full-image coordinates. Each line is
+/// kept only by the tile that owns its centre, which removes the copies in
+/// the overlaps; blocks are rebuilt from their remaining lines.
+pub fn merge_layouts(plan: &TilePlan, layouts: Vec<OcrLayout>) -> OcrLayout {
+    let mut blocks = Vec::new();
+    for (tile, layout) in plan.tiles.iter().zip(layouts) {
+        let owns = |bbox: &BoundingBox| {
+            let (cx, cy) = center(bbox);
+            (tile.keep_x.0..tile.keep_x.1).contains(&cx)
+                && (tile.keep_y.0..tile.keep_y.1).contains(&cy)
+        };
+        for block in layout.blocks {
+            let block_bbox = shift(block.bbox, tile);
+            if block.lines.is_empty() {
+                if owns(&block_bbox) {
+                    blocks.push(OcrBlock {
+                        bbox: block_bbox,
+                        ..block
+                    });
+                }
+                continue;
+            }
+            let total_lines = block.lines.len();
+            let lines: Vec<OcrLine> = block
+                .lines
+                .into_iter()
+                .map(|mut line| {
+                    line.bbox = shift(line.bbox, tile);
+                    for word in &mut line.words {
+                        word.bbox = shift(word.bbox, tile);
+                    }
+                    line
+                })
+                .filter(|line| owns(&line.bbox))
+                .collect();
+            if lines.is_empty() {
+                continue;
+            }
+            let (text, bbox) = if lines.len() == total_lines {
+                (block.text, block_bbox)
+            } else {
+                (
+                    lines
+                        .iter()
+                        .map(|l| l.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    BoundingBox::union(lines.iter().map(|l| l.bbox)).unwrap_or_default(),
+                )
+            };
+            blocks.push(OcrBlock {
+                text,
+                bbox,
+                lines,
+                ..block
+            });
+        }
+    }
+    for (order, block) in blocks.iter_mut().enumerate() {
+        block.order = order;
+    }
+    OcrLayout {
+        width: plan.width,
+        height: plan.height,
+        blocks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_plan(width: u32, height: u32) -> TilePlan {
+        let (rows, columns) = (spans(height), spans(width));
+        let mut tiles = Vec::new();
+        for (row, &(y, _, keep_y)) in rows.iter().enumerate() {
+            for (column, &(x, _, keep_x)) in columns.iter().enumerate() {
+                tiles.push(Tile {
+                    png_bytes: Vec::new(),
+                    x,
+                    y,
+                    row,
+                    column,
+                    keep_x,
+                    keep_y,
+                });
+            }
+        }
+        TilePlan {
+            vertical: height >= width,
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    fn bbox(x: f64, y: f64, width: f64, height: f64) -> BoundingBox {
+        BoundingBox {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// One single-line block per `(text, bbox)`, in tile coordinates.
+    fn layout(lines: &[(&str, BoundingBox)]) -> OcrLayout {
+        OcrLayout {
+            width: TILE_LENGTH,
+            height: TILE_LENGTH,
+            blocks: lines
+                .iter()
+                .enumerate()
+                .map(|(order, &(text, bbox))| OcrBlock {
+                    order,
+                    text: text.to_string(),
+                    language: String::new(),
+                    bbox,
+                    lines: vec![OcrLine {
+                        text: text.to_string(),
+                        bbox,
+                        words: Vec::new(),
+                    }],
+                })
+                .collect(),
+        }
+    }
+
+    fn texts(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn tiles_both_axes_within_the_upload_limit() {
+        assert!(should_tile(2400, 5000, OcrTiling::Auto));
+        assert!(should_tile(4000, 3000, OcrTiling::Always));
+        assert!(!should_tile(4000, 3000, OcrTiling::Auto));
+        assert!(!should_tile(2048, 8000, OcrTiling::Off));
+
+        for length in [1000, TILE_LENGTH, 2400, 3000, 5000, 12_345] {
+            let spans = spans(length);
+            assert_eq!(spans[0].0, 0);
+            assert_eq!(spans.last().unwrap().1, length);
+            assert_eq!(spans[0].2 .0, 0.0);
+            assert_eq!(spans.last().unwrap().2 .1, length as f64);
+            for (i, &(start, end, keep)) in spans.iter().enumerate() {
+                assert!(end - start <= TILE_LENGTH, "{length}: {start}..{end}");
+                assert!(keep.0 >= start as f64 && keep.1 <= end as f64);
+                if let Some(next) = spans.get(i + 1) {
+                    assert!(end - next.0 >= TILE_OVERLAP, "{length}: overlap too small");
+                    assert_eq!(keep.1, next.2 .0);
+                }
+            }
+        }
+        // 2400 wide needs two columns instead of a 2400px tile.
+        assert_eq!(spans(2400).len(), 2);
+        assert_eq!(grid_plan(2400, 5000).tiles.len(), 2 * 3);
+    }
+
+    #[test]
+    fn merge_strip_drops_repeated_seam_lines() {
+        let merged = merge_strip(&texts(&[
+            "First line\nSecond line\nThird line is lo",
+            "Third line is  long\nFourth line",
+        ]));
+        assert_eq!(
+            merged,
+            "First line\nSecond line\nThird line is  long\nFourth line"
+        );
+
+        let merged = merge_strip(&texts(&["Alpha\nBeta\nGamma", "Beta\nGamma\nDelta"]));
+        assert_eq!(merged, "Alpha\nBeta\nGamma\nDelta");
+    }
+
+    #[test]
+    fn merge_strip_keeps_text_without_overlap() {
+        let merged = merge_strip(&texts(&["Alpha\nBeta", "Gamma", ""]));
+        assert_eq!(merged, "Alpha\nBeta\nGamma");
+        // A short fragment is not mistaken for a cut copy of a longer line.
+        let merged = merge_strip(&texts(&["The quick brown fox", "fox\njumps"]));
+        assert_eq!(merged, "The quick brown fox\nfox\njumps");
+    }
+
+    #[test]
+    fn merge_text_chains_columns_of_a_tall_grid() {
+        let plan = grid_plan(2400, 3000);
+        assert_eq!(plan.tiles.len(), 4);
+        // Row-major: (r0, c0), (r0, c1), (r1, c0), (r1, c1).
+        let merged = merge_text(&plan, &texts(&["L1\nL2", "R1", "L2\nL3", "R1\nR2"]));
+        assert_eq!(merged, "L1\nL2\nL3\nR1\nR2");
+    }
+
+    #[test]
+    fn merge_layouts_keeps_each_line_once_across_both_seams() {
+        // Rows and columns start at 0 / 952, so both seams sit at 1500.
+        let plan = grid_plan(3000, 3000);
+        let layouts = vec![
+            // (r0, c0): A inside the column overlap, B inside the row overlap.
+            layout(&[
+                ("A", bbox(1440.0, 100.0, 40.0, 30.0)),
+                ("B", bbox(500.0, 1440.0, 100.0, 30.0)),
+            ]),
+            // (r0, c1): the other copy of A.
+            layout(&[("A", bbox(488.0, 100.0, 40.0, 30.0))]),
+            // (r1, c0): the other copy of B.
+            layout(&[("B", bbox(500.0, 488.0, 100.0, 30.0))]),
+            // (r1, c1): C only appears here.
+            layout(&[("C", bbox(1048.0, 1548.0, 100.0, 30.0))]),
+        ];
+        let merged = merge_layouts(&plan, layouts);
+        assert_eq!((merged.width, merged.height), (3000, 3000));
+        let found: Vec<(&str, usize, BoundingBox)> = merged
+            .blocks
+            .iter()
+            .map(|b| (b.text.as_str(), b.order, b.lines[0].bbox))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("A", 0, bbox(1440.0, 100.0, 40.0, 30.0)),
+                ("B", 1, bbox(500.0, 1440.0, 100.0, 30.0)),
+                ("C", 2, bbox(2000.0, 2500.0, 100.0, 30.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_layouts_rebuilds_blocks_split_by_a_seam() {
+        let plan = grid_plan(1000, 3000);
+        let mut top = layout(&[("kept", bbox(10.0, 1400.0, 200.0, 30.0))]);
+        top.blocks[0].lines.push(OcrLine {
+            text: "below seam".to_string(),
+            bbox: bbox(10.0, 1600.0, 200.0, 30.0),
+            words: Vec::new(),
+        });
+        top.blocks[0].text = "kept\nbelow seam".to_string();
+        let bottom = layout(&[("below seam", bbox(10.0, 648.0, 200.0, 30.0))]);
+        let merged = merge_layouts(&plan, vec![top, bottom]);
+        let texts: Vec<&str> = merged.blocks.iter().map(|b| b.text.as_str()).collect();
+        assert_eq!(texts, vec!["kept", "below seam"]);
+        assert_eq!(merged.blocks[0].bbox, bbox(10.0, 1400.0, 200.0, 30.0));
+    }
+}
@@ -0,0 +1,35 @@
+use log::info;
+use std::path::Path;
+
+/// Write bytes to `path`, creating missing parent directories.
+///
+/// Data is first written to a sibling temp file and then renamed into place,
+/// so a crash or full disk never leaves a half-written document/screenshot
+/// behind under the final name.
+pub fn write_file(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("无效的输出路径: {}", path.display()))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    std::fs::write(&tmp_path, bytes)?;
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+
+    info!(
+        "[Output] 文件已写入: {}, size={}",
+        path.display(),
+        bytes.len()
+    );
+    Ok(())
+}
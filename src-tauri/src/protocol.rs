@@ -0,0 +1,178 @@
+use crate::config::AppState;
+use log::{info, warn};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+/// Custom URI scheme serving frozen screenshots to the overlay windows, so
+/// multi-megabyte images do not travel through IPC as base64 JSON.
+pub const SCHEME: &str = "transshot";
+
+/// URL of a monitor's frozen screenshot. `generation` identifies the capture
+/// session, so an overlay left over from a previous capture never receives
+/// the new image under the old URL.
+pub fn frozen_screenshot_url(monitor_index: usize, generation: u64) -> String {
+    scheme_url(&format!("frozen/{}?v={}", monitor_index, generation))
+}
+
+/// URL of a pin window's image (see `commands::pin`).
+pub fn pin_image_url(label: &str) -> String {
+    scheme_url(&format!("pin/{}", label))
+}
+
+fn scheme_url(path: &str) -> String {
+    // WebView2 and Android only route custom schemes as http://<scheme>.localhost.
+    #[cfg(any(windows, target_os = "android"))]
+    {
+        format!("http://{}.localhost/{}", SCHEME, path)
+    }
+    #[cfg(not(any(windows, target_os = "android")))]
+    {
+        format!("{}://localhost/{}", SCHEME, path)
+    }
+}
+
+/// Handler registered for `SCHEME`. Encoding runs off the main thread.
+pub fn handle<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .and_then(|value| value.to_str().ok())
+        .filter(|origin| is_app_origin(&app, origin))
+        .and_then(|origin| header::HeaderValue::from_str(origin).ok());
+    if origin.is_none() && request.headers().contains_key(header::ORIGIN) {
+        warn!(
+            "[Protocol] 拒绝跨域请求: {:?}",
+            request.headers().get(header::ORIGIN)
+        );
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut response = respond(&app, &path, query.as_deref());
+        // The overlay / pin pages are a different origin than the scheme;
+        // only the app's own pages may read the pixels through fetch().
+        if let Some(origin) = origin {
+            response
+                .headers_mut()
+                .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        }
+        response
+            .headers_mut()
+            .insert(header::VARY, header::HeaderValue::from_static("origin"));
+        responder.respond(response);
+    });
+}
+
+/// Whether `origin` is one of the app's own pages: the bundled frontend
+/// (`tauri://localhost`, or `http(s)://tauri.localhost` on WebView2 and
+/// Android) or, in development builds, the configured `devUrl`.
+fn is_app_origin<R: Runtime>(app: &AppHandle<R>, origin: &str) -> bool {
+    if matches!(
+        origin,
+        "tauri://localhost" | "http://tauri.localhost" | "https://tauri.localhost"
+    ) {
+        return true;
+    }
+    tauri::is_dev()
+        && app
+            .config()
+            .build
+            .dev_url
+            .as_ref()
+            .is_some_and(|url| url.origin().ascii_serialization() == origin)
+}
+
+fn respond<R: Runtime>(app: &AppHandle<R>, path: &str, query: Option<&str>) -> Response<Vec<u8>> {
+    if let Some(label) = path.strip_prefix("/pin/") {
+        return respond_pin(app, label);
+    }
+    let Some(monitor_index) = path
+        .strip_prefix("/frozen/")
+        .and_then(|index| index.parse::<usize>().ok())
+    else {
+        warn!("[Protocol] 未知路径: {}", path);
+        return text_response(StatusCode::NOT_FOUND, "Not found");
+    };
+
+    let state = app.state::<AppState>();
+    let requested_generation = query
+        .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("v=")))
+        .and_then(|v| v.parse::<u64>().ok());
+    let generation = state.frozen_generation.load(Ordering::Acquire);
+    if requested_generation.is_some_and(|v| v != generation) {
+        warn!(
+            "[Protocol] 截图已过期, monitor_index={}, requested={:?}, current={}",
+            monitor_index, requested_generation, generation
+        );
+        return text_response(StatusCode::GONE, "Screenshot expired");
+    }
+
+    let frozen = match crate::commands::ocr::frozen_screenshot(&state, monitor_index) {
+        Ok(frozen) => frozen,
+        Err(e) => return text_response(StatusCode::NOT_FOUND, &e),
+    };
+    let started = Instant::now();
+    match crate::screenshot::encode_png_fast(&frozen) {
+        Ok(png) => {
+            info!(
+                "[Protocol] 返回冻结截图, monitor_index={}, size={}x{}, png size={}, 耗时 {}ms",
+                monitor_index,
+                frozen.width(),
+                frozen.height(),
+                png.len(),
+                started.elapsed().as_millis()
+            );
+            png_response(png)
+        }
+        Err(e) => text_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn respond_pin<R: Runtime>(app: &AppHandle<R>, label: &str) -> Response<Vec<u8>> {
+    let state = app.state::<AppState>();
+    let image = match crate::commands::pin::pinned_image(&state, label) {
+        Ok(image) => image,
+        Err(e) => return text_response(StatusCode::NOT_FOUND, &e),
+    };
+    match crate::screenshot::encode_png_fast(&image) {
+        Ok(png) => {
+            info!(
+                "[Protocol] 返回贴图, label={}, size={}x{}, png size={}",
+                label,
+                image.width(),
+                image.height(),
+                png.len()
+            );
+            png_response(png)
+        }
+        Err(e) => text_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn png_response(png: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/png")
+        .header(header::CONTENT_LENGTH, png.len())
+        // Each image is fetched once; keep megabytes of pixels out of the
+        // WebView's HTTP cache.
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(png)
+        .unwrap_or_default()
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_default()
+}
@@ -0,0 +1,885 @@
+use super::fonts::{self, FontSet};
+use super::{blend, char_advance, line_height, luminance};
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+use anyhow::Context;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Version of the annotation model; written to sidecar files.
+pub const ANNOTATION_VERSION: u32 = 1;
+
+/// Suffix appended to the image file name for its sidecar
+/// (`shot.png` → `shot.png.annotations.json`).
+const SIDECAR_SUFFIX: &str = ".annotations.json";
+
+/// Arrowhead length relative to the stroke width, and its minimum.
+const ARROW_HEAD_RATIO: f32 = 5.0;
+const ARROW_HEAD_MIN: f32 = 14.0;
+
+/// Mosaic block size when the shape does not specify one: 1/40 of the
+/// shorter image side, at least this many pixels.
+const MIN_MOSAIC_BLOCK: u32 = 6;
+
+/// Text line height relative to the font size (matches the editor).
+const TEXT_LINE_HEIGHT: f32 = 1.2;
+
+/// Upper bounds for sizes taken from the model. The editor stays far below
+/// them; they keep a hand-written or corrupted sidecar from overflowing the
+/// padding maths or spending minutes in the blur / glyph rasterizer.
+const MAX_STROKE_WIDTH: f32 = 200.0;
+const MAX_FONT_SIZE: f32 = 512.0;
+const MAX_STEP_RADIUS: f32 = 256.0;
+const MAX_BLUR_SIGMA: f32 = 100.0;
+const MAX_MOSAIC_BLOCK: u32 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// One annotation. Coordinates and sizes are pixels of the annotated image
+/// (the cropped capture), origin at its top-left corner; colours are
+/// `#rrggbb` or `#rrggbbaa`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Shape {
+    #[serde(rename_all = "camelCase")]
+    Rect {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: String,
+        stroke_width: f32,
+        /// Corner radius; clamped to half the shorter side.
+        #[serde(default)]
+        radius: f32,
+    },
+    /// Ellipse inscribed in the box.
+    #[serde(rename_all = "camelCase")]
+    Ellipse {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: String,
+        stroke_width: f32,
+    },
+    /// Line from (x1, y1) with a filled head at (x2, y2).
+    #[serde(rename_all = "camelCase")]
+    Arrow {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        color: String,
+        stroke_width: f32,
+    },
+    /// Freehand stroke with round caps and joins.
+    #[serde(rename_all = "camelCase")]
+    Pen {
+        points: Vec<Point>,
+        color: String,
+        stroke_width: f32,
+    },
+    /// Text whose top-left corner is at (x, y); `\n` starts a new line.
+    #[serde(rename_all = "camelCase")]
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        color: String,
+        font_size: f32,
+        #[serde(default)]
+        bold: bool,
+    },
+    /// Numbered marker: a filled circle centred at (x, y) with `number` in it.
+    #[serde(rename_all = "camelCase")]
+    Step {
+        x: f32,
+        y: f32,
+        number: u32,
+        color: String,
+        radius: f32,
+    },
+    /// Pixelates the capture under a brush stroke.
+    #[serde(rename_all = "camelCase")]
+    Mosaic {
+        points: Vec<Point>,
+        stroke_width: f32,
+        #[serde(default)]
+        block_size: Option<u32>,
+    },
+    /// Gaussian blur of the capture inside the box; `radius` is the standard
+    /// deviation, like CSS `blur()`.
+    #[serde(rename_all = "camelCase")]
+    Blur {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+    },
+}
+
+/// Shapes drawn over a capture, bottom to top.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub shapes: Vec<Shape>,
+}
+
+fn default_version() -> u32 {
+    ANNOTATION_VERSION
+}
+
+/// Sidecar stored next to an annotated image: the unannotated capture and
+/// the shapes, so the capture can be reopened for editing.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnnotationDocument {
+    pub version: u32,
+    /// File name of the rendered image this sidecar belongs to.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    /// The capture without annotations, base64 PNG.
+    pub base: String,
+    pub annotation: Annotation,
+}
+
+/// A reopened annotated capture, pasted into the frozen screenshot of one
+/// monitor so the overlay can edit it like a fresh selection.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrozenEdit {
+    pub monitor_index: usize,
+    /// Where the capture was pasted, in image pixels of that monitor's
+    /// frozen screenshot.
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub annotation: Annotation,
+    /// Image the edited result is written back to.
+    pub path: String,
+}
+
+/// Draw `annotation` over a copy of `base`. Mosaic and blur always sample
+/// `base`, so they hide the captured content but never smear annotations
+/// drawn before them.
+pub fn render_annotation(base: &RgbaImage, annotation: &Annotation) -> anyhow::Result<RgbaImage> {
+    let mut out = base.clone();
+    for shape in &annotation.shapes {
+        draw_shape(&mut out, base, shape)?;
+    }
+    Ok(out)
+}
+
+fn draw_shape(img: &mut RgbaImage, base: &RgbaImage, shape: &Shape) -> anyhow::Result<()> {
+    match shape {
+        Shape::Rect {
+            x,
+            y,
+            w,
+            h,
+            color,
+            stroke_width,
+            radius,
+        } => {
+            let color = parse_color(color)?;
+            let (min, max) = normalize_box(*x, *y, *w, *h);
+            let half = stroke_half(*stroke_width);
+            let center = Point {
+                x: (min.x + max.x) / 2.0,
+                y: (min.y + max.y) / 2.0,
+            };
+            let extent = Point {
+                x: (max.x - min.x) / 2.0,
+                y: (max.y - min.y) / 2.0,
+            };
+            let radius = limit(*radius, 0.0, extent.x.min(extent.y));
+            if let Some(mut mask) = Mask::around(img, min, max, half) {
+                mask.cover(min, max, half, |px, py| {
+                    let d = round_box_distance(px - center.x, py - center.y, extent, radius);
+                    coverage(d.abs() - half)
+                });
+                fill(img, &mask, color);
+            }
+        }
+        Shape::Ellipse {
+            x,
+            y,
+            w,
+            h,
+            color,
+            stroke_width,
+        } => {
+            let color = parse_color(color)?;
+            let (min, max) = normalize_box(*x, *y, *w, *h);
+            let half = stroke_half(*stroke_width);
+            let center = Point {
+                x: (min.x + max.x) / 2.0,
+                y: (min.y + max.y) / 2.0,
+            };
+            let rx = ((max.x - min.x) / 2.0).max(0.5);
+            let ry = ((max.y - min.y) / 2.0).max(0.5);
+            if let Some(mut mask) = Mask::around(img, min, max, half) {
+                mask.cover(min, max, half, |px, py| {
+                    let d = ellipse_distance(px - center.x, py - center.y, rx, ry);
+                    coverage(d.abs() - half)
+                });
+                fill(img, &mask, color);
+            }
+        }
+        Shape::Arrow {
+            x1,
+            y1,
+            x2,
+            y2,
+            color,
+            stroke_width,
+        } => {
+            let color = parse_color(color)?;
+            let half = stroke_half(*stroke_width);
+            let tail = Point { x: *x1, y: *y1 };
+            let tip = Point { x: *x2, y: *y2 };
+            let head_len = (half * 2.0 * ARROW_HEAD_RATIO).max(ARROW_HEAD_MIN);
+            let angle = (tip.y - tail.y).atan2(tip.x - tail.x);
+            let wing = |offset: f32| Point {
+                x: tip.x - head_len * (angle + offset).cos(),
+                y: tip.y - head_len * (angle + offset).sin(),
+            };
+            let head = [
+                tip,
+                wing(-std::f32::consts::FRAC_PI_6),
+                wing(std::f32::consts::FRAC_PI_6),
+            ];
+            let (min, max) = bounds(head.iter().copied().chain([tail]));
+            if let Some(mut mask) = Mask::around(img, min, max, half) {
+                mask.stroke_segment(tail, tip, half);
+                let (head_min, head_max) = bounds(head.iter().copied());
+                mask.cover(head_min, head_max, 0.0, |px, py| {
+                    coverage(triangle_distance(Point { x: px, y: py }, &head))
+                });
+                fill(img, &mask, color);
+            }
+        }
+        Shape::Pen {
+            points,
+            color,
+            stroke_width,
+        } => {
+            let color = parse_color(color)?;
+            let half = stroke_half(*stroke_width);
+            if let Some(mask) = stroke_mask(img, points, half) {
+                fill(img, &mask, color);
+            }
+        }
+        Shape::Text {
+            x,
+            y,
+            text,
+            color,
+            font_size,
+            bold,
+        } => {
+            let color = parse_color(color)?;
+            let fonts = system_fonts()?;
+            let px = limit(*font_size, 1.0, MAX_FONT_SIZE);
+            if let Some(mask) = text_mask(img, fonts, text, Point { x: *x, y: *y }, px, *bold) {
+                fill(img, &mask, color);
+            }
+        }
+        Shape::Step {
+            x,
+            y,
+            number,
+            color,
+            radius,
+        } => {
+            let color = parse_color(color)?;
+            let radius = limit(*radius, 1.0, MAX_STEP_RADIUS);
+            let center = Point { x: *x, y: *y };
+            let extent = Point {
+                x: radius,
+                y: radius,
+            };
+            let min = Point {
+                x: center.x - radius,
+                y: center.y - radius,
+            };
+            let max = Point {
+                x: center.x + radius,
+                y: center.y + radius,
+            };
+            if let Some(mut mask) = Mask::around(img, min, max, 0.0) {
+                mask.cover(min, max, 0.0, |px, py| {
+                    coverage(round_box_distance(
+                        px - center.x,
+                        py - center.y,
+                        extent,
+                        radius,
+                    ))
+                });
+                fill(img, &mask, color);
+            }
+
+            let fonts = system_fonts()?;
+            let label = number.to_string();
+            let px = radius * if label.len() > 1 { 1.0 } else { 1.2 };
+            let width: f32 = label.chars().map(|c| char_advance(fonts, c, px)).sum();
+            // Centre the digits' cap height (about 0.7 em) on the circle.
+            let ascent = fonts
+                .primary()
+                .map(|font| font.as_scaled(PxScale::from(px)).ascent())
+                .unwrap_or(px * 0.8);
+            let origin = Point {
+                x: center.x - width / 2.0,
+                y: center.y + px * 0.35 - ascent,
+            };
+            let ink = if luminance(color) > 160.0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            };
+            if let Some(mask) = text_mask(img, fonts, &label, origin, px, true) {
+                fill(img, &mask, ink);
+            }
+        }
+        Shape::Mosaic {
+            points,
+            stroke_width,
+            block_size,
+        } => {
+            let half = stroke_half(*stroke_width);
+            let block = block_size
+                .unwrap_or_else(|| base.width().min(base.height()) / 40)
+                .clamp(MIN_MOSAIC_BLOCK, MAX_MOSAIC_BLOCK);
+            if let Some(mask) = stroke_mask(img, points, half) {
+                pixelate(img, base, &mask, block);
+            }
+        }
+        Shape::Blur { x, y, w, h, radius } => {
+            let (min, max) = normalize_box(*x, *y, *w, *h);
+            blur_box(img, base, min, max, limit(*radius, 0.5, MAX_BLUR_SIGMA));
+        }
+    }
+    Ok(())
+}
+
+fn system_fonts() -> anyhow::Result<&'static FontSet> {
+    let fonts = fonts::system_fonts();
+    if fonts.is_empty() {
+        anyhow::bail!("未找到可用于渲染标注文字的系统字体");
+    }
+    Ok(fonts)
+}
+
+/// `value` bounded to `min..=max`; NaN becomes `min`.
+fn limit(value: f32, min: f32, max: f32) -> f32 {
+    value.max(min).min(max)
+}
+
+/// Half of a stroke width from the model, bounded to `1..=MAX_STROKE_WIDTH`.
+fn stroke_half(width: f32) -> f32 {
+    limit(width, 1.0, MAX_STROKE_WIDTH) / 2.0
+}
+
+/// Parse `#rgb`, `#rrggbb` or `#rrggbbaa`.
+fn parse_color(value: &str) -> anyhow::Result<Rgba<u8>> {
+    let hex = value.trim().trim_start_matches('#');
+    let invalid = || anyhow::anyhow!("无效的颜色: {}", value);
+    if !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    match hex.len() {
+        3 => {
+            let short = |i: usize| {
+                u8::from_str_radix(&hex[i..i + 1], 16)
+                    .map(|v| v * 17)
+                    .map_err(|_| invalid())
+            };
+            Ok(Rgba([short(0)?, short(1)?, short(2)?, 255]))
+        }
+        6 => Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, 255])),
+        8 => Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, channel(6)?])),
+        _ => Err(invalid()),
+    }
+}
+
+/// Box corners for a box that may have been dragged up or left.
+fn normalize_box(x: f32, y: f32, w: f32, h: f32) -> (Point, Point) {
+    (
+        Point {
+            x: x.min(x + w),
+            y: y.min(y + h),
+        },
+        Point {
+            x: x.max(x + w),
+            y: y.max(y + h),
+        },
+    )
+}
+
+fn bounds(points: impl IntoIterator<Item = Point>) -> (Point, Point) {
+    points.into_iter().fold(
+        (
+            Point {
+                x: f32::INFINITY,
+                y: f32::INFINITY,
+            },
+            Point {
+                x: f32::NEG_INFINITY,
+                y: f32::NEG_INFINITY,
+            },
+        ),
+        |(min, max), p| {
+            (
+                Point {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        },
+    )
+}
+
+/// Pixel coverage from a signed distance to the edge (negative inside),
+/// with a one-pixel anti-aliasing ramp.
+fn coverage(distance: f32) -> f32 {
+    (0.5 - distance).clamp(0.0, 1.0)
+}
+
+fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let (abx, aby) = (b.x - a.x, b.y - a.y);
+    let (apx, apy) = (p.x - a.x, p.y - a.y);
+    let len2 = abx * abx + aby * aby;
+    let t = if len2 > 0.0 {
+        ((apx * abx + apy * aby) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (dx, dy) = (apx - abx * t, apy - aby * t);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Signed distance to a rounded box centred at the origin.
+fn round_box_distance(x: f32, y: f32, extent: Point, radius: f32) -> f32 {
+    let qx = x.abs() - (extent.x - radius);
+    let qy = y.abs() - (extent.y - radius);
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    let inside = qx.max(qy).min(0.0);
+    outside + inside - radius
+}
+
+/// Approximate signed distance to an ellipse centred at the origin; exact
+/// enough near the outline, which is all a stroke needs.
+fn ellipse_distance(x: f32, y: f32, rx: f32, ry: f32) -> f32 {
+    let k0 = ((x / rx).powi(2) + (y / ry).powi(2)).sqrt();
+    let k1 = ((x / (rx * rx)).powi(2) + (y / (ry * ry)).powi(2)).sqrt();
+    if k1 == 0.0 {
+        -rx.min(ry)
+    } else {
+        k0 * (k0 - 1.0) / k1
+    }
+}
+
+/// Signed distance to the edges of a triangle (negative inside); corners
+/// come out slightly sharp, which is fine for an arrowhead.
+fn triangle_distance(p: Point, corners: &[Point; 3]) -> f32 {
+    let [a, b, c] = *corners;
+    let orientation = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+    let sign = if orientation >= 0.0 { -1.0 } else { 1.0 };
+    [(a, b), (b, c), (c, a)]
+        .iter()
+        .map(|(from, to)| {
+            let (ex, ey) = (to.x - from.x, to.y - from.y);
+            let len = (ex * ex + ey * ey).sqrt().max(f32::EPSILON);
+            sign * (ex * (p.y - from.y) - ey * (p.x - from.x)) / len
+        })
+        .fold(f32::NEG_INFINITY, f32::max)
+}
+
+/// Coverage of one shape over its bounding box, clamped to the image. Parts
+/// of a shape are combined with `max`, so overlapping segments of a stroke
+/// or an arrow's shaft and head do not darken each other.
+struct Mask {
+    x0: i64,
+    y0: i64,
+    width: i64,
+    height: i64,
+    data: Vec<f32>,
+}
+
+impl Mask {
+    /// Mask over the box `min`–`max` grown by `pad` (plus the AA ramp);
+    /// `None` when that lies entirely outside the image.
+    fn around(img: &RgbaImage, min: Point, max: Point, pad: f32) -> Option<Self> {
+        if !(min.x.is_finite() && min.y.is_finite() && max.x.is_finite() && max.y.is_finite()) {
+            return None;
+        }
+        let x0 = ((min.x - pad).floor() as i64 - 1).max(0);
+        let y0 = ((min.y - pad).floor() as i64 - 1).max(0);
+        let x1 = ((max.x + pad).ceil() as i64 + 1).min(img.width() as i64);
+        let y1 = ((max.y + pad).ceil() as i64 + 1).min(img.height() as i64);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        let (width, height) = (x1 - x0, y1 - y0);
+        Some(Self {
+            x0,
+            y0,
+            width,
+            height,
+            data: vec![0.0; (width * height) as usize],
+        })
+    }
+
+    /// Evaluate `coverage_at` at the pixel centres of the box `min`–`max`
+    /// grown by `pad`.
+    fn cover(&mut self, min: Point, max: Point, pad: f32, coverage_at: impl Fn(f32, f32) -> f32) {
+        let x0 = ((min.x - pad).floor() as i64 - 1).max(self.x0);
+        let y0 = ((min.y - pad).floor() as i64 - 1).max(self.y0);
+        let x1 = ((max.x + pad).ceil() as i64 + 1).min(self.x0 + self.width);
+        let y1 = ((max.y + pad).ceil() as i64 + 1).min(self.y0 + self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let c = coverage_at(x as f32 + 0.5, y as f32 + 0.5);
+                self.add(x, y, c);
+            }
+        }
+    }
+
+    /// Whether the pixel box `min`–`max` touches the mask at all.
+    fn overlaps(&self, min: ab_glyph::Point, max: ab_glyph::Point) -> bool {
+        (max.x as i64) >= self.x0
+            && (max.y as i64) >= self.y0
+            && (min.x as i64) < self.x0 + self.width
+            && (min.y as i64) < self.y0 + self.height
+    }
+
+    fn add(&mut self, x: i64, y: i64, c: f32) {
+        if c <= 0.0 || x < self.x0 || y < self.y0 {
+            return;
+        }
+        let (dx, dy) = (x - self.x0, y - self.y0);
+        if dx >= self.width || dy >= self.height {
+            return;
+        }
+        let slot = &mut self.data[(dy * self.width + dx) as usize];
+        *slot = slot.max(c.min(1.0));
+    }
+
+    /// Round-capped line of half width `half`.
+    fn stroke_segment(&mut self, a: Point, b: Point, half: f32) {
+        let (min, max) = bounds([a, b]);
+        self.cover(min, max, half, |px, py| {
+            coverage(segment_distance(Point { x: px, y: py }, a, b) - half)
+        });
+    }
+
+    fn for_each(&self, mut f: impl FnMut(u32, u32, f32)) {
+        for (i, &c) in self.data.iter().enumerate() {
+            if c > 0.0 {
+                let i = i as i64;
+                f(
+                    (self.x0 + i % self.width) as u32,
+                    (self.y0 + i / self.width) as u32,
+                    c,
+                );
+            }
+        }
+    }
+}
+
+/// Round-capped, round-joined polyline; a single point is a dot.
+fn stroke_mask(img: &RgbaImage, points: &[Point], half: f32) -> Option<Mask> {
+    let (min, max) = bounds(points.iter().copied());
+    let mut mask = Mask::around(img, min, max, half)?;
+    match points {
+        [] => return None,
+        [only] => mask.stroke_segment(*only, *only, half),
+        _ => {
+            for pair in points.windows(2) {
+                mask.stroke_segment(pair[0], pair[1], half);
+            }
+        }
+    }
+    Some(mask)
+}
+
+/// Glyph coverage of `text` with its top-left corner at `origin`. There is
+/// no bold face in the font chain, so bold is drawn twice, offset sideways.
+fn text_mask(
+    img: &RgbaImage,
+    fonts: &FontSet,
+    text: &str,
+    origin: Point,
+    px: f32,
+    bold: bool,
+) -> Option<Mask> {
+    let primary = fonts.primary()?;
+    let px = px.max(1.0);
+    let scale = PxScale::from(px);
+    let ascent = primary.as_scaled(scale).ascent();
+    let step = line_height(fonts, px).max(px * TEXT_LINE_HEIGHT);
+    let embolden = if bold { (px / 24.0).max(1.0) } else { 0.0 };
+
+    let lines: Vec<&str> = text.lines().collect();
+    let widest = lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|c| char_advance(fonts, c, px))
+                .sum::<f32>()
+        })
+        .fold(0.0, f32::max);
+    let max = Point {
+        x: origin.x + widest + embolden,
+        y: origin.y + step * lines.len() as f32,
+    };
+    // Glyphs may overhang their advance box a little.
+    let mut mask = Mask::around(img, origin, max, px * 0.25)?;
+
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = origin.y + i as f32 * step + ascent;
+        let mut x = origin.x;
+        for c in line.chars() {
+            let Some((font, id)) = fonts.resolve(c) else {
+                continue;
+            };
+            let passes = if bold { 2 } else { 1 };
+            for pass in 0..passes {
+                let shift = pass as f32 * embolden;
+                let glyph = id.with_scale_and_position(scale, point(x + shift, baseline));
+                if let Some(outlined) = font.outline_glyph(glyph) {
+                    let bounds = outlined.px_bounds();
+                    if !mask.overlaps(bounds.min, bounds.max) {
+                        continue;
+                    }
+                    outlined.draw(|gx, gy, c| {
+                        mask.add(
+                            bounds.min.x as i64 + gx as i64,
+                            bounds.min.y as i64 + gy as i64,
+                            c,
+                        );
+                    });
+                }
+            }
+            x += font.as_scaled(scale).h_advance(id);
+        }
+    }
+    Some(mask)
+}
+
+fn fill(img: &mut RgbaImage, mask: &Mask, color: Rgba<u8>) {
+    let alpha = color[3] as f32 / 255.0;
+    mask.for_each(|x, y, c| blend(img.get_pixel_mut(x, y), color, c * alpha));
+}
+
+/// Replace the masked pixels with the average colour of their
+/// `block`×`block` cell of `base` (cells aligned to the image origin).
+fn pixelate(img: &mut RgbaImage, base: &RgbaImage, mask: &Mask, block: u32) {
+    let (width, height) = base.dimensions();
+    let bx0 = mask.x0 as u32 / block;
+    let by0 = mask.y0 as u32 / block;
+    let bx1 = (mask.x0 + mask.width - 1) as u32 / block;
+    let by1 = (mask.y0 + mask.height - 1) as u32 / block;
+    let columns = (bx1 - bx0 + 1) as usize;
+
+    let mut cells = Vec::with_capacity(columns * (by1 - by0 + 1) as usize);
+    for by in by0..=by1 {
+        for bx in bx0..=bx1 {
+            let mut sum = [0u64; 3];
+            let mut count = 0u64;
+            for y in by * block..((by + 1) * block).min(height) {
+                for x in bx * block..((bx + 1) * block).min(width) {
+                    let p = base.get_pixel(x, y);
+                    for (acc, value) in sum.iter_mut().zip(p.0) {
+                        *acc += value as u64;
+                    }
+                    count += 1;
+                }
+            }
+            let count = count.max(1);
+            cells.push(Rgba([
+                (sum[0] / count) as u8,
+                (sum[1] / count) as u8,
+                (sum[2] / count) as u8,
+                255,
+            ]));
+        }
+    }
+
+    mask.for_each(|x, y, c| {
+        let cell = ((y / block - by0) as usize) * columns + (x / block - bx0) as usize;
+        blend(img.get_pixel_mut(x, y), cells[cell], c);
+    });
+}
+
+/// Blur `base` inside the box into `img`. Pixels around the box are
+/// included as input so its edges blur like the rest.
+fn blur_box(img: &mut RgbaImage, base: &RgbaImage, min: Point, max: Point, sigma: f32) {
+    let (width, height) = base.dimensions();
+    let x0 = (min.x.max(0.0).floor() as u32).min(width);
+    let y0 = (min.y.max(0.0).floor() as u32).min(height);
+    let x1 = (max.x.max(0.0).ceil() as u32).min(width);
+    let y1 = (max.y.max(0.0).ceil() as u32).min(height);
+    if x1 <= x0 || y1 <= y0 {
+        return;
+    }
+    let pad = (sigma * 3.0).ceil() as u32;
+    let (sx0, sy0) = (x0.saturating_sub(pad), y0.saturating_sub(pad));
+    let (sx1, sy1) = (
+        x1.saturating_add(pad).min(width),
+        y1.saturating_add(pad).min(height),
+    );
+    let region = image::imageops::crop_imm(base, sx0, sy0, sx1 - sx0, sy1 - sy0).to_image();
+    let blurred = image::imageops::blur(&region, sigma);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            img.put_pixel(x, y, *blurred.get_pixel(x - sx0, y - sy0));
+        }
+    }
+}
+
+/// Sidecar path of an annotated image.
+pub fn sidecar_path(image_path: &Path) -> PathBuf {
+    let mut name = image_path.as_os_str().to_owned();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Image a sidecar belongs to: strips the sidecar suffix, or returns `path`
+/// unchanged when it is not a sidecar.
+pub fn image_path_of(path: &Path) -> PathBuf {
+    path.to_str()
+        .and_then(|s| s.strip_suffix(SIDECAR_SUFFIX))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Write the sidecar of `image_path`; returns its path.
+pub fn write_sidecar(
+    image_path: &Path,
+    base: &RgbaImage,
+    annotation: &Annotation,
+) -> anyhow::Result<PathBuf> {
+    use base64::Engine;
+    let png = crate::screenshot::encode_png(base)?;
+    let document = AnnotationDocument {
+        version: ANNOTATION_VERSION,
+        image: image_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        width: base.width(),
+        height: base.height(),
+        base: base64::engine::general_purpose::STANDARD.encode(png),
+        annotation: annotation.clone(),
+    };
+    let path = sidecar_path(image_path);
+    crate::output::write_file(&path, &serde_json::to_vec(&document)?)?;
+    Ok(path)
+}
+
+/// Read the sidecar of `image_path` (or the sidecar itself): the unannotated
+/// capture and its annotation.
+pub fn read_sidecar(path: &Path) -> anyhow::Result<(RgbaImage, Annotation)> {
+    use base64::Engine;
+    let sidecar = sidecar_path(&image_path_of(path));
+    let bytes = std::fs::read(&sidecar)
+        .with_context(|| format!("没有找到标注文件: {}", sidecar.display()))?;
+    let document: AnnotationDocument = serde_json::from_slice(&bytes)?;
+    if document.version > ANNOTATION_VERSION {
+        anyhow::bail!("标注文件版本过新: {}", document.version);
+    }
+    let png = base64::engine::general_purpose::STANDARD.decode(document.base)?;
+    let base = image::load_from_memory(&png)?.to_rgba8();
+    Ok((base, document.annotation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> RgbaImage {
+        RgbaImage::from_fn(64, 48, |x, y| {
+            if (x / 4 + y / 4) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn clamps_out_of_range_sizes() {
+        let base = checker();
+        let annotation: Annotation = serde_json::from_value(serde_json::json!({
+            "shapes": [
+                { "type": "blur", "x": 0, "y": 0, "w": 64, "h": 48, "radius": 1.0e12 },
+                { "type": "rect", "x": 4, "y": 4, "w": 20, "h": 20, "color": "#f00",
+                  "strokeWidth": 1.0e9, "radius": 1.0e9 },
+                { "type": "arrow", "x1": 0, "y1": 0, "x2": 60, "y2": 40, "color": "#0f0",
+                  "strokeWidth": -5 },
+                { "type": "mosaic", "points": [{ "x": 10, "y": 10 }, { "x": 50, "y": 30 }],
+                  "strokeWidth": 1.0e9, "blockSize": 4000000000u32 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(annotation.version, ANNOTATION_VERSION);
+        let out = render_annotation(&base, &annotation).unwrap();
+        assert_eq!(out.dimensions(), base.dimensions());
+    }
+
+    #[test]
+    fn blur_stays_inside_its_box() {
+        let base = checker();
+        let annotation = Annotation {
+            version: ANNOTATION_VERSION,
+            shapes: vec![Shape::Blur {
+                x: 32.0,
+                y: 0.0,
+                w: -16.0,
+                h: 48.0,
+                radius: 4.0,
+            }],
+        };
+        let out = render_annotation(&base, &annotation).unwrap();
+        for y in 0..48 {
+            for x in (0..16).chain(32..64) {
+                assert_eq!(out.get_pixel(x, y), base.get_pixel(x, y));
+            }
+        }
+        // Inside, the 4px checker is smoothed towards grey.
+        let centre = out.get_pixel(24, 24)[0];
+        assert!((64..=192).contains(&centre), "centre={}", centre);
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_color("#f00").unwrap(), Rgba([255, 0, 0, 255]));
+        assert_eq!(
+            parse_color("#11223380").unwrap(),
+            Rgba([0x11, 0x22, 0x33, 0x80])
+        );
+        assert!(parse_color("red").is_err());
+    }
+
+    #[test]
+    fn sidecar_sits_next_to_image() {
+        let image = Path::new("/tmp/shots/shot.png");
+        let sidecar = sidecar_path(image);
+        assert_eq!(sidecar, Path::new("/tmp/shots/shot.png.annotations.json"));
+        assert_eq!(image_path_of(&sidecar), image);
+        assert_eq!(image_path_of(image), image);
+    }
+}
@@ -0,0 +1,110 @@
+use ab_glyph::{Font, FontVec, GlyphId};
+use log::{info, warn};
+use std::sync::OnceLock;
+
+/// System fonts tried in order. CJK-capable fonts come first so Chinese,
+/// Japanese and Korean all render from the first hit; Latin fonts are only a
+/// last resort when no CJK font is installed.
+#[cfg(target_os = "macos")]
+const FONT_CANDIDATES: &[&str] = &[
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/Helvetica.ttc",
+];
+
+#[cfg(target_os = "windows")]
+const FONT_CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const FONT_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSerifCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+];
+
+/// Fallback chain. Only the primary (first installed) font is read up front;
+/// each later candidate is read from disk the first time `resolve` needs a
+/// glyph the fonts before it lack, so a typical run keeps one font resident.
+pub struct FontSet {
+    primary: Option<FontVec>,
+    fallbacks: Vec<(&'static str, OnceLock<Option<FontVec>>)>,
+}
+
+impl FontSet {
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_none()
+    }
+
+    /// First font in the chain that has a glyph for `c`; falls back to the
+    /// primary font (which will draw its .notdef box).
+    pub fn resolve(&self, c: char) -> Option<(&FontVec, GlyphId)> {
+        let primary = self.primary.as_ref()?;
+        let id = primary.glyph_id(c);
+        if id.0 != 0 {
+            return Some((primary, id));
+        }
+        self.fallbacks
+            .iter()
+            .filter_map(|(path, slot)| slot.get_or_init(|| load_font(path)).as_ref())
+            .map(|font| (font, font.glyph_id(c)))
+            .find(|(_, id)| id.0 != 0)
+            .or(Some((primary, id)))
+    }
+
+    pub fn primary(&self) -> Option<&FontVec> {
+        self.primary.as_ref()
+    }
+}
+
+static FONTS: OnceLock<FontSet> = OnceLock::new();
+
+pub fn system_fonts() -> &'static FontSet {
+    FONTS.get_or_init(load_system_fonts)
+}
+
+fn load_system_fonts() -> FontSet {
+    let mut candidates = FONT_CANDIDATES.iter();
+    let primary = candidates.by_ref().find_map(|path| load_font(path));
+    if primary.is_none() {
+        warn!("[Render] 未找到可用的系统字体");
+    }
+    FontSet {
+        primary,
+        fallbacks: candidates.map(|path| (*path, OnceLock::new())).collect(),
+    }
+}
+
+/// Read one candidate; `None` when it is not installed or does not parse.
+fn load_font(path: &str) -> Option<FontVec> {
+    let data = std::fs::read(path).ok()?;
+    // Collections (.ttc) are loaded at face 0, the regular weight for every
+    // candidate above.
+    match FontVec::try_from_vec_and_index(data, 0) {
+        Ok(font) => {
+            info!("[Render] 加载字体: {}", path);
+            Some(font)
+        }
+        Err(e) => {
+            warn!("[Render] 字体解析失败 {}: {}", path, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,292 @@
+pub mod annotate;
+pub mod fonts;
+
+use crate::ocr::structured::BoundingBox;
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+use fonts::FontSet;
+use image::{Rgba, RgbaImage};
+use log::info;
+
+/// Extra pixels cleared around each OCR box; model boxes tend to hug glyphs
+/// tightly and leave anti-aliased edges behind.
+const BOX_PADDING: i64 = 2;
+/// Width of the ring outside the box sampled for the background colour.
+const BORDER_SAMPLE: i64 = 3;
+const MIN_FONT_PX: f32 = 9.0;
+/// Successive font size reduction while fitting.
+const FIT_STEP: f32 = 0.9;
+/// Below this RGB distance the sampled text colour is treated as unreliable
+/// and black/white is picked from background luminance instead.
+const MIN_TEXT_CONTRAST: f64 = 80.0;
+
+/// One translated block to paint over the source image.
+pub struct RenderBlock {
+    pub bbox: BoundingBox,
+    pub text: String,
+    /// Number of source lines, used to estimate the original font size.
+    pub line_count: usize,
+}
+
+/// Paint each block's translation over the image in place: the box is filled
+/// with the surrounding background colour, then the text is wrapped and drawn
+/// at the largest size that fits, in the colour of the original text.
+pub fn render_translations(img: &mut RgbaImage, blocks: &[RenderBlock]) -> anyhow::Result<()> {
+    let fonts = fonts::system_fonts();
+    if fonts.is_empty() {
+        anyhow::bail!("未找到可用于渲染译文的系统字体");
+    }
+
+    for block in blocks {
+        if block.text.trim().is_empty() {
+            continue;
+        }
+        let Some(rect) = PixelRect::around(&block.bbox, img.width(), img.height()) else {
+            continue;
+        };
+        let background = sample_background(img, &rect);
+        let foreground = sample_text_color(img, &rect, background);
+        fill_rect(img, &rect, background);
+
+        let (px, lines) = fit_text(fonts, &block.text, &rect, block.line_count);
+        draw_lines(img, fonts, &lines, px, &rect, foreground);
+    }
+    info!("[Render] 渲染完成, blocks={}", blocks.len());
+    Ok(())
+}
+
+/// Integer pixel rectangle (exclusive right/bottom) clamped to the image.
+struct PixelRect {
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+}
+
+impl PixelRect {
+    fn around(bbox: &BoundingBox, width: u32, height: u32) -> Option<Self> {
+        let rect = Self {
+            x0: (bbox.x.floor() as i64 - BOX_PADDING).max(0),
+            y0: (bbox.y.floor() as i64 - BOX_PADDING).max(0),
+            x1: ((bbox.x + bbox.width).ceil() as i64 + BOX_PADDING).min(width as i64),
+            y1: ((bbox.y + bbox.height).ceil() as i64 + BOX_PADDING).min(height as i64),
+        };
+        (rect.width() > 0 && rect.height() > 0).then_some(rect)
+    }
+
+    fn width(&self) -> i64 {
+        self.x1 - self.x0
+    }
+
+    fn height(&self) -> i64 {
+        self.y1 - self.y0
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.x0 && x < self.x1 && y >= self.y0 && y < self.y1
+    }
+}
+
+/// Per-channel median of the ring just outside the box. The median ignores
+/// neighbouring text strokes that touch the ring. Falls back to the box
+/// itself when it covers the whole image.
+fn sample_background(img: &RgbaImage, rect: &PixelRect) -> Rgba<u8> {
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    let mut samples = Vec::new();
+    for y in (rect.y0 - BORDER_SAMPLE).max(0)..(rect.y1 + BORDER_SAMPLE).min(h) {
+        for x in (rect.x0 - BORDER_SAMPLE).max(0)..(rect.x1 + BORDER_SAMPLE).min(w) {
+            if !rect.contains(x, y) {
+                samples.push(*img.get_pixel(x as u32, y as u32));
+            }
+        }
+    }
+    if samples.is_empty() {
+        for y in rect.y0..rect.y1 {
+            for x in rect.x0..rect.x1 {
+                samples.push(*img.get_pixel(x as u32, y as u32));
+            }
+        }
+    }
+    let median = |channel: usize| {
+        let mut values: Vec<u8> = samples.iter().map(|p| p[channel]).collect();
+        values.sort_unstable();
+        values[values.len() / 2]
+    };
+    Rgba([median(0), median(1), median(2), 255])
+}
+
+/// Average of the pixels inside the box that differ most from the background
+/// (the glyph cores), or black/white when the box has too little contrast.
+fn sample_text_color(img: &RgbaImage, rect: &PixelRect, background: Rgba<u8>) -> Rgba<u8> {
+    let mut pixels: Vec<(f64, Rgba<u8>)> = Vec::new();
+    for y in rect.y0..rect.y1 {
+        for x in rect.x0..rect.x1 {
+            let pixel = *img.get_pixel(x as u32, y as u32);
+            pixels.push((color_distance(pixel, background), pixel));
+        }
+    }
+    pixels.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let top = &pixels[..(pixels.len() / 10).max(1)];
+    let sum = top.iter().fold([0u64; 3], |mut acc, (_, p)| {
+        for (c, value) in acc.iter_mut().enumerate() {
+            *value += p[c] as u64;
+        }
+        acc
+    });
+    let n = top.len() as u64;
+    let average = Rgba([
+        (sum[0] / n) as u8,
+        (sum[1] / n) as u8,
+        (sum[2] / n) as u8,
+        255,
+    ]);
+    if color_distance(average, background) >= MIN_TEXT_CONTRAST {
+        average
+    } else if luminance(background) > 128.0 {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    }
+}
+
+fn color_distance(a: Rgba<u8>, b: Rgba<u8>) -> f64 {
+    (0..3)
+        .map(|c| (a[c] as f64 - b[c] as f64).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn luminance(p: Rgba<u8>) -> f64 {
+    0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64
+}
+
+fn fill_rect(img: &mut RgbaImage, rect: &PixelRect, color: Rgba<u8>) {
+    for y in rect.y0..rect.y1 {
+        for x in rect.x0..rect.x1 {
+            img.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+fn line_height(fonts: &FontSet, px: f32) -> f32 {
+    fonts
+        .primary()
+        .map(|font| {
+            let scaled = font.as_scaled(PxScale::from(px));
+            scaled.height() + scaled.line_gap()
+        })
+        .unwrap_or(px)
+}
+
+fn char_advance(fonts: &FontSet, c: char, px: f32) -> f32 {
+    fonts
+        .resolve(c)
+        .map(|(font, id)| font.as_scaled(PxScale::from(px)).h_advance(id))
+        .unwrap_or(0.0)
+}
+
+/// Largest font size (starting from the original line height) at which the
+/// wrapped text fits the box. Text that does not fit even at the minimum size
+/// is drawn at the minimum size and clipped by the image bounds.
+fn fit_text(
+    fonts: &FontSet,
+    text: &str,
+    rect: &PixelRect,
+    line_count: usize,
+) -> (f32, Vec<String>) {
+    let max_width = (rect.width() - 2 * BOX_PADDING).max(1) as f32;
+    let max_height = rect.height() as f32;
+    let mut px = (max_height / line_count.max(1) as f32 * 0.85).max(MIN_FONT_PX);
+    loop {
+        let lines = wrap_text(fonts, text, px, max_width);
+        let fits = lines.len() as f32 * line_height(fonts, px) <= max_height;
+        if fits || px <= MIN_FONT_PX {
+            return (px, lines);
+        }
+        px = (px * FIT_STEP).max(MIN_FONT_PX);
+    }
+}
+
+/// Greedy wrap: break at the last space when the line has one (Latin text),
+/// otherwise between any two characters (CJK text).
+fn wrap_text(fonts: &FontSet, text: &str, px: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut width = 0.0;
+        for c in paragraph.chars() {
+            let advance = char_advance(fonts, c, px);
+            if width + advance > max_width && !line.is_empty() {
+                match line.rfind(' ') {
+                    Some(idx) if idx > 0 && c != ' ' => {
+                        let rest = line[idx + 1..].to_string();
+                        line.truncate(idx);
+                        lines.push(std::mem::take(&mut line));
+                        width = rest.chars().map(|r| char_advance(fonts, r, px)).sum();
+                        line = rest;
+                    }
+                    _ => {
+                        lines.push(std::mem::take(&mut line));
+                        width = 0.0;
+                    }
+                }
+                if c == ' ' {
+                    continue;
+                }
+            }
+            line.push(c);
+            width += advance;
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// Draw wrapped lines left-aligned and vertically centred in the box.
+fn draw_lines(
+    img: &mut RgbaImage,
+    fonts: &FontSet,
+    lines: &[String],
+    px: f32,
+    rect: &PixelRect,
+    color: Rgba<u8>,
+) {
+    let Some(primary) = fonts.primary() else {
+        return;
+    };
+    let scale = PxScale::from(px);
+    let ascent = primary.as_scaled(scale).ascent();
+    let step = line_height(fonts, px);
+    let total_height = lines.len() as f32 * step;
+    let top = rect.y0 as f32 + ((rect.height() as f32 - total_height) / 2.0).max(0.0);
+    let (width, height) = (img.width() as i64, img.height() as i64);
+
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = top + i as f32 * step + ascent;
+        let mut x = rect.x0 as f32 + BOX_PADDING as f32;
+        for c in line.chars() {
+            let Some((font, id)) = fonts.resolve(c) else {
+                continue;
+            };
+            let advance = font.as_scaled(scale).h_advance(id);
+            let glyph = id.with_scale_and_position(scale, point(x, baseline));
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    let dx = bounds.min.x as i64 + gx as i64;
+                    let dy = bounds.min.y as i64 + gy as i64;
+                    if dx >= 0 && dy >= 0 && dx < width && dy < height {
+                        blend(img.get_pixel_mut(dx as u32, dy as u32), color, coverage);
+                    }
+                });
+            }
+            x += advance;
+        }
+    }
+}
+
+fn blend(dst: &mut Rgba<u8>, src: Rgba<u8>, coverage: f32) {
+    let alpha = coverage.clamp(0.0, 1.0);
+    for c in 0..3 {
+        dst[c] = (src[c] as f32 * alpha + dst[c] as f32 * (1.0 - alpha)).round() as u8;
+    }
+}
@@ -1,8 +1,10 @@
+use crate::config::MonitorInfo;
 use base64::Engine;
-use image::codecs::jpeg::JpegEncoder;
-use image::{DynamicImage, ImageFormat};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, ImageFormat, RgbaImage};
 use log::info;
 use std::io::Cursor;
+use std::time::Instant;
 
 /// A visible window rectangle in logical (points) coordinates.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -13,22 +15,97 @@ pub struct WindowRect {
     pub height: f64,
 }
 
+/// Title of the screenshot overlay windows, used to keep them out of the
+/// window list.
+pub const OVERLAY_WINDOW_TITLE: &str = "Screenshot";
+
 /// List all visible normal-layer window rectangles in front-to-back order.
-/// Returns logical coordinates (points on macOS).
-/// On non-macOS platforms, returns an empty Vec.
+/// Returns logical coordinates (points on macOS, physical pixels divided by
+/// the window's monitor scale elsewhere).
 pub fn list_window_rects() -> Vec<WindowRect> {
     #[cfg(target_os = "macos")]
     {
-        list_window_rects_macos()
+        let rects: Vec<WindowRect> = list_windows_macos()
+            .into_iter()
+            .map(|window| window.rect)
+            .collect();
+        info!("[Capture] list_window_rects: found {} windows", rects.len());
+        rects
     }
     #[cfg(not(target_os = "macos"))]
     {
-        Vec::new()
+        list_window_rects_xcap()
     }
 }
 
+/// A window from the CoreGraphics window list.
 #[cfg(target_os = "macos")]
-fn list_window_rects_macos() -> Vec<WindowRect> {
+pub(super) struct NativeWindow {
+    /// CGWindowID
+    pub id: u32,
+    pub owner_pid: i32,
+    pub rect: WindowRect,
+}
+
+/// Linux (X11) / Windows: enumerate windows with xcap. `Window::all()` is
+/// already in stacking order, topmost first.
+#[cfg(not(target_os = "macos"))]
+fn list_window_rects_xcap() -> Vec<WindowRect> {
+    use xcap::Window;
+
+    let windows = match Window::all() {
+        Ok(windows) => windows,
+        // e.g. Wayland sessions without window enumeration support
+        Err(e) => {
+            info!("[Capture] xcap 窗口枚举失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let own_pid = std::process::id();
+    let total = windows.len();
+
+    let rects: Vec<WindowRect> = windows
+        .iter()
+        .filter_map(|window| {
+            if window.is_minimized().unwrap_or(true) {
+                return None;
+            }
+            if window.pid().ok() == Some(own_pid)
+                && window.title().ok().as_deref() == Some(OVERLAY_WINDOW_TITLE)
+            {
+                return None;
+            }
+            let (x, y) = (window.x().ok()?, window.y().ok()?);
+            let (width, height) = (window.width().ok()?, window.height().ok()?);
+            let scale = window
+                .current_monitor()
+                .and_then(|monitor| monitor.scale_factor())
+                .map(f64::from)
+                .ok()
+                .filter(|scale| *scale > 0.0)
+                .unwrap_or(1.0);
+            let rect = WindowRect {
+                x: x as f64 / scale,
+                y: y as f64 / scale,
+                width: width as f64 / scale,
+                height: height as f64 / scale,
+            };
+            // Skip tiny windows (< 10x10), same as macOS
+            (rect.width >= 10.0 && rect.height >= 10.0).then_some(rect)
+        })
+        .collect();
+
+    info!(
+        "[Capture] list_window_rects: found {} windows (xcap total={})",
+        rects.len(),
+        total
+    );
+    rects
+}
+
+/// Visible normal-layer windows, front to back, bounds in points.
+#[cfg(target_os = "macos")]
+pub(super) fn list_windows_macos() -> Vec<NativeWindow> {
     use std::ffi::c_void;
 
     #[link(name = "CoreGraphics", kind = "framework")]
@@ -43,6 +120,8 @@ fn list_window_rects_macos() -> Vec<WindowRect> {
         static kCGWindowLayer: *const c_void;
         static kCGWindowBounds: *const c_void;
         static kCGWindowIsOnscreen: *const c_void;
+        static kCGWindowNumber: *const c_void;
+        static kCGWindowOwnerPID: *const c_void;
     }
 
     #[link(name = "CoreFoundation", kind = "framework")]
@@ -81,7 +160,7 @@ fn list_window_rects_macos() -> Vec<WindowRect> {
         }
 
         let count = CFArrayGetCount(window_list);
-        let mut rects = Vec::new();
+        let mut windows = Vec::new();
 
         for i in 0..count {
             let dict = CFArrayGetValueAtIndex(window_list, i);
@@ -128,24 +207,49 @@ fn list_window_rects_macos() -> Vec<WindowRect> {
                 continue;
             }
 
-            rects.push(WindowRect {
-                x: cg_rect.x,
-                y: cg_rect.y,
-                width: cg_rect.width,
-                height: cg_rect.height,
+            let mut id: i32 = 0;
+            let id_val = CFDictionaryGetValue(dict, kCGWindowNumber);
+            if id_val.is_null()
+                || !CFNumberGetValue(
+                    id_val,
+                    K_CF_NUMBER_SINT32_TYPE,
+                    &mut id as *mut i32 as *mut c_void,
+                )
+            {
+                continue;
+            }
+            let mut owner_pid: i32 = -1;
+            let pid_val = CFDictionaryGetValue(dict, kCGWindowOwnerPID);
+            if !pid_val.is_null() {
+                CFNumberGetValue(
+                    pid_val,
+                    K_CF_NUMBER_SINT32_TYPE,
+                    &mut owner_pid as *mut i32 as *mut c_void,
+                );
+            }
+
+            windows.push(NativeWindow {
+                id: id as u32,
+                owner_pid,
+                rect: WindowRect {
+                    x: cg_rect.x,
+                    y: cg_rect.y,
+                    width: cg_rect.width,
+                    height: cg_rect.height,
+                },
             });
         }
 
         CFRelease(window_list);
-
-        info!("[Capture] list_window_rects: found {} windows", rects.len());
-        rects
+        windows
     }
 }
 
-/// Capture each monitor individually, returning one base64 PNG per monitor.
-/// `monitors` is a list of (logical_x, logical_y, logical_w, logical_h) for each monitor.
-pub fn capture_monitors(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<Vec<String>> {
+/// Capture each monitor individually, returning one decoded RGBA image per
+/// monitor. Nothing is encoded here; consumers encode only what they need.
+/// `monitors` is Tauri's monitor list (physical pixels); images are returned
+/// in the same order.
+pub fn capture_monitors(monitors: &[MonitorInfo]) -> anyhow::Result<Vec<RgbaImage>> {
     info!("[Capture] capture_monitors, count={}", monitors.len());
     #[cfg(target_os = "macos")]
     {
@@ -161,7 +265,7 @@ pub fn capture_monitors(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<Vec
 /// macOS: Capture each monitor individually using its logical rect.
 /// Each image is at the monitor's native resolution (no mixed-DPI issues).
 #[cfg(target_os = "macos")]
-fn capture_monitors_macos(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<Vec<String>> {
+fn capture_monitors_macos(monitors: &[MonitorInfo]) -> anyhow::Result<Vec<RgbaImage>> {
     use std::ffi::c_void;
 
     #[link(name = "CoreGraphics", kind = "framework")]
@@ -194,26 +298,37 @@ fn capture_monitors_macos(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<V
         size: CGSize,
     }
 
-    // Capture all monitors in parallel — CGImage capture + BGRA→RGBA + PNG encode
+    // Capture all monitors in parallel — CGImage capture + BGRA→RGBA
     let handles: Vec<_> = monitors
         .iter()
         .enumerate()
-        .map(|(i, &(lx, ly, lw, lh))| {
+        .map(|(i, mon)| {
+            let scale = mon.scale_factor;
+            let (lx, ly) = (mon.x as f64 / scale, mon.y as f64 / scale);
+            let (lw, lh) = (mon.width as f64 / scale, mon.height as f64 / scale);
             std::thread::spawn(move || {
                 info!("[Capture] macOS 截取显示器[{}]: logical=({},{},{}x{})", i, lx, ly, lw, lh);
                 let rect = CGRect {
                     origin: CGPoint { x: lx, y: ly },
                     size: CGSize { width: lw, height: lh },
                 };
+                let started = Instant::now();
                 unsafe {
                     let cg_image = CGWindowListCreateImage(rect, 1, 0, 0);
                     if cg_image.is_null() {
                         anyhow::bail!("CGWindowListCreateImage returned null for monitor {} - check screen recording permission", i);
                     }
-                    let base64 = cgimage_to_base64(cg_image)?;
+                    let img = cgimage_to_rgba(cg_image);
                     CFRelease(cg_image);
-                    info!("[Capture] 显示器[{}] 截图完成, base64 size={}", i, base64.len());
-                    Ok(base64)
+                    let img = img?;
+                    info!(
+                        "[Capture] 显示器[{}] 截图完成, size={}x{}, 耗时 {}ms",
+                        i,
+                        img.width(),
+                        img.height(),
+                        started.elapsed().as_millis()
+                    );
+                    Ok(img)
                 }
             })
         })
@@ -221,18 +336,18 @@ fn capture_monitors_macos(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<V
 
     let mut results = Vec::with_capacity(handles.len());
     for (i, handle) in handles.into_iter().enumerate() {
-        let base64 = handle
+        let img = handle
             .join()
             .map_err(|_| anyhow::anyhow!("Monitor {} capture thread panicked", i))??;
-        results.push(base64);
+        results.push(img);
     }
 
     Ok(results)
 }
 
-/// Convert a CGImage to base64 PNG.
+/// Copy a CGImage into an RGBA buffer.
 #[cfg(target_os = "macos")]
-fn cgimage_to_base64(cg_image: *const std::ffi::c_void) -> anyhow::Result<String> {
+pub(super) fn cgimage_to_rgba(cg_image: *const std::ffi::c_void) -> anyhow::Result<RgbaImage> {
     use std::ffi::c_void;
 
     #[link(name = "CoreGraphics", kind = "framework")]
@@ -296,120 +411,150 @@ fn cgimage_to_base64(cg_image: *const std::ffi::c_void) -> anyhow::Result<String
 
         CFRelease(cf_data);
 
-        let img = image::RgbaImage::from_raw(img_width as u32, img_height as u32, rgba_buf)
-            .ok_or_else(|| anyhow::anyhow!("Failed to create image from raw data"))?;
-
-        image_to_base64(&img)
+        RgbaImage::from_raw(img_width as u32, img_height as u32, rgba_buf)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create image from raw data"))
     }
 }
 
 /// Non-macOS: Capture each monitor individually using xcap.
-/// NOTE: xcap discovers monitors via its own API; the `monitors` parameter (from Tauri)
-/// is logged for comparison but not used for capture. If ordering differs between
-/// Tauri and xcap, screenshots may be assigned to the wrong overlay.
+/// xcap enumerates monitors in its own order, so each Tauri monitor in
+/// `monitors` is paired with the xcap monitor of the same physical geometry
+/// (`match_monitors`) and the results are returned in Tauri order, one per
+/// overlay. Fails instead of guessing when a monitor has no match.
 #[cfg(not(target_os = "macos"))]
-fn capture_monitors_xcap(monitors: &[(f64, f64, f64, f64)]) -> anyhow::Result<Vec<String>> {
+fn capture_monitors_xcap(monitors: &[MonitorInfo]) -> anyhow::Result<Vec<RgbaImage>> {
+    use super::monitors::match_monitors;
     use xcap::Monitor;
 
     let xcap_monitors = Monitor::all()?;
-    if xcap_monitors.is_empty() {
-        anyhow::bail!("No monitor found");
-    }
+    let captured = xcap_monitors
+        .iter()
+        .map(|mon| {
+            Ok(MonitorInfo {
+                name: mon.name().unwrap_or_default(),
+                x: mon.x()?,
+                y: mon.y()?,
+                width: mon.width()?,
+                height: mon.height()?,
+                scale_factor: mon.scale_factor().map(f64::from).unwrap_or(1.0),
+            })
+        })
+        .collect::<xcap::XCapResult<Vec<_>>>()?;
 
     info!(
         "[Capture] xcap per-monitor 截图, tauri_count={}, xcap_count={}",
         monitors.len(),
-        xcap_monitors.len()
+        captured.len()
     );
-    if monitors.len() != xcap_monitors.len() {
-        info!("[Capture] ⚠ Tauri 与 xcap 显示器数量不一致，截图可能错位");
-    }
-    for (i, rect) in monitors.iter().enumerate() {
+    for (i, m) in captured.iter().enumerate() {
         info!(
-            "[Capture] tauri 显示器[{}]: logical=({},{},{}x{})",
-            i, rect.0, rect.1, rect.2, rect.3
+            "[Capture] xcap 显示器[{}]: name={}, pos=({},{}), size={}x{}, scale={}",
+            i, m.name, m.x, m.y, m.width, m.height, m.scale_factor
         );
     }
 
-    let mut results = Vec::with_capacity(xcap_monitors.len());
-    for (i, mon) in xcap_monitors.iter().enumerate() {
-        let img = mon.capture_image()?;
+    let matches = match_monitors(monitors, &captured).inspect_err(|e| {
+        log::warn!("[Capture] Tauri 与 xcap 显示器匹配失败: {}", e);
+    })?;
+
+    let mut results = Vec::with_capacity(matches.len());
+    for (i, &xcap_index) in matches.iter().enumerate() {
+        let started = Instant::now();
+        let img = xcap_monitors[xcap_index].capture_image()?;
         info!(
-            "[Capture] xcap 显示器[{}]: size={}x{}",
+            "[Capture] tauri 显示器[{}] ← xcap 显示器[{}]: size={}x{}, 耗时 {}ms",
             i,
+            xcap_index,
             img.width(),
-            img.height()
+            img.height(),
+            started.elapsed().as_millis()
         );
-        let base64 = image_to_base64(&img)?;
-        results.push(base64);
+        results.push(img);
     }
 
     Ok(results)
 }
 
-/// Given a full-screen image as base64 PNG, crop the specified region
-/// and return as base64 JPEG (smaller and faster than PNG for screenshots).
+/// Crop a region of a frozen monitor screenshot and return it as base64 JPEG
+/// (smaller and faster than PNG for screenshots).
 pub fn capture_region_from_full(
-    full_base64: &str,
+    full: &RgbaImage,
     x: u32,
     y: u32,
     width: u32,
     height: u32,
 ) -> anyhow::Result<String> {
-    info!(
-        "[Capture] capture_region_from_full, region=({},{},{}x{}), full base64 size={}",
-        x,
-        y,
-        width,
-        height,
-        full_base64.len()
-    );
-    let bytes = base64::engine::general_purpose::STANDARD.decode(full_base64)?;
-    let img = image::load_from_memory_with_format(&bytes, ImageFormat::Png)?;
-    info!(
-        "[Capture] 原图解码完成, 尺寸={}x{}",
-        img.width(),
-        img.height()
-    );
-    let cropped = img.crop_imm(x, y, width, height);
-    image_to_jpeg_base64(&cropped)
+    let cropped = crop_region(full, x, y, width, height);
+    Ok(base64::engine::general_purpose::STANDARD.encode(encode_jpeg(&cropped)?))
 }
 
-fn image_to_base64(img: &image::RgbaImage) -> anyhow::Result<String> {
+/// Encode a full frozen screenshot as PNG bytes, for the overlay window.
+pub fn encode_png(img: &RgbaImage) -> anyhow::Result<Vec<u8>> {
     let mut buf = Cursor::new(Vec::new());
     img.write_to(&mut buf, ImageFormat::Png)?;
-    Ok(base64::engine::general_purpose::STANDARD.encode(buf.into_inner()))
+    Ok(buf.into_inner())
 }
 
-fn image_to_jpeg_base64(img: &image::DynamicImage) -> anyhow::Result<String> {
-    let rgb = img.to_rgb8();
-    let mut buf = Cursor::new(Vec::new());
-    let mut encoder = JpegEncoder::new_with_quality(&mut buf, 90);
-    encoder.encode_image(&DynamicImage::ImageRgb8(rgb))?;
-    Ok(base64::engine::general_purpose::STANDARD.encode(buf.into_inner()))
+/// Encode an image as PNG with the fastest compression level and no row
+/// filtering, for images that only travel from the backend to a local
+/// WebView (overlay / pin): encode time matters there, file size does not.
+pub fn encode_png_fast(img: &RgbaImage) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    PngEncoder::new_with_quality(&mut buf, CompressionType::Fast, FilterType::NoFilter)
+        .write_image(
+            img.as_raw(),
+            img.width(),
+            img.height(),
+            ExtendedColorType::Rgba8,
+        )?;
+    Ok(buf)
 }
 
-/// Given a full-screen image as base64 PNG, crop the specified region and return
-/// the cropped image bytes as JPEG (no base64 encoding — avoids double encoding
-/// when the caller only needs the raw image for further processing).
+/// Crop a region of a frozen monitor screenshot and return the cropped image
+/// bytes as JPEG (no base64 encoding — avoids double encoding when the caller
+/// only needs the raw image for further processing).
 pub fn capture_region_bytes(
-    full_base64: &str,
+    full: &RgbaImage,
     x: u32,
     y: u32,
     width: u32,
     height: u32,
 ) -> anyhow::Result<Vec<u8>> {
-    let bytes = base64::engine::general_purpose::STANDARD.decode(full_base64)?;
-    let img = image::load_from_memory_with_format(&bytes, ImageFormat::Png)?;
-    let img_width = img.width();
-    let img_height = img.height();
+    let cropped = crop_region(full, x, y, width, height);
+    encode_jpeg(&cropped)
+}
+
+/// The region `crop_region` actually crops out of an `img_width` x
+/// `img_height` image: the origin is kept inside the image and the size is cut
+/// to what remains (at least 1x1). Callers that map results back to
+/// screenshot coordinates must offset by this origin, not the requested one.
+pub fn clamp_region(
+    img_width: u32,
+    img_height: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> (u32, u32, u32, u32) {
     let crop_x = x.min(img_width.saturating_sub(1));
     let crop_y = y.min(img_height.saturating_sub(1));
     let crop_width = width.min(img_width.saturating_sub(crop_x)).max(1);
     let crop_height = height.min(img_height.saturating_sub(crop_y)).max(1);
+    (crop_x, crop_y, crop_width, crop_height)
+}
+
+/// Crop a region of a frozen monitor screenshot, clamped to the image bounds.
+/// Lossless, for callers that draw on the crop before encoding.
+pub fn crop_region(full: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> DynamicImage {
+    let started = Instant::now();
+    let (img_width, img_height) = full.dimensions();
+    let (crop_x, crop_y, crop_width, crop_height) =
+        clamp_region(img_width, img_height, x, y, width, height);
+    let cropped =
+        image::imageops::crop_imm(full, crop_x, crop_y, crop_width, crop_height).to_image();
 
     info!(
-        "[Capture] capture_region_bytes, full={}x{}, requested=({},{},{}x{}), clamped=({},{},{}x{})",
+        "[Capture] crop_region, full={}x{}, requested=({},{},{}x{}), clamped=({},{},{}x{}), 耗时 {}ms",
         img_width,
         img_height,
         x,
@@ -419,13 +564,30 @@ pub fn capture_region_bytes(
         crop_x,
         crop_y,
         crop_width,
-        crop_height
+        crop_height,
+        started.elapsed().as_millis()
     );
 
-    let cropped = img.crop_imm(crop_x, crop_y, crop_width, crop_height);
-    let rgb = cropped.to_rgb8();
-    let mut buf = Cursor::new(Vec::new());
-    let mut encoder = JpegEncoder::new_with_quality(&mut buf, 90);
-    encoder.encode_image(&DynamicImage::ImageRgb8(rgb))?;
-    Ok(buf.into_inner())
+    DynamicImage::ImageRgba8(cropped)
+}
+
+/// Encode an image as JPEG bytes (quality 90).
+pub fn encode_jpeg(img: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    super::save::encode_image(img, super::save::SaveFormat::Jpeg, 90)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_region_keeps_the_origin_inside_the_image() {
+        assert_eq!(clamp_region(100, 50, 10, 5, 20, 10), (10, 5, 20, 10));
+        assert_eq!(clamp_region(100, 50, 90, 40, 20, 20), (90, 40, 10, 10));
+        assert_eq!(clamp_region(100, 50, 500, 500, 20, 20), (99, 49, 1, 1));
+        assert_eq!(
+            clamp_region(100, 50, 0, 0, u32::MAX, u32::MAX),
+            (0, 0, 100, 50)
+        );
+    }
 }
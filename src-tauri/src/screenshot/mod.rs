@@ -1,5 +1,12 @@
 pub mod capture;
+#[cfg(not(target_os = "macos"))]
+pub mod monitors;
+pub mod pin;
+pub mod save;
+pub mod scroll;
+pub mod window;
 
 pub use capture::{
-    capture_monitors, capture_region_bytes, capture_region_from_full, list_window_rects,
+    capture_monitors, capture_region_bytes, capture_region_from_full, clamp_region, crop_region,
+    encode_jpeg, encode_png, encode_png_fast, list_window_rects,
 };
@@ -0,0 +1,223 @@
+use crate::config::MonitorInfo;
+use std::fmt;
+
+/// Allowed difference (physical pixels) in position and size, to absorb
+/// rounding between the two backends under fractional scaling.
+const GEOMETRY_TOLERANCE: u32 = 2;
+
+/// Why a Tauri monitor could not be paired with a captured (xcap) monitor.
+#[derive(Debug)]
+pub enum MonitorMatchError {
+    /// xcap reported no monitors at all.
+    NoMonitors,
+    /// No remaining captured monitor has the geometry of Tauri monitor `index`.
+    Unmatched {
+        index: usize,
+        monitor: MonitorInfo,
+        available: Vec<MonitorInfo>,
+    },
+}
+
+fn describe(m: &MonitorInfo) -> String {
+    format!(
+        "{} ({},{} {}x{} @{})",
+        m.name, m.x, m.y, m.width, m.height, m.scale_factor
+    )
+}
+
+impl fmt::Display for MonitorMatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoMonitors => write!(f, "No monitor found"),
+            Self::Unmatched {
+                index,
+                monitor,
+                available,
+            } => {
+                let available: Vec<String> = available.iter().map(describe).collect();
+                write!(
+                    f,
+                    "No captured monitor matches monitor {} {}; available: [{}]",
+                    index,
+                    describe(monitor),
+                    available.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonitorMatchError {}
+
+fn same_geometry(a: &MonitorInfo, b: &MonitorInfo) -> bool {
+    a.x.abs_diff(b.x) <= GEOMETRY_TOLERANCE
+        && a.y.abs_diff(b.y) <= GEOMETRY_TOLERANCE
+        && a.width.abs_diff(b.width) <= GEOMETRY_TOLERANCE
+        && a.height.abs_diff(b.height) <= GEOMETRY_TOLERANCE
+}
+
+/// Lower is better. A scale mismatch outweighs any pixel offset inside the
+/// tolerance, so mirrored displays with equal geometry pair up by scale.
+fn match_cost(a: &MonitorInfo, b: &MonitorInfo) -> f64 {
+    let offset = a.x.abs_diff(b.x)
+        + a.y.abs_diff(b.y)
+        + a.width.abs_diff(b.width)
+        + a.height.abs_diff(b.height);
+    (a.scale_factor - b.scale_factor).abs() * 1000.0 + offset as f64
+}
+
+/// Pair each Tauri monitor with a captured monitor by physical position and
+/// size, using the scale factor to break ties. Returns, in Tauri order, the
+/// index into `captured` for each monitor; each captured monitor is used once.
+///
+/// Scale is not a hard requirement: on X11 xcap reports one global scale
+/// (Xft.dpi) that may differ from the toolkit's per-monitor value.
+pub fn match_monitors(
+    tauri: &[MonitorInfo],
+    captured: &[MonitorInfo],
+) -> Result<Vec<usize>, MonitorMatchError> {
+    if captured.is_empty() {
+        return Err(MonitorMatchError::NoMonitors);
+    }
+
+    let mut used = vec![false; captured.len()];
+    let mut matches = Vec::with_capacity(tauri.len());
+    for (index, monitor) in tauri.iter().enumerate() {
+        let best = captured
+            .iter()
+            .enumerate()
+            .filter(|(i, candidate)| !used[*i] && same_geometry(monitor, candidate))
+            .min_by(|(_, a), (_, b)| match_cost(monitor, a).total_cmp(&match_cost(monitor, b)))
+            .map(|(i, _)| i);
+        let Some(i) = best else {
+            return Err(MonitorMatchError::Unmatched {
+                index,
+                monitor: monitor.clone(),
+                available: captured
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !used[*i])
+                    .map(|(_, candidate)| candidate.clone())
+                    .collect(),
+            });
+        };
+        used[i] = true;
+        matches.push(i);
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32, scale: f64) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            x,
+            y,
+            width,
+            height,
+            scale_factor: scale,
+        }
+    }
+
+    #[test]
+    fn pairs_reordered_monitors_by_geometry() {
+        let tauri = [
+            monitor("DP-1", 0, 0, 2560, 1440, 1.0),
+            monitor("HDMI-1", 2560, 0, 1920, 1080, 1.0),
+            monitor("DP-2", 4480, 0, 1920, 1080, 1.0),
+        ];
+        let captured = [
+            monitor("DP-2", 4480, 0, 1920, 1080, 1.0),
+            monitor("DP-1", 0, 0, 2560, 1440, 1.0),
+            monitor("HDMI-1", 2560, 0, 1920, 1080, 1.0),
+        ];
+        assert_eq!(match_monitors(&tauri, &captured).unwrap(), [1, 2, 0]);
+    }
+
+    #[test]
+    fn pairs_mixed_dpi_with_rounding() {
+        // 4K panel at 200% next to a 1080p display at 100%; xcap rounds the
+        // fractional layout one pixel differently and reports one global scale.
+        let tauri = [
+            monitor("Built-in", 0, 0, 3840, 2160, 2.0),
+            monitor("External", 3840, 0, 1920, 1080, 1.0),
+        ];
+        let captured = [
+            monitor("External", 3841, 0, 1920, 1079, 1.0),
+            monitor("Built-in", 0, 0, 3840, 2160, 1.0),
+        ];
+        assert_eq!(match_monitors(&tauri, &captured).unwrap(), [1, 0]);
+    }
+
+    #[test]
+    fn pairs_negative_origin_secondary() {
+        let tauri = [
+            monitor("Primary", 0, 0, 2560, 1440, 1.0),
+            monitor("Left", -1920, -360, 1920, 1080, 1.0),
+        ];
+        let captured = [
+            monitor("Left", -1920, -360, 1920, 1080, 1.0),
+            monitor("Primary", 0, 0, 2560, 1440, 1.0),
+        ];
+        assert_eq!(match_monitors(&tauri, &captured).unwrap(), [1, 0]);
+    }
+
+    #[test]
+    fn pairs_mirrored_displays_by_scale_then_order() {
+        let tauri = [
+            monitor("Panel", 0, 0, 1920, 1080, 1.5),
+            monitor("Projector", 0, 0, 1920, 1080, 1.0),
+        ];
+        let captured = [
+            monitor("Projector", 0, 0, 1920, 1080, 1.0),
+            monitor("Panel", 0, 0, 1920, 1080, 1.5),
+        ];
+        assert_eq!(match_monitors(&tauri, &captured).unwrap(), [1, 0]);
+
+        // Identical geometry and scale: each captured monitor is still used
+        // exactly once, in order.
+        let same = [
+            monitor("A", 0, 0, 1920, 1080, 1.0),
+            monitor("B", 0, 0, 1920, 1080, 1.0),
+        ];
+        assert_eq!(match_monitors(&same, &same).unwrap(), [0, 1]);
+    }
+
+    #[test]
+    fn reports_unmatched_monitor() {
+        let tauri = [
+            monitor("DP-1", 0, 0, 2560, 1440, 1.0),
+            monitor("HDMI-1", 2560, 0, 1920, 1080, 1.0),
+        ];
+        let captured = [
+            monitor("DP-1", 0, 0, 2560, 1440, 1.0),
+            monitor("HDMI-1", 2560, 0, 1280, 720, 1.0),
+        ];
+        match match_monitors(&tauri, &captured) {
+            Err(MonitorMatchError::Unmatched {
+                index,
+                monitor,
+                available,
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(monitor.name, "HDMI-1");
+                // Already paired monitors are not offered again.
+                assert_eq!(available.len(), 1);
+                assert_eq!(available[0].width, 1280);
+            }
+            other => panic!("expected Unmatched, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_capture_backend_monitors() {
+        let tauri = [monitor("DP-1", 0, 0, 1920, 1080, 1.0)];
+        assert!(matches!(
+            match_monitors(&tauri, &[]),
+            Err(MonitorMatchError::NoMonitors)
+        ));
+    }
+}
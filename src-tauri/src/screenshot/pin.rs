@@ -0,0 +1,11 @@
+use image::RgbaImage;
+use std::sync::Arc;
+
+/// An image pinned to the screen, kept in `AppState.pins` until its window
+/// is destroyed.
+pub struct PinnedImage {
+    pub image: Arc<RgbaImage>,
+    /// Scale factor of the monitor it was captured on; the window shows the
+    /// image at 1 image pixel per physical pixel at 100 % zoom.
+    pub scale_factor: f64,
+}
@@ -0,0 +1,177 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Default file name template for auto-saved screenshots.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "TransShot_{date}_{time}";
+
+/// Folder created under the user's Pictures directory when no save
+/// directory is configured.
+pub const DEFAULT_SAVE_DIR_NAME: &str = "DH-TransShot";
+
+/// Collision suffixes tried before giving up (`name-2` … `name-999`).
+const MAX_COLLISION_SUFFIX: u32 = 999;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveFormat {
+    #[default]
+    Png,
+    /// Lossy, with the configured quality.
+    Jpeg,
+    /// Lossless WebP (the `image` crate has no lossy WebP encoder).
+    Webp,
+}
+
+impl SaveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Format implied by a file extension (case-insensitive).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    fn image_format(self) -> ImageFormat {
+        match self {
+            Self::Png => ImageFormat::Png,
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Webp => ImageFormat::WebP,
+        }
+    }
+}
+
+/// What to do when the rendered file name already exists.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionPolicy {
+    /// Append `-2`, `-3`, … before the extension.
+    #[default]
+    Suffix,
+    Overwrite,
+}
+
+/// Encode an image in `format`; `jpeg_quality` (1–100) only applies to JPEG.
+pub fn encode_image(
+    img: &DynamicImage,
+    format: SaveFormat,
+    jpeg_quality: u8,
+) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Cursor::new(Vec::new());
+    match format {
+        SaveFormat::Jpeg => {
+            let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+            JpegEncoder::new_with_quality(&mut buf, jpeg_quality.clamp(1, 100))
+                .encode_image(&rgb)?;
+        }
+        SaveFormat::Png | SaveFormat::Webp => {
+            DynamicImage::ImageRgba8(img.to_rgba8()).write_to(&mut buf, format.image_format())?;
+        }
+    }
+    Ok(buf.into_inner())
+}
+
+/// Re-encode `bytes` when they are not already in the format implied by the
+/// extension of `path`; unknown extensions and matching data pass through.
+pub fn transcode_for_path(
+    path: &Path,
+    bytes: Vec<u8>,
+    jpeg_quality: u8,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(target) = SaveFormat::from_path(path) else {
+        return Ok(bytes);
+    };
+    if image::guess_format(&bytes).ok() == Some(target.image_format()) {
+        return Ok(bytes);
+    }
+    let img = image::load_from_memory(&bytes)?;
+    encode_image(&img, target, jpeg_quality)
+}
+
+/// Values substituted into the file name template.
+pub struct FilenameContext<'a> {
+    pub now: chrono::DateTime<chrono::Local>,
+    pub monitor_index: usize,
+    pub mode: &'a str,
+    pub counter: u64,
+}
+
+/// Render a file name (without extension) from `template`.
+///
+/// Placeholders: `{date}` (2024-05-01), `{time}` (13-45-09), `{datetime}`
+/// (20240501-134509), `{monitor}`, `{mode}`, `{counter}` (zero-padded to 3).
+/// Characters not allowed in file names are replaced with `_`.
+pub fn render_filename(template: &str, ctx: &FilenameContext) -> String {
+    let template = if template.trim().is_empty() {
+        DEFAULT_FILENAME_TEMPLATE
+    } else {
+        template
+    };
+    let rendered = template
+        .replace("{date}", &ctx.now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &ctx.now.format("%H-%M-%S").to_string())
+        .replace("{datetime}", &ctx.now.format("%Y%m%d-%H%M%S").to_string())
+        .replace("{monitor}", &ctx.monitor_index.to_string())
+        .replace("{mode}", ctx.mode)
+        .replace("{counter}", &format!("{:03}", ctx.counter));
+    let sanitized: String = rendered
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_matches('.');
+    if sanitized.is_empty() {
+        "screenshot".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Final path for `stem.ext` in `dir` under `policy`. Checks the file system.
+pub fn resolve_path(
+    dir: &Path,
+    stem: &str,
+    format: SaveFormat,
+    policy: CollisionPolicy,
+) -> anyhow::Result<PathBuf> {
+    let ext = format.extension();
+    let path = dir.join(format!("{}.{}", stem, ext));
+    if policy == CollisionPolicy::Overwrite || !path.exists() {
+        return Ok(path);
+    }
+    (2..=MAX_COLLISION_SUFFIX)
+        .map(|n| dir.join(format!("{}-{}.{}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| anyhow::anyhow!("文件名冲突过多: {}", path.display()))
+}
+
+/// One auto-saved screenshot, newest first in `RecentSaves`.
+#[derive(Debug, Serialize, Clone)]
+pub struct SavedScreenshot {
+    pub path: String,
+    pub format: SaveFormat,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: usize,
+    /// Unix time in milliseconds.
+    pub saved_at: i64,
+    /// Sidecar with the unannotated capture and its shapes, for annotated
+    /// screenshots; see `render::annotate`.
+    pub annotations: Option<String>,
+}
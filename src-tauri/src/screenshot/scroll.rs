@@ -0,0 +1,315 @@
+use image::RgbaImage;
+
+/// Fraction of informative overlap rows that must match for a scroll offset
+/// to be accepted; the rest absorbs blinking carets and hover highlights.
+const MATCH_THRESHOLD: f64 = 0.9;
+
+/// Minimum number of non-uniform rows in the overlap. Blank rows match at any
+/// offset, so they do not count as evidence.
+const MIN_INFORMATIVE_ROWS: usize = 8;
+
+/// A static header or footer may take at most this fraction of the frame;
+/// anything larger is treated as "nothing scrolled".
+const MAX_STATIC_BAND_RATIO: usize = 3;
+
+/// Height limit of the stitched image in pixels.
+pub const MAX_STITCHED_HEIGHT: u32 = 30_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct RowSig {
+    hash: u64,
+    /// Every pixel in the row is the same colour.
+    uniform: bool,
+}
+
+fn row_signatures(img: &RgbaImage) -> Vec<RowSig> {
+    img.rows()
+        .map(|row| {
+            // FNV-1a over RGB; alpha is always opaque in screen captures.
+            let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+            let mut first = None;
+            let mut uniform = true;
+            for pixel in row {
+                let rgb = [pixel[0], pixel[1], pixel[2]];
+                match first {
+                    None => first = Some(rgb),
+                    Some(first) if first != rgb => uniform = false,
+                    Some(_) => {}
+                }
+                for byte in rgb {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+            RowSig { hash, uniform }
+        })
+        .collect()
+}
+
+/// Rows that are identical at the same position in both frames, counted from
+/// the top and from the bottom (sticky headers, toolbars, input boxes).
+fn static_bands(prev: &[RowSig], next: &[RowSig]) -> (usize, usize) {
+    let limit = prev.len() / MAX_STATIC_BAND_RATIO;
+    let top = prev
+        .iter()
+        .zip(next)
+        .take(limit)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let bottom = prev
+        .iter()
+        .rev()
+        .zip(next.iter().rev())
+        .take(limit)
+        .take_while(|(a, b)| a == b)
+        .count();
+    (top, bottom)
+}
+
+/// How far the content between the static bands moved up, in rows: the
+/// offset whose overlap has the highest share of matching informative rows,
+/// the smaller offset winning ties. `Some(0)` if the frame is unchanged up to
+/// the threshold, `None` if no offset reaches it.
+fn find_scroll_offset(prev: &[RowSig], next: &[RowSig]) -> Option<usize> {
+    let len = prev.len();
+    let mut best: Option<(usize, f64)> = None;
+    for dy in 0..len {
+        let mut informative = 0;
+        let mut matched = 0;
+        for (before, after) in prev[dy..].iter().zip(next) {
+            if before.uniform {
+                continue;
+            }
+            informative += 1;
+            if before == after {
+                matched += 1;
+            }
+        }
+        if informative < MIN_INFORMATIVE_ROWS {
+            // Overlaps only get smaller from here.
+            break;
+        }
+        let ratio = matched as f64 / informative as f64;
+        if dy == 0 && ratio >= MATCH_THRESHOLD {
+            return Some(0);
+        }
+        let better = match best {
+            Some((_, best_ratio)) => ratio > best_ratio,
+            None => true,
+        };
+        if ratio >= MATCH_THRESHOLD && better {
+            best = Some((dy, ratio));
+        }
+    }
+    best.map(|(dy, _)| dy)
+}
+
+/// Result of feeding one frame to a `ScrollStitcher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// The frame shows the same content as the last accepted one.
+    Unchanged,
+    /// New content of this many rows was appended.
+    Appended(u32),
+    /// No overlap with the last accepted frame (scrolled back, jumped, or the
+    /// content changed); the frame is dropped and the next one is compared
+    /// with the last accepted frame again.
+    NoMatch,
+    /// Appending would exceed `MAX_STITCHED_HEIGHT`; the frame is dropped.
+    LimitReached,
+}
+
+/// Stitches frames of one screen region, captured while the user scrolls,
+/// into a single tall image.
+///
+/// Each new frame is compared with the last accepted frame by row hashes.
+/// Rows that stay in place at the top and bottom (sticky headers, footers)
+/// are excluded from matching, so they appear once at the start and once at
+/// the end instead of repeating between scroll steps.
+pub struct ScrollStitcher {
+    width: u32,
+    /// Stitched RGBA rows, without the static footer of `last`.
+    body: Vec<u8>,
+    last: RgbaImage,
+    last_rows: Vec<RowSig>,
+    /// Static bottom rows of `last`, added back by `finish`.
+    footer: u32,
+    frames: usize,
+}
+
+impl ScrollStitcher {
+    pub fn new(first: RgbaImage) -> Self {
+        Self {
+            width: first.width(),
+            last_rows: row_signatures(&first),
+            body: first.as_raw().clone(),
+            last: first,
+            footer: 0,
+            frames: 1,
+        }
+    }
+
+    fn row_bytes(&self) -> usize {
+        self.width as usize * 4
+    }
+
+    fn body_height(&self) -> u32 {
+        (self.body.len() / self.row_bytes()) as u32
+    }
+
+    /// Frames accepted so far (the first one included).
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Height of the image `finish` would return.
+    pub fn height(&self) -> u32 {
+        self.body_height() + self.footer
+    }
+
+    pub fn push(&mut self, frame: RgbaImage) -> PushOutcome {
+        if frame.dimensions() != self.last.dimensions() {
+            return PushOutcome::NoMatch;
+        }
+        let rows = row_signatures(&frame);
+        let (top, bottom) = static_bands(&self.last_rows, &rows);
+        let len = rows.len();
+        if top + bottom >= len {
+            return PushOutcome::Unchanged;
+        }
+        let Some(dy) =
+            find_scroll_offset(&self.last_rows[top..len - bottom], &rows[top..len - bottom])
+        else {
+            return PushOutcome::NoMatch;
+        };
+        if dy == 0 {
+            return PushOutcome::Unchanged;
+        }
+
+        let height = frame.height() as usize;
+        let footer = bottom as u32;
+        if self.height() - footer + dy as u32 > MAX_STITCHED_HEIGHT {
+            return PushOutcome::LimitReached;
+        }
+
+        // The body must end where this frame's footer starts: drop rows that
+        // turned out to be footer, or take back rows that no longer are.
+        let row_bytes = self.row_bytes();
+        if footer > self.footer {
+            let drop = (footer - self.footer) as usize * row_bytes;
+            self.body.truncate(self.body.len() - drop);
+        } else if footer < self.footer {
+            let from = (height - self.footer as usize) * row_bytes;
+            let to = (height - bottom) * row_bytes;
+            self.body.extend_from_slice(&self.last.as_raw()[from..to]);
+        }
+        let from = (height - bottom - dy) * row_bytes;
+        let to = (height - bottom) * row_bytes;
+        self.body.extend_from_slice(&frame.as_raw()[from..to]);
+
+        self.last = frame;
+        self.last_rows = rows;
+        self.footer = footer;
+        self.frames += 1;
+        PushOutcome::Appended(dy as u32)
+    }
+
+    /// The stitched image, with the footer of the last accepted frame.
+    pub fn finish(mut self) -> RgbaImage {
+        let row_bytes = self.row_bytes();
+        let height = self.last.height() as usize;
+        let from = (height - self.footer as usize) * row_bytes;
+        self.body.extend_from_slice(&self.last.as_raw()[from..]);
+        let height = self.body_height();
+        RgbaImage::from_raw(self.width, height, self.body)
+            .expect("stitched buffer holds whole rows")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const WIDTH: u32 = 16;
+
+    /// A row no other row in the tests repeats: varies along x (so it is not
+    /// uniform) and encodes `seed` in its colour.
+    fn row(seed: u32) -> Vec<Rgba<u8>> {
+        (0..WIDTH)
+            .map(|x| {
+                Rgba([
+                    (seed * 7 + x * 13) as u8,
+                    seed as u8,
+                    (seed >> 8) as u8,
+                    255,
+                ])
+            })
+            .collect()
+    }
+
+    /// Stack rows `seeds` into a frame.
+    fn frame(seeds: impl IntoIterator<Item = u32>) -> RgbaImage {
+        let rows: Vec<Vec<Rgba<u8>>> = seeds.into_iter().map(row).collect();
+        RgbaImage::from_fn(WIDTH, rows.len() as u32, |x, y| {
+            rows[y as usize][x as usize]
+        })
+    }
+
+    /// 100 rows of scrolling content starting at content row `offset`.
+    fn window(offset: u32) -> RgbaImage {
+        frame(offset..offset + 100)
+    }
+
+    /// `window` between a 10-row sticky header and an 8-row sticky footer.
+    fn with_bars(offset: u32) -> RgbaImage {
+        let header = 50_000..50_010;
+        let footer = 60_000..60_008;
+        frame(header.chain(offset..offset + 100).chain(footer))
+    }
+
+    #[test]
+    fn finds_a_plain_scroll_offset() {
+        let prev = row_signatures(&window(0));
+        assert_eq!(
+            find_scroll_offset(&prev, &row_signatures(&window(37))),
+            Some(37)
+        );
+        assert_eq!(find_scroll_offset(&prev, &prev), Some(0));
+        assert_eq!(
+            find_scroll_offset(&prev, &row_signatures(&window(500))),
+            None
+        );
+    }
+
+    #[test]
+    fn stitches_plain_scroll_steps() {
+        let mut stitcher = ScrollStitcher::new(window(0));
+        assert_eq!(stitcher.push(window(30)), PushOutcome::Appended(30));
+        assert_eq!(stitcher.push(window(75)), PushOutcome::Appended(45));
+        assert_eq!((stitcher.frames(), stitcher.height()), (3, 175));
+        assert_eq!(stitcher.finish(), frame(0..175));
+    }
+
+    #[test]
+    fn ignores_frames_without_motion_or_overlap() {
+        let mut stitcher = ScrollStitcher::new(window(0));
+        assert_eq!(stitcher.push(window(0)), PushOutcome::Unchanged);
+        assert_eq!(stitcher.push(window(500)), PushOutcome::NoMatch);
+        assert_eq!(stitcher.push(frame(0..60)), PushOutcome::NoMatch);
+        // Dropped frames do not move the reference frame.
+        assert_eq!(stitcher.push(window(40)), PushOutcome::Appended(40));
+        assert_eq!(stitcher.frames(), 2);
+        assert_eq!(stitcher.finish(), frame(0..140));
+    }
+
+    #[test]
+    fn keeps_sticky_header_and_footer_once() {
+        let mut stitcher = ScrollStitcher::new(with_bars(0));
+        assert_eq!(stitcher.push(with_bars(25)), PushOutcome::Appended(25));
+        assert_eq!(stitcher.push(with_bars(60)), PushOutcome::Appended(35));
+        let expected = frame((50_000..50_010).chain(0..160).chain(60_000..60_008));
+        assert_eq!(stitcher.height(), expected.height());
+        assert_eq!(stitcher.finish(), expected);
+    }
+}
@@ -0,0 +1,128 @@
+use image::RgbaImage;
+use log::info;
+use std::time::Instant;
+
+/// Capture one window's own content rather than what is on screen at its
+/// position, so a window covered by others still comes out whole where the
+/// platform allows it (macOS; Windows via `PrintWindow`; X11 only with a
+/// compositor). `window_id` is the native id (CGWindowID / HWND / X11 window);
+/// `None` picks the frontmost window not owned by this app. Blocking.
+pub fn capture_window(window_id: Option<u32>) -> anyhow::Result<RgbaImage> {
+    let started = Instant::now();
+    #[cfg(target_os = "macos")]
+    let img = capture_window_macos(window_id)?;
+    #[cfg(not(target_os = "macos"))]
+    let img = capture_window_xcap(window_id)?;
+    info!(
+        "[Capture] 窗口截图完成, size={}x{}, 耗时 {}ms",
+        img.width(),
+        img.height(),
+        started.elapsed().as_millis()
+    );
+    Ok(img)
+}
+
+#[cfg(target_os = "macos")]
+fn capture_window_macos(window_id: Option<u32>) -> anyhow::Result<RgbaImage> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    struct CGRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        static CGRectNull: CGRect;
+        fn CGWindowListCreateImage(
+            rect: CGRect,
+            option: u32,
+            window_id: u32,
+            image_option: u32,
+        ) -> *const c_void;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    // kCGWindowListOptionIncludingWindow
+    const INCLUDING_WINDOW: u32 = 1 << 3;
+    // kCGWindowImageBoundsIgnoreFraming (no shadow) | kCGWindowImageBestResolution
+    const IMAGE_OPTIONS: u32 = (1 << 0) | (1 << 3);
+
+    let id = match window_id {
+        Some(id) => id,
+        None => {
+            let own_pid = std::process::id() as i32;
+            let window = super::capture::list_windows_macos()
+                .into_iter()
+                .find(|window| window.owner_pid != own_pid)
+                .ok_or_else(|| anyhow::anyhow!("没有可截取的窗口"))?;
+            info!(
+                "[Capture] 当前活动窗口: id={}, pid={}, bounds=({},{},{}x{})",
+                window.id,
+                window.owner_pid,
+                window.rect.x,
+                window.rect.y,
+                window.rect.width,
+                window.rect.height
+            );
+            window.id
+        }
+    };
+
+    unsafe {
+        // CGRectNull = the window's own bounds
+        let cg_image = CGWindowListCreateImage(CGRectNull, INCLUDING_WINDOW, id, IMAGE_OPTIONS);
+        if cg_image.is_null() {
+            anyhow::bail!(
+                "CGWindowListCreateImage returned null for window {} - check screen recording permission",
+                id
+            );
+        }
+        let img = super::capture::cgimage_to_rgba(cg_image);
+        CFRelease(cg_image);
+        img
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture_window_xcap(window_id: Option<u32>) -> anyhow::Result<RgbaImage> {
+    use xcap::Window;
+
+    let windows = Window::all()?;
+    let window = match window_id {
+        Some(id) => windows
+            .into_iter()
+            .find(|window| window.id().ok() == Some(id))
+            .ok_or_else(|| anyhow::anyhow!("窗口不存在: {}", id))?,
+        None => {
+            let own_pid = std::process::id();
+            let mut candidates: Vec<Window> = windows
+                .into_iter()
+                .filter(|window| {
+                    window.pid().ok() != Some(own_pid) && !window.is_minimized().unwrap_or(true)
+                })
+                .collect();
+            // Focused window first, else the topmost one (`all()` is front to back).
+            let index = candidates
+                .iter()
+                .position(|window| window.is_focused().unwrap_or(false))
+                .unwrap_or(0);
+            if candidates.is_empty() {
+                anyhow::bail!("没有可截取的窗口");
+            }
+            candidates.swap_remove(index)
+        }
+    };
+
+    info!(
+        "[Capture] 截取窗口: id={:?}, app={:?}, title={:?}",
+        window.id().ok(),
+        window.app_name().ok(),
+        window.title().ok()
+    );
+    Ok(window.capture_image()?)
+}
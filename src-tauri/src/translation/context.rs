@@ -0,0 +1,82 @@
+/// Longest context snippet (in chars) injected into the prompt. OCR of a whole
+/// window can be large; the translator only needs the neighbourhood.
+const MAX_CONTEXT_CHARS: usize = 2000;
+
+/// Longest history entry (in chars, per side) injected into the prompt.
+const MAX_HISTORY_ENTRY_CHARS: usize = 200;
+
+/// Extra, non-translatable information that helps disambiguate short inputs
+/// such as "Save" or "Bank".
+#[derive(Debug, Default, Clone)]
+pub struct TranslationContext {
+    /// Domain hint, e.g. "finance", "software UI", "medical".
+    pub domain: Option<String>,
+    /// Free-form surrounding text: neighbouring OCR text, source window title...
+    pub surrounding: Option<String>,
+    /// Previous (source, translation) pairs of this session, oldest first.
+    pub history: Vec<(String, String)>,
+}
+
+impl TranslationContext {
+    pub fn is_empty(&self) -> bool {
+        non_empty(&self.domain).is_none()
+            && non_empty(&self.surrounding).is_none()
+            && self.history.is_empty()
+    }
+
+    /// Prompt section appended to the translator's system prompt. The context
+    /// is fenced in `<context>` tags and explicitly marked as reference-only so
+    /// the model never echoes or translates it.
+    pub fn prompt_section(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::new();
+        if let Some(domain) = non_empty(&self.domain) {
+            section.push_str(&format!(
+                "\n\nDomain: {}. Prefer the terminology used in this domain.",
+                domain
+            ));
+        }
+
+        let surrounding = non_empty(&self.surrounding);
+        if surrounding.is_none() && self.history.is_empty() {
+            return section;
+        }
+
+        section.push_str(
+            "\n\nThe <context> block below is reference material only. Use it to \
+             disambiguate meaning and keep terminology consistent. Do NOT translate \
+             it and do NOT include it in your output.\n<context>",
+        );
+        if let Some(text) = surrounding {
+            section.push_str("\nSurrounding text:\n");
+            section.push_str(&truncate_chars(text, MAX_CONTEXT_CHARS));
+        }
+        if !self.history.is_empty() {
+            section.push_str("\nPrevious translations in this session:");
+            for (source, translated) in &self.history {
+                section.push_str(&format!(
+                    "\n- {} => {}",
+                    truncate_chars(source, MAX_HISTORY_ENTRY_CHARS),
+                    truncate_chars(translated, MAX_HISTORY_ENTRY_CHARS)
+                ));
+            }
+        }
+        section.push_str("\n</context>");
+        section
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let single_line = text.trim().replace('\n', " ");
+    match single_line.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &single_line[..idx]),
+        None => single_line,
+    }
+}
@@ -1,3 +1,5 @@
+pub mod context;
 pub mod openai_compat;
 
+pub use context::TranslationContext;
 pub use openai_compat::OpenAiCompatProvider;
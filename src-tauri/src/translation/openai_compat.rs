@@ -1,3 +1,4 @@
+use super::TranslationContext;
 use log::info;
 use reqwest::Client;
 
@@ -10,6 +11,7 @@ impl OpenAiCompatProvider {
         Self { client }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn translate(
         &self,
         text: &str,
@@ -19,6 +21,7 @@ impl OpenAiCompatProvider {
         api_key: &str,
         model: &str,
         extra: &str,
+        context: &TranslationContext,
     ) -> anyhow::Result<String> {
         let source_display = if source_lang == "auto" {
             "the detected language".to_string()
@@ -29,12 +32,40 @@ impl OpenAiCompatProvider {
         let system_prompt = format!(
             "You are a translator. Translate the following text from {} to {}. \
              Output ONLY the translated text, nothing else. \
-             Do not add explanations, notes, or any extra content.",
-            source_display, target_lang
+             Do not add explanations, notes, or any extra content.{}",
+            source_display,
+            target_lang,
+            context.prompt_section()
         );
 
+        info!("[Translation] with_context={}", !context.is_empty());
+        self.complete(
+            &system_prompt,
+            text,
+            base_url,
+            api_key,
+            model,
+            extra,
+            "Translation",
+        )
+        .await
+    }
+
+    /// Run a single system-prompt + user-text chat completion. Shared by
+    /// translation and text actions (polish, summarize, ...).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn complete(
+        &self,
+        system_prompt: &str,
+        text: &str,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        extra: &str,
+        tag: &str,
+    ) -> anyhow::Result<String> {
         let url = crate::api_client::chat_completions_url(base_url);
-        info!("[Translation] 发送请求到 {}, model={}", url, model);
+        info!("[{}] 发送请求到 {}, model={}", tag, url, model);
 
         let request_body = serde_json::json!({
             "model": model,
@@ -51,7 +82,7 @@ impl OpenAiCompatProvider {
             api_key,
             extra,
             request_body,
-            "Translation",
+            tag,
         )
         .await
     }
@@ -1,19 +1,123 @@
+use crate::actions::{self, ACTION_EVENT_PREFIX};
 use crate::config::{AppState, HotkeyConfig};
-use log::info;
+use log::{info, warn};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{IsMenuItem, Menu, MenuItem, Submenu},
     tray::TrayIconBuilder,
-    Emitter, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
 
+const TRAY_ID: &str = "main";
+
 pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let hotkeys = app
+    let menu = build_menu(app.handle())?;
+
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(Image::from_path("icons/32x32.png").unwrap_or_else(|_| {
+            app.default_window_icon().cloned().unwrap_or_else(|| {
+                Image::from_bytes(include_bytes!("../icons/32x32.png"))
+                    .expect("Failed to load tray icon")
+            })
+        }))
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "show" => {
+                info!("[Tray] 点击: 显示窗口");
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "screenshot" => {
+                info!("[Tray] 点击: 区域截图");
+                let _ = app.emit("tray-action", "screenshot");
+            }
+            "delayed_screenshot" => {
+                info!("[Tray] 点击: 延时截图");
+                let _ = app.emit("tray-action", "delayed_screenshot");
+            }
+            "fullscreen_screenshot" => {
+                info!("[Tray] 点击: 全屏截图");
+                let _ = app.emit("tray-action", "fullscreen_screenshot");
+            }
+            "window_screenshot" => {
+                info!("[Tray] 点击: 窗口截图");
+                let _ = app.emit("tray-action", "window_screenshot");
+            }
+            "scroll_screenshot" => {
+                info!("[Tray] 点击: 长截图");
+                let _ = app.emit("tray-action", "scroll_screenshot");
+            }
+            "image_translate" => {
+                info!("[Tray] 点击: 图片区域翻译");
+                let _ = app.emit("tray-action", "image_translate");
+            }
+            "decode_codes" => {
+                info!("[Tray] 点击: 识别二维码 / 条码");
+                let _ = app.emit("tray-action", "decode_codes");
+            }
+            "scroll_ocr_translate" => {
+                info!("[Tray] 点击: 长截图翻译");
+                let _ = app.emit("tray-action", "scroll_ocr_translate");
+            }
+            "ocr_translate" => {
+                info!("[Tray] 点击: 区域翻译");
+                let _ = app.emit("tray-action", "ocr_translate");
+            }
+            "clipboard_translate" => {
+                info!("[Tray] 点击: 翻译选中文本");
+                let _ = app.emit("tray-action", "clipboard_translate");
+            }
+            "clipboard_image_translate" => {
+                info!("[Tray] 点击: 翻译剪贴板图片");
+                let _ = app.emit("tray-action", "clipboard_image_translate");
+            }
+            "quit" => {
+                info!("[Tray] 点击: 退出");
+                app.exit(0);
+            }
+            id if id.starts_with(ACTION_EVENT_PREFIX) => {
+                info!("[Tray] 点击: 文本动作 {}", id);
+                let _ = app.emit("tray-action", id);
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Rebuild the tray menu so hotkey labels and custom text actions reflect the
+/// latest settings.
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                warn!("[Tray] 更新菜单失败: {}", e);
+            }
+        }
+        Err(e) => warn!("[Tray] 构建菜单失败: {}", e),
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let (hotkeys, custom_actions, capture_delay_secs) = app
         .state::<AppState>()
         .settings
         .lock()
-        .map(|settings| settings.hotkeys.clone())
-        .unwrap_or_else(|_| HotkeyConfig::default());
+        .map(|settings| {
+            (
+                settings.hotkeys.clone(),
+                settings.actions.clone(),
+                settings.screenshot.capture_delay_secs,
+            )
+        })
+        .unwrap_or_else(|_| (HotkeyConfig::default(), Vec::new(), 3));
 
     let version_item = MenuItem::with_id(
         app,
@@ -33,6 +137,40 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         true,
         None::<&str>,
     )?;
+    let delayed_label = match hotkeys.delayed_screenshot.trim() {
+        "" => format!("延时截图 ({}s)", capture_delay_secs),
+        hotkey => format!("延时截图 ({}s, {})", capture_delay_secs, hotkey),
+    };
+    let delayed_screenshot =
+        MenuItem::with_id(app, "delayed_screenshot", delayed_label, true, None::<&str>)?;
+    let fullscreen_screenshot = MenuItem::with_id(
+        app,
+        "fullscreen_screenshot",
+        optional_hotkey_label("全屏截图", &hotkeys.fullscreen_screenshot),
+        true,
+        None::<&str>,
+    )?;
+    let window_screenshot = MenuItem::with_id(
+        app,
+        "window_screenshot",
+        optional_hotkey_label("窗口截图", &hotkeys.window_screenshot),
+        true,
+        None::<&str>,
+    )?;
+    let scroll_screenshot = MenuItem::with_id(
+        app,
+        "scroll_screenshot",
+        optional_hotkey_label("长截图", &hotkeys.scroll_screenshot),
+        true,
+        None::<&str>,
+    )?;
+    let scroll_ocr_translate = MenuItem::with_id(
+        app,
+        "scroll_ocr_translate",
+        "长截图翻译",
+        true,
+        None::<&str>,
+    )?;
     let ocr_translate = MenuItem::with_id(
         app,
         "ocr_translate",
@@ -40,6 +178,15 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         true,
         None::<&str>,
     )?;
+    let image_translate = MenuItem::with_id(
+        app,
+        "image_translate",
+        "图片区域翻译（译文绘制在原图上）",
+        true,
+        None::<&str>,
+    )?;
+    let decode_codes =
+        MenuItem::with_id(app, "decode_codes", "识别二维码 / 条码", true, None::<&str>)?;
     let clipboard_translate = MenuItem::with_id(
         app,
         "clipboard_translate",
@@ -47,59 +194,107 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         true,
         None::<&str>,
     )?;
+    let clipboard_image_translate = MenuItem::with_id(
+        app,
+        "clipboard_image_translate",
+        "翻译剪贴板图片",
+        true,
+        None::<&str>,
+    )?;
+
+    let action_items = actions::all_actions(&custom_actions)
+        .into_iter()
+        .map(|action| {
+            let label = match hotkeys.actions.get(&action.id) {
+                Some(hotkey) if !hotkey.trim().is_empty() => {
+                    format!("{} ({})", action.name, hotkey)
+                }
+                _ => action.name.clone(),
+            };
+            MenuItem::with_id(
+                app,
+                format!("{}{}", ACTION_EVENT_PREFIX, action.id),
+                label,
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let action_refs: Vec<&dyn IsMenuItem<Wry>> = action_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let text_actions = Submenu::with_items(app, "处理选中文本", true, &action_refs)?;
+
     let separator = MenuItem::with_id(app, "sep", "─────────", false, None::<&str>)?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &version_item,
             &version_separator,
             &show,
             &screenshot,
+            &delayed_screenshot,
+            &fullscreen_screenshot,
+            &window_screenshot,
+            &scroll_screenshot,
             &ocr_translate,
+            &image_translate,
+            &scroll_ocr_translate,
             &clipboard_translate,
+            &clipboard_image_translate,
+            &decode_codes,
+            &text_actions,
             &separator,
             &quit,
         ],
-    )?;
+    )
+}
 
-    let _tray = TrayIconBuilder::new()
-        .icon(Image::from_path("icons/32x32.png").unwrap_or_else(|_| {
-            app.default_window_icon().cloned().unwrap_or_else(|| {
-                Image::from_bytes(include_bytes!("../icons/32x32.png"))
-                    .expect("Failed to load tray icon")
-            })
-        }))
-        .menu(&menu)
-        .show_menu_on_left_click(true)
-        .on_menu_event(move |app, event| match event.id.as_ref() {
-            "show" => {
-                info!("[Tray] 点击: 显示窗口");
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            "screenshot" => {
-                info!("[Tray] 点击: 区域截图");
-                let _ = app.emit("tray-action", "screenshot");
-            }
-            "ocr_translate" => {
-                info!("[Tray] 点击: 区域翻译");
-                let _ = app.emit("tray-action", "ocr_translate");
-            }
-            "clipboard_translate" => {
-                info!("[Tray] 点击: 翻译选中文本");
-                let _ = app.emit("tray-action", "clipboard_translate");
-            }
-            "quit" => {
-                info!("[Tray] 点击: 退出");
-                app.exit(0);
-            }
-            _ => {}
-        })
-        .build(app)?;
+/// "name (hotkey)", or just "name" when the hotkey is unbound.
+fn optional_hotkey_label(name: &str, hotkey: &str) -> String {
+    match hotkey.trim() {
+        "" => name.to_string(),
+        hotkey => format!("{} ({})", name, hotkey),
+    }
+}
 
-    Ok(())
+/// Show the delayed-capture countdown on the tray (tooltip everywhere, title
+/// next to the icon on macOS / Linux); `None` restores the defaults.
+pub fn set_countdown(app: &AppHandle, remaining: Option<u64>) {
+    let (tooltip, title) = match remaining {
+        Some(secs) => (
+            format!("DH-TransShot: {}s 后截图（再次触发取消）", secs),
+            Some(format!("{}s", secs)),
+        ),
+        None => ("DH-TransShot".to_string(), None),
+    };
+    set_status(app, tooltip, title);
+}
+
+/// Show the stitched height of a running scrolling capture on the tray;
+/// `None` restores the defaults.
+pub fn set_scroll_progress(app: &AppHandle, height: Option<u32>) {
+    let (tooltip, title) = match height {
+        Some(height) => (
+            format!("DH-TransShot: 长截图中 {}px（再次触发结束）", height),
+            Some(format!("{}px", height)),
+        ),
+        None => ("DH-TransShot".to_string(), None),
+    };
+    set_status(app, tooltip, title);
+}
+
+fn set_status(app: &AppHandle, tooltip: String, title: Option<String>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        warn!("[Tray] 更新提示失败: {}", e);
+    }
+    if let Err(e) = tray.set_title(title) {
+        warn!("[Tray] 更新标题失败: {}", e);
+    }
 }
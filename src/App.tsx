@@ -2,6 +2,7 @@ import { useEffect, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow, getAllWindows } from "@tauri-apps/api/window";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { TitleBar } from "./components/common/TitleBar";
 import { TranslationPanel } from "./components/translation/TranslationPanel";
 import { useSettingsStore } from "./stores/settingsStore";
@@ -10,12 +11,14 @@ import { useTranslationStore } from "./stores/translationStore";
 import { appLog, openDebugWindow, setupMainWindowLogListeners } from "./stores/logStore";
 import { useScreenshot } from "./hooks/useScreenshot";
 import { useTranslation, cancelPendingTranslation } from "./hooks/useTranslation";
-import { captureRegion, captureAndOcr, copyImageToClipboard, getSettings, readSelectedText, readClipboard } from "./lib/invoke";
-import type { RegionSelectEvent } from "./types";
+import { captureRegion, captureFullscreen, captureWindow, startScrollCapture, stopScrollCapture, captureAndOcr, captureAndTranslate, copyImageToClipboard, saveScreenshot, renderAnnotation, getSettings, readSelectedText, readClipboard, runTextAction, ocrImageFile, ocrClipboardImage, ocrScrollCapture, translateImageRegion, decodeCodes } from "./lib/invoke";
+import type { DirectCapture, ImageOcrResult, OcrMode, RegionSelectEvent } from "./types";
+
+const IMAGE_FILE_PATTERN = /\.(png|jpe?g|webp|bmp|tiff?)$/i;
 
 export default function App() {
   const { setSettings } = useSettingsStore();
-  const { startRegion } = useScreenshot();
+  const { startRegion, startDelayedRegion } = useScreenshot();
   const { translate, setSourceText } = useTranslation();
   const { sourceLang } = useTranslationStore();
 
@@ -85,10 +88,11 @@ export default function App() {
 
       try {
         if (mode === "screenshot") {
-          if (event.payload.annotatedImage) {
-            // Annotation mode: frontend already rendered the annotated image
-            appLog.info("[App] screenshot 标注模式，直接使用标注图片, base64 size=" + event.payload.annotatedImage.length);
-            await copyImageToClipboard(event.payload.annotatedImage);
+          if (event.payload.annotation) {
+            // Annotation mode: the backend draws the shapes over the frozen crop
+            appLog.info("[App] screenshot 标注模式，后端渲染标注, shapes=" + event.payload.annotation.shapes.length);
+            const imageBase64 = await renderAnnotation(monitor_index, x, y, width, height, event.payload.annotation);
+            await copyImageToClipboard(imageBase64);
           } else {
             // Plain screenshot: crop → copy to clipboard
             appLog.info("[App] screenshot 模式，裁切中...");
@@ -97,6 +101,15 @@ export default function App() {
             await copyImageToClipboard(imageBase64);
           }
           appLog.info("[App] 图片已复制到剪贴板");
+          // Auto-save is checked on the backend; a failure must not undo the clipboard copy
+          try {
+            const saved = await saveScreenshot(monitor_index, x, y, width, height, mode, event.payload.annotation);
+            if (saved) {
+              appLog.info("[App] 截图已自动保存: " + saved.path);
+            }
+          } catch (e) {
+            appLog.error("[App] 截图自动保存失败: " + String(e));
+          }
         } else if (mode === "ocr_translate") {
           // OCR + Translate mode: show main window, then capture+OCR → set source text → translate
           await getCurrentWindow().show();
@@ -112,6 +125,22 @@ export default function App() {
           store.setIsOcrProcessing(true);
 
           try {
+            const startedAt = performance.now();
+            if (useSettingsStore.getState().settings.ocr.translate_pipeline === "one_shot") {
+              const result = await captureAndTranslate(
+                monitor_index, x, y, width, height, sourceLangRef.current, useTranslationStore.getState().targetLang
+              );
+              if (sessionId !== ocrSessionRef.current) {
+                appLog.info("[App] 识别+翻译结果已过期 (session=" + sessionId + "), 丢弃");
+                return;
+              }
+              store.setIsOcrProcessing(false);
+              setSourceText(result.source);
+              store.setTranslatedText(result.translation);
+              appLog.info("[App] 一次请求识别+翻译完成, 总耗时=" + Math.round(performance.now() - startedAt) + "ms");
+              return;
+            }
+
             const ocrText = await captureAndOcr(monitor_index, x, y, width, height, sourceLangRef.current);
             if (sessionId !== ocrSessionRef.current) {
               appLog.info("[App] OCR 结果已过期 (session=" + sessionId + "), 丢弃");
@@ -124,7 +153,7 @@ export default function App() {
               setSourceText(ocrText);
               appLog.info("[App] 源文本已设置，开始翻译...");
               await translate(ocrText);
-              appLog.info("[App] 翻译完成");
+              appLog.info("[App] 翻译完成, 两步流程总耗时=" + Math.round(performance.now() - startedAt) + "ms");
             } else {
               appLog.warn("[App] OCR 结果为空，跳过翻译");
             }
@@ -137,6 +166,105 @@ export default function App() {
             store.setError("OCR 识别失败: " + String(e));
             appLog.error("[App] OCR 失败: " + String(e));
           }
+        } else if (mode === "image_translate") {
+          // Image translation: translations are painted over the region; the result goes to the clipboard
+          await getCurrentWindow().show();
+          await getCurrentWindow().setFocus();
+          const sessionId = ++ocrSessionRef.current;
+          cancelPendingTranslation();
+          appLog.info("[App] image_translate 模式，开始识别并翻译各文本块... (session=" + sessionId + ")");
+          const store = useTranslationStore.getState();
+          store.setSourceText("");
+          store.setTranslatedText("");
+          store.setError(null);
+          store.setIsOcrProcessing(false);
+          store.setIsTranslating(true);
+
+          try {
+            const result = await translateImageRegion(
+              monitor_index, x, y, width, height, sourceLangRef.current, useTranslationStore.getState().targetLang
+            );
+            if (sessionId !== ocrSessionRef.current) {
+              appLog.info("[App] 图片翻译结果已过期 (session=" + sessionId + "), 丢弃");
+              return;
+            }
+            await copyImageToClipboard(result.image);
+            setSourceText(result.blocks.map((b) => b.source).join("\n\n"));
+            store.setTranslatedText(result.blocks.map((b) => b.translation ?? b.source).join("\n\n"));
+            const failed = result.blocks.filter((b) => b.translation === null).length;
+            if (failed > 0) {
+              store.setError(`${failed} 个文本块翻译失败，已保留原文`);
+            }
+            appLog.info("[App] 图片翻译完成, 文本块=" + result.blocks.length + ", 失败=" + failed + ", 译图已复制到剪贴板");
+          } catch (e) {
+            if (sessionId !== ocrSessionRef.current) return;
+            store.setError("图片翻译失败: " + String(e));
+            appLog.error("[App] 图片翻译失败: " + String(e));
+          } finally {
+            if (sessionId === ocrSessionRef.current) {
+              store.setIsTranslating(false);
+            }
+          }
+        } else if (mode === "decode_codes") {
+          // QR code / barcode: decoded locally, contents shown in the source box for copying
+          await getCurrentWindow().show();
+          await getCurrentWindow().setFocus();
+          cancelPendingTranslation();
+          const store = useTranslationStore.getState();
+          store.setTranslatedText("");
+          store.setError(null);
+          const codes = await decodeCodes(monitor_index, { x, y, width, height });
+          appLog.info("[App] 条码识别完成, 数量=" + codes.length + (codes.length ? ", 码制=" + codes.map((c) => c.format).join(",") : ""));
+          setSourceText(codes.map((c) => c.text).join("\n"));
+          if (codes.length === 0) {
+            store.setError("未识别到二维码或条码");
+          }
+        } else if (mode === "scroll" || mode === "scroll_ocr") {
+          // Scrolling capture: resolves once the user stops it (tray / hotkey again)
+          appLog.info("[App] 长截图开始，滚动区域内容，再次触发长截图结束");
+          const result = await startScrollCapture(monitor_index, x, y, width, height);
+          appLog.info(
+            `[App] 长截图完成: ${result.width}x${result.height}, copied=${result.copied}` +
+              (result.saved ? `, saved=${result.saved.path}` : "")
+          );
+          if (mode === "scroll_ocr") {
+            runImageOcr(() => ocrScrollCapture(sourceLangRef.current));
+          }
+        } else if (mode.startsWith("ocr:")) {
+          // OCR-only mode (plain / markdown / latex / code): show recognized text, no translation
+          const ocrMode = mode.slice("ocr:".length) as OcrMode;
+          await getCurrentWindow().show();
+          await getCurrentWindow().setFocus();
+          const sessionId = ++ocrSessionRef.current;
+          cancelPendingTranslation();
+          appLog.info("[App] OCR 模式 " + ocrMode + "，开始 capture+OCR... (session=" + sessionId + ")");
+          const store = useTranslationStore.getState();
+          store.setSourceText("");
+          store.setTranslatedText("");
+          store.setError(null);
+          store.setIsTranslating(false);
+          store.setIsOcrProcessing(true);
+
+          try {
+            const ocrText = await captureAndOcr(monitor_index, x, y, width, height, sourceLangRef.current, ocrMode);
+            if (sessionId !== ocrSessionRef.current) {
+              appLog.info("[App] OCR 结果已过期 (session=" + sessionId + "), 丢弃");
+              return;
+            }
+            appLog.info("[App] OCR 完成, 文本长度=" + ocrText.length);
+            setSourceText(ocrText);
+          } catch (e) {
+            if (sessionId !== ocrSessionRef.current) {
+              appLog.info("[App] OCR 错误已过期 (session=" + sessionId + "), 忽略");
+              return;
+            }
+            store.setError("OCR 识别失败: " + String(e));
+            appLog.error("[App] OCR 失败: " + String(e));
+          } finally {
+            if (sessionId === ocrSessionRef.current) {
+              store.setIsOcrProcessing(false);
+            }
+          }
         }
       } catch (e) {
         appLog.error("[App] 区域处理失败: " + String(e));
@@ -166,6 +294,18 @@ export default function App() {
         .catch((e) => appLog.error("[App] 配置刷新失败: " + String(e)));
     });
 
+    // Image files dropped onto the main window: OCR the first one, then translate
+    const unlistenDragDrop = getCurrentWebview().onDragDropEvent((event) => {
+      if (event.payload.type !== "drop") return;
+      const path = event.payload.paths.find((p) => IMAGE_FILE_PATTERN.test(p));
+      if (!path) {
+        appLog.warn("[App] 拖入的文件不是支持的图片格式");
+        return;
+      }
+      appLog.info("[App] 拖入图片: " + path);
+      runImageOcr(() => ocrImageFile(path, sourceLangRef.current));
+    });
+
     // Set up debug log event listeners (main window side)
     const cleanupLogListeners = setupMainWindowLogListeners();
 
@@ -175,21 +315,138 @@ export default function App() {
       unlistenTray.then((fn) => fn());
       unlistenHotkey.then((fn) => fn());
       unlistenSettingsSaved.then((fn) => fn());
+      unlistenDragDrop.then((fn) => fn());
       cleanupLogListeners();
     };
   }, []);
 
+  const runDirectCapture = async (label: string, capture: () => Promise<DirectCapture>) => {
+    appLog.info(`[App] ${label}截图`);
+    try {
+      const result = await capture();
+      appLog.info(
+        `[App] ${label}截图完成: ${result.width}x${result.height}, copied=${result.copied}` +
+          (result.saved ? `, saved=${result.saved.path}` : "")
+      );
+    } catch (e) {
+      appLog.error(`[App] ${label}截图失败: ` + String(e));
+    }
+  };
+
   const handleAction = async (action: string) => {
     switch (action) {
       case "screenshot":
         startRegion("screenshot");
         break;
+      case "delayed_screenshot":
+        startDelayedRegion("screenshot");
+        break;
+      case "fullscreen_screenshot":
+        runDirectCapture("全屏", () => captureFullscreen());
+        break;
+      case "window_screenshot":
+        runDirectCapture("窗口", () => captureWindow());
+        break;
+      case "scroll_screenshot":
+      case "scroll_ocr_translate":
+        // Triggering again while a scrolling capture runs stops it
+        if (await stopScrollCapture()) {
+          appLog.info("[App] 长截图已结束");
+        } else {
+          startRegion(action === "scroll_screenshot" ? "scroll" : "scroll_ocr");
+        }
+        break;
       case "ocr_translate":
         startRegion("ocr_translate");
         break;
+      case "image_translate":
+        startRegion("image_translate");
+        break;
+      case "decode_codes":
+        startRegion("decode_codes");
+        break;
       case "clipboard_translate":
         handleSelectedTextTranslate();
         break;
+      case "clipboard_image_translate":
+        appLog.info("[App] 翻译剪贴板图片");
+        runImageOcr(() => ocrClipboardImage(sourceLangRef.current));
+        break;
+      default:
+        if (action.startsWith("action:")) {
+          handleTextAction(action.slice("action:".length));
+        } else if (action.startsWith("ocr:")) {
+          startRegion(action);
+        }
+        break;
+    }
+  };
+
+  // OCR an image file / clipboard image, then translate like ocr_translate
+  const runImageOcr = async (recognize: () => Promise<ImageOcrResult>) => {
+    await getCurrentWindow().show();
+    await getCurrentWindow().setFocus();
+    const sessionId = ++ocrSessionRef.current;
+    cancelPendingTranslation();
+    const store = useTranslationStore.getState();
+    store.setSourceText("");
+    store.setTranslatedText("");
+    store.setError(null);
+    store.setIsTranslating(false);
+    store.setIsOcrProcessing(true);
+
+    try {
+      const { text } = await recognize();
+      if (sessionId !== ocrSessionRef.current) {
+        appLog.info("[App] 图片 OCR 结果已过期 (session=" + sessionId + "), 丢弃");
+        return;
+      }
+      appLog.info("[App] 图片 OCR 完成, 文本长度=" + text.length);
+      store.setIsOcrProcessing(false);
+      if (text.trim()) {
+        setSourceText(text);
+        await translate(text);
+      } else {
+        appLog.warn("[App] 图片 OCR 结果为空，跳过翻译");
+      }
+    } catch (e) {
+      if (sessionId !== ocrSessionRef.current) return;
+      store.setIsOcrProcessing(false);
+      store.setError("OCR 识别失败: " + String(e));
+      appLog.error("[App] 图片 OCR 失败: " + String(e));
+    }
+  };
+
+  const handleTextAction = async (actionId: string) => {
+    const store = useTranslationStore.getState();
+    try {
+      appLog.info("[App] 文本动作 " + actionId + ": 读取选中文字...");
+      cancelPendingTranslation();
+      store.setSourceText("");
+      store.setTranslatedText("");
+      store.setError(null);
+      store.setIsOcrProcessing(false);
+
+      const text = await readSelectedText();
+      if (!text.trim()) {
+        appLog.warn("[App] 未获取到选中文本");
+        return;
+      }
+      setSourceText(text);
+
+      const appWindow = getCurrentWindow();
+      await appWindow.show();
+      await appWindow.setFocus();
+
+      store.setIsTranslating(true);
+      const result = await runTextAction(actionId, text, store.targetLang);
+      store.setTranslatedText(result);
+      appLog.info("[App] 文本动作完成, 结果长度=" + result.length);
+    } catch (e) {
+      store.setError(String(e));
+      appLog.error("[App] 文本动作失败: " + String(e));
+    } finally {
+      store.setIsTranslating(false);
     }
   };
 
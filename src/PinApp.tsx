@@ -0,0 +1,5 @@
+import { PinWindow } from "./components/pin/PinWindow";
+
+export default function PinApp() {
+  return <PinWindow />;
+}
@@ -0,0 +1,195 @@
+import { useState, useEffect, useRef, useCallback } from "react";
+import { getCurrentWindow, LogicalSize } from "@tauri-apps/api/window";
+import { save } from "@tauri-apps/plugin-dialog";
+import { getPin, copyPin, savePin } from "../../lib/invoke";
+import { appLog } from "../../stores/logStore";
+import type { PinInfo } from "../../types";
+
+const MIN_ZOOM = 0.1;
+const MAX_ZOOM = 5;
+const ZOOM_STEP = 0.1;
+const MIN_OPACITY = 0.2;
+const OPACITY_STEP = 0.1;
+
+const clamp = (value: number, min: number, max: number) => Math.min(max, Math.max(min, value));
+
+/**
+ * 贴图窗口：无边框、置顶，显示一张钉在屏幕上的截图。
+ * 拖动移动；滚轮缩放（窗口随之变化）；Ctrl/⌘ + 滚轮调透明度；
+ * 悬停显示工具栏（缩放比例、透明度、复制、保存、关闭）。
+ */
+export function PinWindow() {
+  const appWindow = getCurrentWindow();
+  const label = appWindow.label;
+  const [info, setInfo] = useState<PinInfo | null>(null);
+  const [zoom, setZoom] = useState(1);
+  const [opacity, setOpacity] = useState(1);
+  const [hovered, setHovered] = useState(false);
+  const [status, setStatus] = useState<string | null>(null);
+  const statusTimerRef = useRef<number | null>(null);
+
+  useEffect(() => {
+    getPin(label)
+      .then((pin) => {
+        appLog.info(`[Pin] 贴图加载: ${label}, image=${pin.width}x${pin.height}, scale=${pin.scale_factor}`);
+        setInfo(pin);
+      })
+      .catch((e) => {
+        appLog.error("[Pin] 贴图加载失败: " + String(e));
+        appWindow.close();
+      });
+  }, []);
+
+  const flash = useCallback((message: string) => {
+    setStatus(message);
+    if (statusTimerRef.current !== null) window.clearTimeout(statusTimerRef.current);
+    statusTimerRef.current = window.setTimeout(() => setStatus(null), 1200);
+  }, []);
+
+  // Window size follows zoom; 100% = one image pixel per physical pixel
+  const applyZoom = useCallback(
+    async (next: number) => {
+      if (!info) return;
+      const z = clamp(Math.round(next * 100) / 100, MIN_ZOOM, MAX_ZOOM);
+      setZoom(z);
+      await appWindow.setSize(
+        new LogicalSize(
+          Math.max(1, Math.round((info.width / info.scale_factor) * z)),
+          Math.max(1, Math.round((info.height / info.scale_factor) * z))
+        )
+      );
+    },
+    [info]
+  );
+
+  const handleCopy = useCallback(async () => {
+    try {
+      await copyPin(label);
+      flash("已复制");
+    } catch (e) {
+      appLog.error("[Pin] 复制失败: " + String(e));
+      flash("复制失败");
+    }
+  }, [label, flash]);
+
+  const handleSave = useCallback(async () => {
+    const ts = new Date().toISOString().replace(/[-:T]/g, "").slice(0, 14);
+    const filePath = await save({
+      defaultPath: `dh_pin_${ts}.png`,
+      filters: [{ name: "Images", extensions: ["png", "jpg", "webp"] }],
+    });
+    if (!filePath) return;
+    try {
+      await savePin(label, filePath);
+      appLog.info("[Pin] 贴图已保存: " + filePath);
+      flash("已保存");
+    } catch (e) {
+      appLog.error("[Pin] 保存失败: " + String(e));
+      flash("保存失败");
+    }
+  }, [label, flash]);
+
+  const handleClose = useCallback(() => {
+    appLog.info("[Pin] 关闭贴图: " + label);
+    appWindow.close();
+  }, [label]);
+
+  const handleWheel = (e: React.WheelEvent) => {
+    const direction = e.deltaY < 0 ? 1 : -1;
+    if (e.ctrlKey || e.metaKey) {
+      setOpacity((prev) => clamp(Math.round((prev + direction * OPACITY_STEP) * 10) / 10, MIN_OPACITY, 1));
+    } else {
+      applyZoom(zoom + direction * ZOOM_STEP);
+    }
+  };
+
+  useEffect(() => {
+    const handleKey = (e: KeyboardEvent) => {
+      const mod = e.ctrlKey || e.metaKey;
+      if (e.key === "Escape" || (mod && e.key === "w")) {
+        e.preventDefault();
+        handleClose();
+      } else if (mod && e.key === "c") {
+        e.preventDefault();
+        handleCopy();
+      } else if (mod && e.key === "s") {
+        e.preventDefault();
+        handleSave();
+      } else if (e.key === "+" || e.key === "=") {
+        applyZoom(zoom + ZOOM_STEP);
+      } else if (e.key === "-") {
+        applyZoom(zoom - ZOOM_STEP);
+      } else if (e.key === "0") {
+        applyZoom(1);
+      }
+    };
+    window.addEventListener("keydown", handleKey);
+    return () => window.removeEventListener("keydown", handleKey);
+  }, [zoom, applyZoom, handleClose, handleCopy, handleSave]);
+
+  const btnClass =
+    "h-6 px-1.5 flex items-center justify-center rounded text-white/80 hover:bg-white/15 text-xs";
+
+  return (
+    <div
+      className="pin-window-root fixed inset-0 select-none"
+      onMouseEnter={() => setHovered(true)}
+      onMouseLeave={() => setHovered(false)}
+      onWheel={handleWheel}
+      onDoubleClick={handleClose}
+    >
+      {info && (
+        <img
+          src={info.image_url}
+          draggable={false}
+          onMouseDown={(e) => {
+            if (e.button === 0) appWindow.startDragging();
+          }}
+          className="w-full h-full block cursor-move"
+          style={{ opacity, outline: "1px solid rgba(59, 130, 246, 0.6)", outlineOffset: -1 }}
+        />
+      )}
+
+      {(hovered || status) && (
+        <div
+          className="absolute top-1 right-1 flex items-center gap-0.5 rounded-md px-1 py-0.5"
+          style={{ background: "rgba(0, 0, 0, 0.65)" }}
+          onDoubleClick={(e) => e.stopPropagation()}
+        >
+          {status ? (
+            <span className="text-white/90 text-xs px-1">{status}</span>
+          ) : (
+            <>
+              <button className={btnClass} title="恢复 100% (0)" onClick={() => applyZoom(1)}>
+                {Math.round(zoom * 100)}%
+              </button>
+              <input
+                type="range"
+                min={MIN_OPACITY * 100}
+                max={100}
+                step={OPACITY_STEP * 100}
+                value={Math.round(opacity * 100)}
+                onChange={(e) => setOpacity(Number(e.target.value) / 100)}
+                title={`透明度 ${Math.round(opacity * 100)}% (Ctrl/⌘ + 滚轮)`}
+                className="w-14 accent-blue-500"
+              />
+              <button className={btnClass} title="复制 (Ctrl/⌘+C)" onClick={handleCopy}>
+                复制
+              </button>
+              <button className={btnClass} title="保存 (Ctrl/⌘+S)" onClick={handleSave}>
+                保存
+              </button>
+              <button
+                className="w-6 h-6 flex items-center justify-center rounded text-red-400 hover:bg-red-400/20 text-sm"
+                title="关闭 (Esc / 双击)"
+                onClick={handleClose}
+              >
+                ✗
+              </button>
+            </>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,26 +1,21 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 import { emit } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { getFrozenScreenshot, saveFile } from "../../lib/invoke";
+import { getFrozenScreenshot, pinImage, renderAnnotation, saveAnnotated } from "../../lib/invoke";
 import { save } from "@tauri-apps/plugin-dialog";
 import { appLog } from "../../stores/logStore";
-import type { WindowRect, MonitorInfo } from "../../types";
+import type { WindowRect, MonitorInfo, Annotation, AnnotationPoint, AnnotationShape, FrozenEdit } from "../../types";
 
 // --- Annotation types ---
 
-interface Point {
-  x: number;
-  y: number;
-}
+// Shapes are the serializable model rendered by the backend (see render/annotate.rs);
+// the canvas here is only a preview.
+type Point = AnnotationPoint;
+type Shape = AnnotationShape;
 
-type Shape =
-  | { type: "rect"; x: number; y: number; w: number; h: number; color: string; strokeWidth: number; radius: number }
-  | { type: "arrow"; x1: number; y1: number; x2: number; y2: number; color: string; strokeWidth: number }
-  | { type: "pen"; points: Point[]; color: string; strokeWidth: number }
-  | { type: "mosaic"; points: Point[]; strokeWidth: number }
-  | { type: "text"; x: number; y: number; text: string; color: string; fontSize: number; bold: boolean };
+type Tool = "rect" | "ellipse" | "arrow" | "pen" | "mosaic" | "blur" | "text" | "step";
 
-type Tool = "rect" | "arrow" | "pen" | "mosaic" | "text";
+const ANNOTATION_VERSION = 1;
 
 const PRESET_COLORS = ["#ef4444", "#3b82f6", "#22c55e", "#eab308", "#ffffff"];
 const DEFAULT_STROKE_WIDTH = 3;
@@ -28,7 +23,8 @@ const DEFAULT_MOSAIC_WIDTH = 24;
 const DEFAULT_FONT_SIZE_RATIO = 8;
 const DEFAULT_RECT_RADIUS = 4;
 const DEFAULT_TEXT_BOLD = false;
-const ANNOTATION_TOOLBAR_MIN_WIDTH = 460;
+const DEFAULT_BLUR_RADIUS = 10;
+const ANNOTATION_TOOLBAR_MIN_WIDTH = 560;
 const COLOR_TOOLTIP_OFFSET = 14;
 const ANNOTATION_TOOLBAR_HEIGHT_ESTIMATE = 52; // 工具栏预估高度（含 padding，用于边缘 clamp 计算）
 const ANNOTATION_TOOLBAR_SPACING = 6; // 工具栏与 canvas 之间的间距
@@ -116,6 +112,27 @@ function drawRoundedRect(
   ctx.stroke();
 }
 
+function normalizeBox(shape: { x: number; y: number; w: number; h: number }) {
+  return {
+    x: Math.min(shape.x, shape.x + shape.w),
+    y: Math.min(shape.y, shape.y + shape.h),
+    w: Math.abs(shape.w),
+    h: Math.abs(shape.h),
+  };
+}
+
+/** 序号标记半径随线宽变化 */
+function stepRadius(strokeWidth: number) {
+  return 8 + strokeWidth * 3;
+}
+
+function isLightColor(hex: string) {
+  const m = /^#?([0-9a-f]{2})([0-9a-f]{2})([0-9a-f]{2})/i.exec(hex);
+  if (!m) return false;
+  const [r, g, b] = m.slice(1, 4).map((v) => parseInt(v, 16));
+  return 0.299 * r + 0.587 * g + 0.114 * b > 160;
+}
+
 // --- Selection types ---
 
 interface Selection {
@@ -143,6 +160,8 @@ export function ScreenshotOverlay() {
   } | null>(null);
   const [hoveredRect, setHoveredRect] = useState<WindowRect | null>(null);
   const [hoverColor, setHoverColor] = useState<{ x: number; y: number; hex: string; copied: boolean } | null>(null);
+  // Reopened annotated capture: enter annotate on this region once the background is loaded
+  const [pendingEdit, setPendingEdit] = useState<FrozenEdit | null>(null);
 
   // --- Annotate phase state ---
   const [croppedImageEl, setCroppedImageEl] = useState<HTMLImageElement | null>(null);
@@ -152,6 +171,7 @@ export function ScreenshotOverlay() {
   const [strokeWidth, setStrokeWidth] = useState(DEFAULT_STROKE_WIDTH);
   const [mosaicWidth, setMosaicWidth] = useState(DEFAULT_MOSAIC_WIDTH);
   const [rectRadius, setRectRadius] = useState(DEFAULT_RECT_RADIUS);
+  const [blurRadius, setBlurRadius] = useState(DEFAULT_BLUR_RADIUS);
   const [fontSize, setFontSize] = useState(30);
   const [textBold, setTextBold] = useState(DEFAULT_TEXT_BOLD);
   const [showStylePicker, setShowStylePicker] = useState(false);
@@ -190,6 +210,8 @@ export function ScreenshotOverlay() {
   const strokeWidthRef = useRef(DEFAULT_STROKE_WIDTH);
   const mosaicWidthRef = useRef(DEFAULT_MOSAIC_WIDTH);
   const rectRadiusRef = useRef(DEFAULT_RECT_RADIUS);
+  const blurRadiusRef = useRef(DEFAULT_BLUR_RADIUS);
+  const mosaicBlockSizeRef = useRef(6);
   const fontSizeRef = useRef(30);
   const textBoldRef = useRef(DEFAULT_TEXT_BOLD);
   const drawingRef = useRef(false);
@@ -216,6 +238,7 @@ export function ScreenshotOverlay() {
   useEffect(() => { strokeWidthRef.current = strokeWidth; }, [strokeWidth]);
   useEffect(() => { mosaicWidthRef.current = mosaicWidth; }, [mosaicWidth]);
   useEffect(() => { rectRadiusRef.current = rectRadius; }, [rectRadius]);
+  useEffect(() => { blurRadiusRef.current = blurRadius; }, [blurRadius]);
   useEffect(() => { fontSizeRef.current = fontSize; }, [fontSize]);
   useEffect(() => { textBoldRef.current = textBold; }, [textBold]);
   useEffect(() => { croppedImageElRef.current = croppedImageEl; }, [croppedImageEl]);
@@ -281,18 +304,24 @@ export function ScreenshotOverlay() {
             "[Overlay] 冻结截图获取成功, attempt=" + attempt +
             ", monitor_index=" + myMonitorIndex +
             ", mode=" + data.mode +
-            ", image size=" + data.image.length +
+            ", image=" + data.image_width + "x" + data.image_height +
             ", window_rects=" + (data.window_rects?.length ?? 0) +
             ", monitor=" + (myMonitor?.name ?? "unknown")
           );
 
-          const binaryStr = atob(data.image);
-          const bytes = new Uint8Array(binaryStr.length);
-          for (let i = 0; i < binaryStr.length; i++) {
-            bytes[i] = binaryStr.charCodeAt(i);
+          // Fetch into a same-origin blob URL so canvas reads (color picker,
+          // export) are not blocked by the custom scheme's origin.
+          const fetchStart = performance.now();
+          const response = await fetch(data.image_url);
+          if (!response.ok) {
+            throw new Error("Frozen screenshot request failed: HTTP " + response.status);
           }
-          const blob = new Blob([bytes], { type: "image/png" });
+          const blob = await response.blob();
           const url = URL.createObjectURL(blob);
+          appLog.info(
+            "[Overlay] 冻结截图下载完成, size=" + blob.size +
+            ", 耗时 " + Math.round(performance.now() - fetchStart) + "ms"
+          );
 
           const imgEl = await new Promise<HTMLImageElement>((resolve, reject) => {
             const img = new Image();
@@ -331,6 +360,14 @@ export function ScreenshotOverlay() {
           setWindowRects(data.window_rects ?? []);
           setMonitor(myMonitor);
           setMonitorIndex(myMonitorIndex);
+          if (data.edit) {
+            appLog.info(
+              "[Overlay] 重新编辑标注截图: " + data.edit.path +
+              ", region=(" + data.edit.x + "," + data.edit.y + "," + data.edit.width + "x" + data.edit.height + ")" +
+              ", shapes=" + data.edit.annotation.shapes.length
+            );
+            setPendingEdit(data.edit);
+          }
 
           const currentWin = getCurrentWindow();
           await currentWin.show();
@@ -537,6 +574,7 @@ export function ScreenshotOverlay() {
       croppedBlobUrlRef.current = url;
       setCroppedImageEl(img);
       const blockSize = Math.max(6, Math.round(Math.min(img.naturalWidth, img.naturalHeight) / 40));
+      mosaicBlockSizeRef.current = blockSize;
       mosaicCanvasRef.current = buildMosaicCanvas(img, blockSize);
       setCanvasDisplaySize({ width: sel.width, height: sel.height });
       // Keep the full-screen overlay window as-is (no resize).
@@ -556,6 +594,21 @@ export function ScreenshotOverlay() {
     }
   }, [cropRegion]);
 
+  useEffect(() => {
+    if (!pendingEdit || !backgroundUrl) return;
+    const edit = pendingEdit;
+    setPendingEdit(null);
+    const { scaleX, scaleY } = getImageScale();
+    const sel = {
+      left: edit.x / scaleX,
+      top: edit.y / scaleY,
+      width: edit.width / scaleX,
+      height: edit.height / scaleY,
+    };
+    setSelRect(sel);
+    enterAnnotate(sel).then(() => setShapes(edit.annotation.shapes));
+  }, [pendingEdit, backgroundUrl, getImageScale, enterAnnotate]);
+
   // --- Canvas rendering ---
   const renderCanvas = useCallback(() => {
     const canvas = canvasRef.current;
@@ -580,6 +633,39 @@ export function ScreenshotOverlay() {
           drawRoundedRect(ctx, shape.x, shape.y, shape.w, shape.h, shape.radius);
           break;
         }
+        case "ellipse": {
+          const { x, y, w, h } = normalizeBox(shape);
+          ctx.strokeStyle = shape.color;
+          ctx.lineWidth = shape.strokeWidth;
+          ctx.beginPath();
+          ctx.ellipse(x + w / 2, y + h / 2, Math.max(w / 2, 0.5), Math.max(h / 2, 0.5), 0, 0, Math.PI * 2);
+          ctx.stroke();
+          break;
+        }
+        case "blur": {
+          // Like the backend, blur samples the original capture, not earlier shapes
+          const { x, y, w, h } = normalizeBox(shape);
+          if (w < 1 || h < 1) break;
+          ctx.beginPath();
+          ctx.rect(x, y, w, h);
+          ctx.clip();
+          ctx.filter = `blur(${shape.radius}px)`;
+          ctx.drawImage(img, 0, 0);
+          break;
+        }
+        case "step": {
+          ctx.fillStyle = shape.color;
+          ctx.beginPath();
+          ctx.arc(shape.x, shape.y, shape.radius, 0, Math.PI * 2);
+          ctx.fill();
+          const label = String(shape.number);
+          ctx.fillStyle = isLightColor(shape.color) ? "#000" : "#fff";
+          ctx.font = `bold ${shape.radius * (label.length > 1 ? 1 : 1.2)}px sans-serif`;
+          ctx.textAlign = "center";
+          ctx.textBaseline = "middle";
+          ctx.fillText(label, shape.x, shape.y);
+          break;
+        }
         case "arrow": {
           const { x1, y1, x2, y2, color: c, strokeWidth: sw } = shape;
           ctx.strokeStyle = c;
@@ -658,128 +744,88 @@ export function ScreenshotOverlay() {
     renderCanvas();
   }, [phase, shapes, currentShape, renderCanvas]);
 
-  // --- Render annotated image to an offscreen canvas ---
-  const renderAnnotatedCanvas = useCallback((): HTMLCanvasElement | null => {
-    const img = croppedImageElRef.current;
-    if (!img) return null;
-
-    const canvas = document.createElement("canvas");
-    canvas.width = img.naturalWidth;
-    canvas.height = img.naturalHeight;
-    const ctx = canvas.getContext("2d")!;
-    ctx.drawImage(img, 0, 0);
-
-    for (const shape of shapesRef.current) {
-      ctx.save();
-      switch (shape.type) {
-        case "rect": {
-          ctx.strokeStyle = shape.color;
-          ctx.lineWidth = shape.strokeWidth;
-          drawRoundedRect(ctx, shape.x, shape.y, shape.w, shape.h, shape.radius);
-          break;
-        }
-        case "arrow": {
-          const { x1, y1, x2, y2, color: c, strokeWidth: sw } = shape;
-          ctx.strokeStyle = c;
-          ctx.fillStyle = c;
-          ctx.lineWidth = sw;
-          ctx.lineCap = "round";
-          ctx.beginPath();
-          ctx.moveTo(x1, y1);
-          ctx.lineTo(x2, y2);
-          ctx.stroke();
-          const angle = Math.atan2(y2 - y1, x2 - x1);
-          const headLen = Math.max(sw * 5, 14);
-          ctx.beginPath();
-          ctx.moveTo(x2, y2);
-          ctx.lineTo(x2 - headLen * Math.cos(angle - Math.PI / 6), y2 - headLen * Math.sin(angle - Math.PI / 6));
-          ctx.lineTo(x2 - headLen * Math.cos(angle + Math.PI / 6), y2 - headLen * Math.sin(angle + Math.PI / 6));
-          ctx.closePath();
-          ctx.fill();
-          break;
-        }
-        case "pen": {
-          if (shape.points.length < 2) break;
-          ctx.strokeStyle = shape.color;
-          ctx.lineWidth = shape.strokeWidth;
-          ctx.lineCap = "round";
-          ctx.lineJoin = "round";
-          ctx.beginPath();
-          ctx.moveTo(shape.points[0].x, shape.points[0].y);
-          for (let i = 1; i < shape.points.length; i++) {
-            ctx.lineTo(shape.points[i].x, shape.points[i].y);
-          }
-          ctx.stroke();
-          break;
-        }
-        case "mosaic": {
-          const mc = mosaicCanvasRef.current;
-          if (!mc) break;
-          drawMosaicShape(ctx, shape, mc);
-          break;
-        }
-        case "text": {
-          ctx.fillStyle = shape.color;
-          ctx.font = `${shape.bold ? "bold " : ""}${shape.fontSize}px sans-serif`;
-          ctx.textBaseline = "top";
-          ctx.fillText(shape.text, shape.x, shape.y);
-          break;
-        }
-      }
-      ctx.restore();
-    }
+  // --- Final image: the backend renders the vector shapes over the frozen crop ---
+  const annotationRegion = useCallback(() => (selRect ? toImageRect(selRect) : null), [selRect, toImageRect]);
 
-    return canvas;
-  }, []);
+  const buildAnnotation = useCallback((): Annotation => ({
+    version: ANNOTATION_VERSION,
+    shapes: shapesRef.current,
+  }), []);
 
-  // --- Confirm: render final image and emit ---
+  // --- Confirm: emit the region and its annotation ---
   const handleConfirm = useCallback(async () => {
-    const canvas = renderAnnotatedCanvas();
-    if (!canvas) return;
+    const region = annotationRegion();
+    if (!region) return;
+    const annotation = buildAnnotation();
 
-    const dataUrl = canvas.toDataURL("image/png");
-    const base64 = dataUrl.split(",")[1];
-
-    appLog.info("[Overlay] 标注确认, shapes=" + shapesRef.current.length + ", base64 size=" + base64.length);
-
-    const currentMonitorIndex = monitorIndexRef.current;
-    const currentMode = modeRef.current;
+    appLog.info(
+      "[Overlay] 标注确认, shapes=" + annotation.shapes.length +
+      ", image=(" + region.x + "," + region.y + "," + region.width + "x" + region.height + ")"
+    );
 
     await emit("region-selected", {
-      x: 0,
-      y: 0,
-      width: canvas.width,
-      height: canvas.height,
-      mode: currentMode,
-      monitor_index: currentMonitorIndex,
-      annotatedImage: base64,
+      x: region.x,
+      y: region.y,
+      width: region.width,
+      height: region.height,
+      mode: modeRef.current,
+      monitor_index: monitorIndexRef.current,
+      annotation,
     });
 
     await emit("close-all-overlays");
-  }, [renderAnnotatedCanvas]);
+  }, [annotationRegion, buildAnnotation]);
 
   const handleDownload = useCallback(async () => {
-    const canvas = renderAnnotatedCanvas();
-    if (!canvas) return;
+    const region = annotationRegion();
+    if (!region) return;
 
-    const dataUrl = canvas.toDataURL("image/png");
-    const base64 = dataUrl.split(",")[1];
     const ts = new Date().toISOString().replace(/[-:T]/g, "").slice(0, 14);
     const defaultName = `dh_${ts}.png`;
 
     const filePath = await save({
       defaultPath: defaultName,
-      filters: [{ name: "Images", extensions: ["png"] }],
+      filters: [{ name: "Images", extensions: ["png", "jpg", "webp"] }],
     });
     if (!filePath) return;
 
     try {
-      await saveFile(filePath, base64);
+      await saveAnnotated(
+        monitorIndexRef.current,
+        region.x,
+        region.y,
+        region.width,
+        region.height,
+        buildAnnotation(),
+        filePath
+      );
       appLog.info("[Overlay] 图片已保存: " + filePath);
     } catch (e) {
       appLog.error("[Overlay] 图片保存失败: " + String(e));
     }
-  }, [renderAnnotatedCanvas]);
+  }, [annotationRegion, buildAnnotation]);
+
+  // Pin the annotated selection to the screen at the same position
+  const handlePin = useCallback(async () => {
+    const region = annotationRegion();
+    if (!region || !selRect) return;
+
+    try {
+      const base64 = await renderAnnotation(
+        monitorIndexRef.current,
+        region.x,
+        region.y,
+        region.width,
+        region.height,
+        buildAnnotation()
+      );
+      const label = await pinImage(monitorIndexRef.current, selRect.left, selRect.top, base64);
+      appLog.info("[Overlay] 已钉到屏幕: " + label + ", image=" + region.width + "x" + region.height);
+    } catch (e) {
+      appLog.error("[Overlay] 钉到屏幕失败: " + String(e));
+    }
+    await emit("close-all-overlays");
+  }, [annotationRegion, buildAnnotation, selRect]);
 
   // --- Annotate phase: keyboard shortcuts ---
   useEffect(() => {
@@ -818,6 +864,9 @@ export function ScreenshotOverlay() {
         if (e.key === "3") { setTool("pen"); setSelectedTextIndex(null); }
         if (e.key === "4") { setTool("mosaic"); setSelectedTextIndex(null); }
         if (e.key === "5") setTool("text");
+        if (e.key === "6") { setTool("ellipse"); setSelectedTextIndex(null); }
+        if (e.key === "7") { setTool("step"); setSelectedTextIndex(null); }
+        if (e.key === "8") { setTool("blur"); setSelectedTextIndex(null); }
       }
     };
     window.addEventListener("keydown", handleKey);
@@ -1123,7 +1172,39 @@ export function ScreenshotOverlay() {
         type: "mosaic",
         points: [pos],
         strokeWidth: mosaicWidthRef.current,
+        blockSize: mosaicBlockSizeRef.current,
+      });
+    } else if (toolRef.current === "step") {
+      // A click places the marker; numbering continues after the highest one
+      drawingRef.current = false;
+      const next =
+        shapesRef.current.reduce((max, s) => (s.type === "step" ? Math.max(max, s.number) : max), 0) + 1;
+      setShapes((prev) => [
+        ...prev,
+        {
+          type: "step",
+          x: pos.x,
+          y: pos.y,
+          number: next,
+          color: colorRef.current,
+          radius: stepRadius(strokeWidthRef.current),
+        },
+      ]);
+    } else if (toolRef.current === "ellipse") {
+      setCurrentShape({
+        type: "ellipse",
+        x: pos.x, y: pos.y, w: 0, h: 0,
+        color: colorRef.current,
+        strokeWidth: strokeWidthRef.current,
       });
+      penPointsRef.current = [pos];
+    } else if (toolRef.current === "blur") {
+      setCurrentShape({
+        type: "blur",
+        x: pos.x, y: pos.y, w: 0, h: 0,
+        radius: blurRadiusRef.current,
+      });
+      penPointsRef.current = [pos];
     } else if (toolRef.current === "rect") {
       setCurrentShape({
         type: "rect",
@@ -1177,6 +1258,28 @@ export function ScreenshotOverlay() {
         type: "mosaic",
         points: [...penPointsRef.current],
         strokeWidth: mosaicWidthRef.current,
+        blockSize: mosaicBlockSizeRef.current,
+      });
+    } else if (toolRef.current === "ellipse") {
+      const start = penPointsRef.current[0];
+      setCurrentShape({
+        type: "ellipse",
+        x: Math.min(start.x, pos.x),
+        y: Math.min(start.y, pos.y),
+        w: Math.abs(pos.x - start.x),
+        h: Math.abs(pos.y - start.y),
+        color: colorRef.current,
+        strokeWidth: strokeWidthRef.current,
+      });
+    } else if (toolRef.current === "blur") {
+      const start = penPointsRef.current[0];
+      setCurrentShape({
+        type: "blur",
+        x: Math.min(start.x, pos.x),
+        y: Math.min(start.y, pos.y),
+        w: Math.abs(pos.x - start.x),
+        h: Math.abs(pos.y - start.y),
+        radius: blurRadiusRef.current,
       });
     } else if (toolRef.current === "rect") {
       const start = penPointsRef.current[0];
@@ -1410,10 +1513,13 @@ export function ScreenshotOverlay() {
             {/* Tools */}
             {[
               { id: "rect" as Tool, label: "□", title: "矩形 (1)" },
+              { id: "ellipse" as Tool, label: "○", title: "椭圆 (6)" },
               { id: "arrow" as Tool, label: "→", title: "箭头 (2)" },
               { id: "pen" as Tool, label: "✏", title: "画笔 (3)" },
-              { id: "mosaic" as Tool, label: "▦", title: "马赛克 (4)" },
               { id: "text" as Tool, label: "T", title: "文字 (5)" },
+              { id: "step" as Tool, label: "①", title: "序号 (7)" },
+              { id: "mosaic" as Tool, label: "▦", title: "马赛克 (4)" },
+              { id: "blur" as Tool, label: "◍", title: "模糊 (8)" },
             ].map((t) => (
               <button
                 key={t.id}
@@ -1444,7 +1550,11 @@ export function ScreenshotOverlay() {
                     ? "颜色 · 字号"
                     : tool === "mosaic"
                       ? "笔刷大小"
-                      : "颜色 · 线宽"
+                      : tool === "blur"
+                        ? "模糊强度"
+                        : tool === "step"
+                          ? "颜色 · 大小"
+                          : "颜色 · 线宽"
                 }
                 onClick={() => setShowStylePicker((prev) => !prev)}
                 className="h-8 px-2 gap-1.5 inline-flex items-center rounded-md hover:bg-white/10 transition-colors"
@@ -1462,7 +1572,7 @@ export function ScreenshotOverlay() {
                   strokeLinejoin="round"
                   style={{ color: "rgba(255,255,255,0.85)" }}
                 >
-                  {tool === "mosaic" ? (
+                  {tool === "mosaic" || tool === "blur" ? (
                     <>
                       <rect x="3" y="3" width="6" height="6" />
                       <rect x="15" y="3" width="6" height="6" />
@@ -1480,9 +1590,15 @@ export function ScreenshotOverlay() {
                   )}
                 </svg>
                 <span className="text-white/90 text-xs tabular-nums leading-none">
-                  {tool === "text" ? fontSize : tool === "mosaic" ? mosaicWidth : strokeWidth}
+                  {tool === "text"
+                    ? fontSize
+                    : tool === "mosaic"
+                      ? mosaicWidth
+                      : tool === "blur"
+                        ? blurRadius
+                        : strokeWidth}
                 </span>
-                {tool !== "mosaic" && (
+                {tool !== "mosaic" && tool !== "blur" && (
                   <span
                     className="inline-flex flex-col items-stretch rounded-[5px] overflow-hidden shrink-0"
                     style={{
@@ -1540,6 +1656,22 @@ export function ScreenshotOverlay() {
                         />
                       </div>
                     </>
+                  ) : tool === "blur" ? (
+                    <>
+                      <div className="flex items-center justify-between text-white/60 text-xs mb-2">
+                        <span>模糊强度</span>
+                        <span>{blurRadius}px</span>
+                      </div>
+                      <input
+                        type="range"
+                        min="2"
+                        max="40"
+                        step="1"
+                        value={blurRadius}
+                        onChange={(e) => setBlurRadius(Number(e.target.value))}
+                        className="w-full accent-blue-500"
+                      />
+                    </>
                   ) : (
                     <>
                       <div className="text-white/60 text-xs mb-2">颜色</div>
@@ -1574,8 +1706,8 @@ export function ScreenshotOverlay() {
                       {tool !== "text" ? (
                     <>
                       <div className="flex items-center justify-between text-white/60 text-xs mb-2">
-                        <span>线宽</span>
-                        <span>{strokeWidth}px</span>
+                        <span>{tool === "step" ? "大小" : "线宽"}</span>
+                        <span>{tool === "step" ? stepRadius(strokeWidth) * 2 : strokeWidth}px</span>
                       </div>
                       <input
                         type="range"
@@ -1703,6 +1835,13 @@ export function ScreenshotOverlay() {
             >
               ↓
             </button>
+            <button
+              title="钉到屏幕"
+              onClick={handlePin}
+              className="w-8 h-8 flex items-center justify-center rounded-md text-white/60 hover:bg-white/10 text-base"
+            >
+              📌
+            </button>
 
             {/* Undo hint */}
             <span className="text-white/40 text-xs ml-1 select-none">Ctrl+Z</span>
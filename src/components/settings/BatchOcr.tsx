@@ -0,0 +1,172 @@
+import { useEffect, useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import { startBatchOcr, pauseBatchOcr, resumeBatchOcr, cancelBatchOcr } from "../../lib/invoke";
+import { appLog } from "../../stores/logStore";
+import { LanguageSelector } from "../translation/LanguageSelector";
+import type { BatchOutputFormat, BatchOcrProgressEvent, BatchOcrFinishedEvent } from "../../types";
+
+const pillStyle = {
+  padding: "3px 10px",
+  borderRadius: "9999px",
+  border: "none",
+  cursor: "pointer",
+  backgroundColor: "var(--color-surface)",
+  color: "var(--color-text-secondary)",
+};
+
+const formats: { key: BatchOutputFormat; label: string }[] = [
+  { key: "txt", label: "TXT" },
+  { key: "md", label: "Markdown" },
+  { key: "json", label: "汇总 JSON" },
+];
+
+/** 选择文件夹批量 OCR（可选翻译），结果写到 <文件夹>/transshot-ocr；同参数重新开始会从断点继续 */
+export function BatchOcr() {
+  const [language, setLanguage] = useState("auto");
+  const [translate, setTranslate] = useState(false);
+  const [targetLang, setTargetLang] = useState("zh-CN");
+  const [format, setFormat] = useState<BatchOutputFormat>("txt");
+  const [recursive, setRecursive] = useState(false);
+  const [jobId, setJobId] = useState<string | null>(null);
+  const [paused, setPaused] = useState(false);
+  const [status, setStatus] = useState("");
+  const jobIdRef = useRef<string | null>(null);
+
+  useEffect(() => {
+    const unlistenProgress = listen<BatchOcrProgressEvent>("batch-ocr-progress", (event) => {
+      const { job_id, completed, failed, skipped, total, current } = event.payload;
+      if (job_id !== jobIdRef.current) return;
+      setStatus(
+        `${completed} / ${total}` +
+          (skipped ? `，跳过 ${skipped}` : "") +
+          (failed ? `，失败 ${failed}` : "") +
+          (current ? ` · ${current}` : "")
+      );
+    });
+    const unlistenFinished = listen<BatchOcrFinishedEvent>("batch-ocr-finished", (event) => {
+      const { job_id, output_dir, completed, failed, cancelled, error } = event.payload;
+      if (job_id !== jobIdRef.current) return;
+      jobIdRef.current = null;
+      setJobId(null);
+      setPaused(false);
+      if (error) {
+        setStatus("批量 OCR 失败：" + error);
+        appLog.error("[Settings] 批量 OCR 失败: " + error);
+      } else if (cancelled) {
+        setStatus(`已取消（完成 ${completed}），用相同参数重新开始可继续`);
+        appLog.info("[Settings] 批量 OCR 已取消: " + job_id);
+      } else {
+        setStatus(`完成 ${completed}` + (failed ? `，失败 ${failed}` : "") + `，结果在 ${output_dir}`);
+        appLog.info("[Settings] 批量 OCR 完成: " + output_dir + ", completed=" + completed + ", failed=" + failed);
+      }
+    });
+    return () => {
+      unlistenProgress.then((fn) => fn());
+      unlistenFinished.then((fn) => fn());
+    };
+  }, []);
+
+  const handleChooseFolder = async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected !== "string") return;
+    try {
+      appLog.info("[Settings] 开始批量 OCR: " + selected + ", format=" + format + ", recursive=" + recursive);
+      const id = await startBatchOcr({
+        input_dir: selected,
+        recursive,
+        language,
+        target_lang: translate ? targetLang : null,
+        format,
+      });
+      jobIdRef.current = id;
+      setJobId(id);
+      setPaused(false);
+      setStatus("准备中…");
+    } catch (e) {
+      setStatus("批量 OCR 失败：" + String(e));
+      appLog.error("[Settings] 批量 OCR 启动失败: " + String(e));
+    }
+  };
+
+  const handlePauseResume = async () => {
+    if (!jobId) return;
+    try {
+      if (paused) {
+        await resumeBatchOcr(jobId);
+      } else {
+        await pauseBatchOcr(jobId);
+      }
+      setPaused(!paused);
+    } catch (e) {
+      appLog.error("[Settings] 暂停/继续批量 OCR 失败: " + String(e));
+    }
+  };
+
+  const handleCancel = async () => {
+    if (!jobId) return;
+    try {
+      await cancelBatchOcr(jobId);
+    } catch (e) {
+      appLog.error("[Settings] 取消批量 OCR 失败: " + String(e));
+    }
+  };
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex flex-wrap items-center gap-1.5 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+        识别语言
+        <LanguageSelector value={language} onChange={setLanguage} includeAuto />
+        <label className="flex items-center gap-1">
+          <input type="checkbox" checked={translate} onChange={(e) => setTranslate(e.target.checked)} />
+          同时翻译为
+        </label>
+        {translate && <LanguageSelector value={targetLang} onChange={setTargetLang} />}
+      </div>
+      <div className="flex flex-wrap items-center gap-1.5">
+        {formats.map(({ key, label }) => (
+          <button
+            key={key}
+            onClick={() => setFormat(key)}
+            className="text-xs transition-colors"
+            style={{
+              ...pillStyle,
+              backgroundColor: format === key ? "var(--color-primary)" : "var(--color-surface)",
+              color: format === key ? "#fff" : "var(--color-text-secondary)",
+            }}
+          >
+            {label}
+          </button>
+        ))}
+        <label className="flex items-center gap-1 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+          <input type="checkbox" checked={recursive} onChange={(e) => setRecursive(e.target.checked)} />
+          包含子文件夹
+        </label>
+      </div>
+      <div className="flex items-center justify-between gap-2">
+        <div className="text-xs truncate" style={{ color: "var(--color-text)" }} title={status}>
+          {status}
+        </div>
+        {jobId ? (
+          <div className="flex items-center gap-1.5 shrink-0">
+            <button onClick={handlePauseResume} className="text-xs transition-colors" style={pillStyle}>
+              {paused ? "继续" : "暂停"}
+            </button>
+            <button onClick={handleCancel} className="text-xs transition-colors" style={pillStyle}>
+              取消
+            </button>
+          </div>
+        ) : (
+          <button
+            onClick={handleChooseFolder}
+            title="结果写到所选文件夹下的 transshot-ocr；中断后用相同参数重新开始会跳过已完成的图片"
+            className="text-xs shrink-0 transition-colors"
+            style={pillStyle}
+          >
+            选择文件夹…
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,110 @@
+import { useEffect, useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import { translateDocument, cancelDocumentJob } from "../../lib/invoke";
+import { appLog } from "../../stores/logStore";
+import { LanguageSelector } from "../translation/LanguageSelector";
+import type { DocumentProgressEvent, DocumentFinishedEvent } from "../../types";
+
+const pillStyle = {
+  padding: "3px 10px",
+  borderRadius: "9999px",
+  border: "none",
+  cursor: "pointer",
+  backgroundColor: "var(--color-surface)",
+  color: "var(--color-text-secondary)",
+};
+
+/** 选择 txt / md / html / pdf 文件翻译，译文写到原文件旁（<文件名>.<目标语言>.<扩展名>） */
+export function DocumentTranslation() {
+  const [sourceLang, setSourceLang] = useState("auto");
+  const [targetLang, setTargetLang] = useState("zh-CN");
+  const [jobId, setJobId] = useState<string | null>(null);
+  const [status, setStatus] = useState("");
+  const jobIdRef = useRef<string | null>(null);
+
+  useEffect(() => {
+    const unlistenProgress = listen<DocumentProgressEvent>("document-progress", (event) => {
+      if (event.payload.job_id !== jobIdRef.current) return;
+      setStatus(`翻译中 ${event.payload.completed} / ${event.payload.total}`);
+    });
+    const unlistenFinished = listen<DocumentFinishedEvent>("document-finished", (event) => {
+      const { job_id, output_path, cancelled, error } = event.payload;
+      if (job_id !== jobIdRef.current) return;
+      jobIdRef.current = null;
+      setJobId(null);
+      if (error) {
+        setStatus("翻译失败：" + error);
+        appLog.error("[Settings] 文档翻译失败: " + error);
+      } else if (cancelled) {
+        setStatus("已取消");
+        appLog.info("[Settings] 文档翻译已取消: " + job_id);
+      } else {
+        setStatus("已保存到 " + (output_path ?? ""));
+        appLog.info("[Settings] 文档翻译完成: " + output_path);
+      }
+    });
+    return () => {
+      unlistenProgress.then((fn) => fn());
+      unlistenFinished.then((fn) => fn());
+    };
+  }, []);
+
+  const handleChooseFile = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Documents", extensions: ["txt", "md", "markdown", "html", "htm", "pdf"] }],
+    });
+    if (typeof selected !== "string") return;
+    try {
+      appLog.info("[Settings] 开始文档翻译: " + selected + ", " + sourceLang + " → " + targetLang);
+      const id = await translateDocument(selected, null, sourceLang, targetLang);
+      jobIdRef.current = id;
+      setJobId(id);
+      setStatus("准备中…");
+    } catch (e) {
+      setStatus("翻译失败：" + String(e));
+      appLog.error("[Settings] 文档翻译启动失败: " + String(e));
+    }
+  };
+
+  const handleCancel = async () => {
+    if (!jobId) return;
+    try {
+      await cancelDocumentJob(jobId);
+    } catch (e) {
+      appLog.error("[Settings] 取消文档翻译失败: " + String(e));
+    }
+  };
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex items-center justify-between gap-2">
+        <div className="flex items-center gap-1 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+          <LanguageSelector value={sourceLang} onChange={setSourceLang} includeAuto />
+          →
+          <LanguageSelector value={targetLang} onChange={setTargetLang} />
+        </div>
+        {jobId ? (
+          <button onClick={handleCancel} className="text-xs transition-colors" style={pillStyle}>
+            取消
+          </button>
+        ) : (
+          <button
+            onClick={handleChooseFile}
+            title="支持 txt / md / html / 带文本层的 pdf，译文保存在原文件旁"
+            className="text-xs transition-colors"
+            style={pillStyle}
+          >
+            选择文件…
+          </button>
+        )}
+      </div>
+      {status && (
+        <div className="text-xs truncate" style={{ color: "var(--color-text)" }} title={status}>
+          {status}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -4,6 +4,8 @@ import { appLog } from "../../stores/logStore";
 interface HotkeyInputProps {
   value: string;
   onChange: (value: string) => void;
+  /** Optional binding: Backspace/Delete while recording clears it */
+  clearable?: boolean;
 }
 
 const MOD_LABEL_MAC: Record<string, string> = {
@@ -62,7 +64,7 @@ function codeToToken(code: string, key: string): string | null {
   }
 }
 
-export function HotkeyInput({ value, onChange }: HotkeyInputProps) {
+export function HotkeyInput({ value, onChange, clearable = false }: HotkeyInputProps) {
   const [recording, setRecording] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const inputRef = useRef<HTMLDivElement>(null);
@@ -86,6 +88,15 @@ export function HotkeyInput({ value, onChange }: HotkeyInputProps) {
         return;
       }
 
+      // Optional bindings: Backspace / Delete (with no modifiers) unbinds
+      if (clearable && mods.length === 0 && (e.code === "Backspace" || e.code === "Delete")) {
+        appLog.info("[Settings] 清除快捷键");
+        onChange("");
+        setRecording(false);
+        setError(null);
+        return;
+      }
+
       // Skip pure modifier keydown
       if (["AltLeft", "AltRight", "ControlLeft", "ControlRight",
            "ShiftLeft", "ShiftRight", "MetaLeft", "MetaRight"].includes(e.code)) {
@@ -109,7 +120,7 @@ export function HotkeyInput({ value, onChange }: HotkeyInputProps) {
     };
     window.addEventListener("keydown", onKeyDown, true);
     return () => window.removeEventListener("keydown", onKeyDown, true);
-  }, [recording, onChange]);
+  }, [recording, onChange, clearable]);
 
   const startRecording = () => {
     setRecording(true);
@@ -134,7 +145,13 @@ export function HotkeyInput({ value, onChange }: HotkeyInputProps) {
           minWidth: "84px",
           cursor: "pointer",
         }}
-        title={recording ? "按下组合键，Esc 取消" : "点击录入新快捷键"}
+        title={
+          recording
+            ? clearable
+              ? "按下组合键，Backspace 清除，Esc 取消"
+              : "按下组合键，Esc 取消"
+            : "点击录入新快捷键"
+        }
       >
         {recording ? "按下组合键..." : (formatShortcut(value) || "未设置")}
       </button>
@@ -1,14 +1,25 @@
 import { useState, useEffect, useCallback } from "react";
 import { emit } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { getSettings, saveSettings, suspendHotkeys, resumeHotkeys } from "../../lib/invoke";
+import { getSettings, saveSettings, suspendHotkeys, resumeHotkeys, listOcrModes, listPreprocessProfiles, getOcrCacheStats, clearOcrCache, getRecentSaves, clearRecentSaves, openAnnotation, listTextActions } from "../../lib/invoke";
+import { open } from "@tauri-apps/plugin-dialog";
 import { appLog } from "../../stores/logStore";
 import { defaultSettings } from "../../stores/settingsStore";
 import { HotkeyInput } from "./HotkeyInput";
-import type { Settings, ServiceConfig, ExtraProvider, HotkeyConfig } from "../../types";
+import { DocumentTranslation } from "./DocumentTranslation";
+import { TextActions } from "./TextActions";
+import { BatchOcr } from "./BatchOcr";
+import type { Settings, ServiceConfig, ExtraProvider, HotkeyConfig, OcrEngine, OcrMode, OcrModeInfo, OcrCacheStats, OcrTiling, PreprocessProfile, TranslatePipeline, SaveFormat, CollisionPolicy, SavedScreenshot, ScreenshotConfig, TextAction, TranslationContextConfig } from "../../types";
 
 type TabName = "translation" | "ocr" | "tts";
 
+const ocrModeLabels: Record<OcrMode, string> = {
+  plain: "纯文本",
+  markdown: "Markdown",
+  latex: "LaTeX",
+  code: "代码",
+};
+
 const tabs: { key: TabName; label: string }[] = [
   { key: "translation", label: "翻译" },
   { key: "ocr", label: "OCR" },
@@ -303,6 +314,11 @@ function ServiceFields({
 export function SettingsPanel() {
   const [settings, setSettings] = useState<Settings>(defaultSettings);
   const [activeTab, setActiveTab] = useState<TabName>("translation");
+  const [ocrModes, setOcrModes] = useState<OcrModeInfo[]>([]);
+  const [preprocessProfiles, setPreprocessProfiles] = useState<PreprocessProfile[]>([]);
+  const [ocrCacheStats, setOcrCacheStats] = useState<OcrCacheStats | null>(null);
+  const [recentSaves, setRecentSaves] = useState<SavedScreenshot[]>([]);
+  const [builtinActions, setBuiltinActions] = useState<TextAction[]>([]);
 
   useEffect(() => {
     appLog.info("[Settings] 设置窗口: 加载配置...");
@@ -312,6 +328,70 @@ export function SettingsPanel() {
         setSettings(s);
       })
       .catch((e) => appLog.error("[Settings] 设置窗口: 配置加载失败: " + String(e)));
+    listOcrModes()
+      .then(setOcrModes)
+      .catch((e) => appLog.error("[Settings] OCR 模式加载失败: " + String(e)));
+    listPreprocessProfiles()
+      .then(setPreprocessProfiles)
+      .catch((e) => appLog.error("[Settings] 预处理 profile 加载失败: " + String(e)));
+    getOcrCacheStats()
+      .then(setOcrCacheStats)
+      .catch((e) => appLog.error("[Settings] OCR 缓存统计加载失败: " + String(e)));
+    getRecentSaves()
+      .then(setRecentSaves)
+      .catch((e) => appLog.error("[Settings] 最近保存列表加载失败: " + String(e)));
+    listTextActions()
+      .then((actions) => setBuiltinActions(actions.filter((a) => a.builtin)))
+      .catch((e) => appLog.error("[Settings] 文本动作加载失败: " + String(e)));
+  }, []);
+
+  const updateScreenshot = useCallback(<K extends keyof ScreenshotConfig>(key: K, value: ScreenshotConfig[K]) => {
+    setSettings((prev) => ({ ...prev, screenshot: { ...prev.screenshot, [key]: value } }));
+  }, []);
+
+  const updateTranslationContext = useCallback(
+    <K extends keyof TranslationContextConfig>(key: K, value: TranslationContextConfig[K]) => {
+      setSettings((prev) => ({ ...prev, translation_context: { ...prev.translation_context, [key]: value } }));
+    },
+    []
+  );
+
+  const handleClearRecentSaves = useCallback(async () => {
+    try {
+      await clearRecentSaves();
+      setRecentSaves([]);
+      appLog.info("[Settings] 最近保存列表已清空");
+    } catch (e) {
+      appLog.error("[Settings] 清空最近保存列表失败: " + String(e));
+    }
+  }, []);
+
+  // Reopen an annotated screenshot in the overlay; the edit is written back to the same file
+  const handleEditAnnotation = useCallback(async (path: string) => {
+    try {
+      appLog.info("[Settings] 重新编辑标注截图: " + path);
+      await openAnnotation(path);
+    } catch (e) {
+      appLog.error("[Settings] 重新编辑标注截图失败: " + String(e));
+    }
+  }, []);
+
+  const handleOpenAnnotation = useCallback(async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Annotated screenshots", extensions: ["png", "jpg", "jpeg", "webp", "json"] }],
+    });
+    if (typeof selected === "string") await handleEditAnnotation(selected);
+  }, [handleEditAnnotation]);
+
+  const handleClearOcrCache = useCallback(async () => {
+    try {
+      await clearOcrCache();
+      setOcrCacheStats(await getOcrCacheStats());
+      appLog.info("[Settings] OCR 缓存已清空");
+    } catch (e) {
+      appLog.error("[Settings] 清空 OCR 缓存失败: " + String(e));
+    }
   }, []);
 
   // 设置面板期间挂起全局快捷键，避免录入新组合时被系统拦截
@@ -350,6 +430,11 @@ export function SettingsPanel() {
       alert("快捷键不能为空，请为三个动作都设置快捷键");
       return;
     }
+    if (settings.actions.some((a) => !a.name.trim() || !a.prompt.trim())) {
+      appLog.warn("[Settings] 文本动作名称或提示词为空");
+      alert("文本动作的名称和提示词不能为空");
+      return;
+    }
     try {
       appLog.info("[Settings] 保存配置, translation.model=" + settings.translation.model + ", ocr.model=" + settings.ocr.model);
       await saveSettings(settings);
@@ -489,6 +574,224 @@ export function SettingsPanel() {
           ))}
         </div>
 
+        {/* OCR engine */}
+        {activeTab === "ocr" && (
+          <div className="space-y-2" style={{ marginBottom: "10px" }}>
+            <div>
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                识别引擎
+              </span>
+              <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                {([
+                  { key: "vision", label: "视觉模型" },
+                  { key: "tesseract", label: "Tesseract（离线）" },
+                ] as { key: OcrEngine; label: string }[]).map(({ key, label }) => (
+                  <button
+                    key={key}
+                    onClick={() =>
+                      setSettings((prev) => ({ ...prev, ocr: { ...prev.ocr, engine: key } }))
+                    }
+                    className="text-xs transition-colors"
+                    style={{
+                      padding: "3px 10px",
+                      borderRadius: "9999px",
+                      border: "none",
+                      cursor: "pointer",
+                      backgroundColor:
+                        settings.ocr.engine === key ? "var(--color-primary)" : "var(--color-surface)",
+                      color: settings.ocr.engine === key ? "#fff" : "var(--color-text-secondary)",
+                    }}
+                  >
+                    {label}
+                  </button>
+                ))}
+              </div>
+            </div>
+            <div>
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                图像预处理（小字放大、深色反色、增强对比度等）
+              </span>
+              <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                {[{ name: "", label: "不预处理" }, ...preprocessProfiles.map((p) => ({ name: p.name, label: p.name }))].map(
+                  ({ name, label }) => (
+                    <button
+                      key={name || "none"}
+                      onClick={() =>
+                        setSettings((prev) => ({ ...prev, ocr: { ...prev.ocr, preprocess_profile: name } }))
+                      }
+                      className="text-xs transition-colors"
+                      style={{
+                        padding: "3px 10px",
+                        borderRadius: "9999px",
+                        border: "none",
+                        cursor: "pointer",
+                        backgroundColor:
+                          (settings.ocr.preprocess_profile ?? "") === name
+                            ? "var(--color-primary)"
+                            : "var(--color-surface)",
+                        color:
+                          (settings.ocr.preprocess_profile ?? "") === name ? "#fff" : "var(--color-text-secondary)",
+                      }}
+                    >
+                      {label}
+                    </button>
+                  )
+                )}
+              </div>
+            </div>
+            {settings.ocr.engine === "vision" && (
+              <div>
+                <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                  区域翻译流程
+                </span>
+                <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                  {([
+                    { key: "two_step", label: "OCR 后翻译（两次请求）" },
+                    { key: "one_shot", label: "视觉模型直接翻译（一次请求）" },
+                  ] as { key: TranslatePipeline; label: string }[]).map(({ key, label }) => (
+                    <button
+                      key={key}
+                      onClick={() =>
+                        setSettings((prev) => ({ ...prev, ocr: { ...prev.ocr, translate_pipeline: key } }))
+                      }
+                      className="text-xs transition-colors"
+                      style={{
+                        padding: "3px 10px",
+                        borderRadius: "9999px",
+                        border: "none",
+                        cursor: "pointer",
+                        backgroundColor:
+                          (settings.ocr.translate_pipeline ?? "two_step") === key
+                            ? "var(--color-primary)"
+                            : "var(--color-surface)",
+                        color:
+                          (settings.ocr.translate_pipeline ?? "two_step") === key ? "#fff" : "var(--color-text-secondary)",
+                      }}
+                    >
+                      {label}
+                    </button>
+                  ))}
+                </div>
+              </div>
+            )}
+            {settings.ocr.engine === "vision" && (
+              <div>
+                <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                  大图分块识别（长截图按原分辨率分块，避免缩小后看不清）
+                </span>
+                <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                  {([
+                    { key: "auto", label: "自动" },
+                    { key: "always", label: "超过 2048px 即分块" },
+                    { key: "off", label: "关闭" },
+                  ] as { key: OcrTiling; label: string }[]).map(({ key, label }) => (
+                    <button
+                      key={key}
+                      onClick={() => setSettings((prev) => ({ ...prev, ocr: { ...prev.ocr, tiling: key } }))}
+                      className="text-xs transition-colors"
+                      style={{
+                        padding: "3px 10px",
+                        borderRadius: "9999px",
+                        border: "none",
+                        cursor: "pointer",
+                        backgroundColor:
+                          (settings.ocr.tiling ?? "auto") === key ? "var(--color-primary)" : "var(--color-surface)",
+                        color: (settings.ocr.tiling ?? "auto") === key ? "#fff" : "var(--color-text-secondary)",
+                      }}
+                    >
+                      {label}
+                    </button>
+                  ))}
+                </div>
+              </div>
+            )}
+            {settings.ocr.engine === "vision" && ocrCacheStats && (
+              <div className="flex items-center justify-between gap-2">
+                <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                  识别缓存：{ocrCacheStats.disk_entries} 条，{(ocrCacheStats.disk_bytes / 1024).toFixed(1)} KB；命中{" "}
+                  {ocrCacheStats.memory_hits + ocrCacheStats.disk_hits} / 未命中 {ocrCacheStats.misses}
+                </span>
+                <button
+                  onClick={handleClearOcrCache}
+                  className="text-xs transition-colors"
+                  style={{
+                    padding: "3px 10px",
+                    borderRadius: "9999px",
+                    border: "none",
+                    cursor: "pointer",
+                    backgroundColor: "var(--color-surface)",
+                    color: "var(--color-text-secondary)",
+                  }}
+                >
+                  清空缓存
+                </button>
+              </div>
+            )}
+            {settings.ocr.engine === "tesseract" && (
+              <label className="block">
+                <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                  tesseract 路径（留空则使用 PATH 中的 tesseract）
+                </span>
+                <input
+                  type="text"
+                  value={settings.ocr.tesseract_path ?? ""}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      ocr: { ...prev.ocr, tesseract_path: e.target.value },
+                    }))
+                  }
+                  className="w-full text-sm outline-none"
+                  style={{
+                    backgroundColor: "var(--color-surface)",
+                    color: "var(--color-text)",
+                    borderRadius: "8px",
+                    padding: "8px 10px",
+                    marginTop: "4px",
+                    border: "none",
+                  }}
+                  placeholder="/usr/local/bin/tesseract"
+                />
+              </label>
+            )}
+            <div>
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                输出模式提示词（留空使用内置提示词；语言提示仍会自动附加）
+              </span>
+              <div className="space-y-1.5 mt-1">
+                {ocrModes.map(({ mode, default_prompt }) => (
+                  <label key={mode} className="block">
+                    <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                      {ocrModeLabels[mode]}
+                    </span>
+                    <textarea
+                      value={settings.ocr.prompts?.[mode] ?? ""}
+                      onChange={(e) =>
+                        setSettings((prev) => ({
+                          ...prev,
+                          ocr: { ...prev.ocr, prompts: { ...prev.ocr.prompts, [mode]: e.target.value } },
+                        }))
+                      }
+                      className="w-full text-sm outline-none resize-none"
+                      style={{
+                        backgroundColor: "var(--color-surface)",
+                        color: "var(--color-text)",
+                        borderRadius: "8px",
+                        padding: "8px 10px",
+                        marginTop: "4px",
+                        border: "none",
+                        minHeight: "56px",
+                      }}
+                      placeholder={default_prompt}
+                      rows={2}
+                    />
+                  </label>
+                ))}
+              </div>
+            </div>
+          </div>
+        )}
+
         {/* Service config for active tab */}
         <ServiceFields
           config={settings[activeTab]}
@@ -498,6 +801,340 @@ export function SettingsPanel() {
           onActiveChange={(active) => updateActive(activeTab, active)}
         />
 
+        {/* Translation context */}
+        <div style={{ marginTop: "14px" }}>
+          <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
+            翻译上下文（主窗口手动翻译）
+          </h3>
+          <div className="space-y-2">
+            <label className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                领域提示（如 finance、software UI，留空不发送）
+              </span>
+              <input
+                type="text"
+                value={settings.translation_context.domain}
+                onChange={(e) => updateTranslationContext("domain", e.target.value)}
+                placeholder="不限"
+                className="text-xs outline-none"
+                style={{
+                  width: "140px",
+                  backgroundColor: "var(--color-surface)",
+                  color: "var(--color-text)",
+                  borderRadius: "6px",
+                  padding: "3px 6px",
+                  border: "none",
+                }}
+              />
+            </label>
+            <label className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                参考本次会话最近几条翻译（0-10，0 为关闭）
+              </span>
+              <input
+                type="number"
+                min={0}
+                max={10}
+                value={settings.translation_context.history_turns}
+                onChange={(e) =>
+                  updateTranslationContext("history_turns", Math.min(10, Math.max(0, Math.floor(Number(e.target.value) || 0))))
+                }
+                className="text-xs outline-none"
+                style={{
+                  width: "52px",
+                  backgroundColor: "var(--color-surface)",
+                  color: "var(--color-text)",
+                  borderRadius: "6px",
+                  padding: "3px 6px",
+                  border: "none",
+                }}
+              />
+            </label>
+          </div>
+        </div>
+
+        {/* Screenshot auto-save */}
+        <div style={{ marginTop: "14px" }}>
+          <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
+            截图
+          </h3>
+          <div className="space-y-2">
+            <label className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                延时截图倒计时（秒，1-10，用于截取悬停菜单/提示）
+              </span>
+              <input
+                type="number"
+                min={1}
+                max={10}
+                value={settings.screenshot.capture_delay_secs}
+                onChange={(e) =>
+                  updateScreenshot("capture_delay_secs", Math.min(10, Math.max(1, Number(e.target.value) || 3)))
+                }
+                className="text-xs outline-none"
+                style={{
+                  width: "52px",
+                  backgroundColor: "var(--color-surface)",
+                  color: "var(--color-text)",
+                  borderRadius: "6px",
+                  padding: "3px 6px",
+                  border: "none",
+                }}
+              />
+            </label>
+            <div className="flex flex-wrap items-center gap-1.5">
+              {([
+                { key: false, label: "仅复制到剪贴板" },
+                { key: true, label: "同时自动保存" },
+              ] as { key: boolean; label: string }[]).map(({ key, label }) => (
+                <button
+                  key={String(key)}
+                  onClick={() => updateScreenshot("auto_save", key)}
+                  className="text-xs transition-colors"
+                  style={{
+                    padding: "3px 10px",
+                    borderRadius: "9999px",
+                    border: "none",
+                    cursor: "pointer",
+                    backgroundColor:
+                      settings.screenshot.auto_save === key ? "var(--color-primary)" : "var(--color-surface)",
+                    color: settings.screenshot.auto_save === key ? "#fff" : "var(--color-text-secondary)",
+                  }}
+                >
+                  {label}
+                </button>
+              ))}
+            </div>
+            {settings.screenshot.auto_save && (
+              <>
+                <label className="block">
+                  <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                    保存目录（留空保存到 图片/DH-TransShot）
+                  </span>
+                  <input
+                    type="text"
+                    value={settings.screenshot.save_dir}
+                    onChange={(e) => updateScreenshot("save_dir", e.target.value)}
+                    className="w-full text-sm outline-none"
+                    style={{
+                      backgroundColor: "var(--color-surface)",
+                      color: "var(--color-text)",
+                      borderRadius: "8px",
+                      padding: "8px 10px",
+                      marginTop: "4px",
+                      border: "none",
+                    }}
+                    placeholder="~/Pictures/DH-TransShot"
+                  />
+                </label>
+                <label className="block">
+                  <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                    文件名模板（{"{date} {time} {datetime} {monitor} {mode} {counter}"}）
+                  </span>
+                  <input
+                    type="text"
+                    value={settings.screenshot.filename_template}
+                    onChange={(e) => updateScreenshot("filename_template", e.target.value)}
+                    className="w-full text-sm outline-none"
+                    style={{
+                      backgroundColor: "var(--color-surface)",
+                      color: "var(--color-text)",
+                      borderRadius: "8px",
+                      padding: "8px 10px",
+                      marginTop: "4px",
+                      border: "none",
+                    }}
+                    placeholder="TransShot_{date}_{time}"
+                  />
+                </label>
+                <div>
+                  <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                    格式
+                  </span>
+                  <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                    {([
+                      { key: "png", label: "PNG" },
+                      { key: "jpeg", label: "JPEG" },
+                      { key: "webp", label: "WebP（无损）" },
+                    ] as { key: SaveFormat; label: string }[]).map(({ key, label }) => (
+                      <button
+                        key={key}
+                        onClick={() => updateScreenshot("format", key)}
+                        className="text-xs transition-colors"
+                        style={{
+                          padding: "3px 10px",
+                          borderRadius: "9999px",
+                          border: "none",
+                          cursor: "pointer",
+                          backgroundColor:
+                            settings.screenshot.format === key ? "var(--color-primary)" : "var(--color-surface)",
+                          color: settings.screenshot.format === key ? "#fff" : "var(--color-text-secondary)",
+                        }}
+                      >
+                        {label}
+                      </button>
+                    ))}
+                    {settings.screenshot.format === "jpeg" && (
+                      <label className="flex items-center gap-1 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                        质量
+                        <input
+                          type="number"
+                          min={1}
+                          max={100}
+                          value={settings.screenshot.jpeg_quality}
+                          onChange={(e) =>
+                            updateScreenshot("jpeg_quality", Math.min(100, Math.max(1, Number(e.target.value) || 90)))
+                          }
+                          className="text-xs outline-none"
+                          style={{
+                            width: "52px",
+                            backgroundColor: "var(--color-surface)",
+                            color: "var(--color-text)",
+                            borderRadius: "6px",
+                            padding: "3px 6px",
+                            border: "none",
+                          }}
+                        />
+                      </label>
+                    )}
+                  </div>
+                </div>
+                <div>
+                  <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                    文件已存在时
+                  </span>
+                  <div className="flex flex-wrap items-center gap-1.5 mt-1">
+                    {([
+                      { key: "suffix", label: "追加序号（-2、-3…）" },
+                      { key: "overwrite", label: "覆盖" },
+                    ] as { key: CollisionPolicy; label: string }[]).map(({ key, label }) => (
+                      <button
+                        key={key}
+                        onClick={() => updateScreenshot("collision", key)}
+                        className="text-xs transition-colors"
+                        style={{
+                          padding: "3px 10px",
+                          borderRadius: "9999px",
+                          border: "none",
+                          cursor: "pointer",
+                          backgroundColor:
+                            settings.screenshot.collision === key ? "var(--color-primary)" : "var(--color-surface)",
+                          color: settings.screenshot.collision === key ? "#fff" : "var(--color-text-secondary)",
+                        }}
+                      >
+                        {label}
+                      </button>
+                    ))}
+                  </div>
+                </div>
+              </>
+            )}
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                重新编辑带标注的截图
+              </span>
+              <button
+                onClick={handleOpenAnnotation}
+                title="选择保存过标注的图片（同目录需有 .annotations.json）"
+                className="text-xs transition-colors"
+                style={{
+                  padding: "3px 10px",
+                  borderRadius: "9999px",
+                  border: "none",
+                  cursor: "pointer",
+                  backgroundColor: "var(--color-surface)",
+                  color: "var(--color-text-secondary)",
+                }}
+              >
+                打开…
+              </button>
+            </div>
+            {recentSaves.length > 0 && (
+              <div>
+                <div className="flex items-center justify-between gap-2">
+                  <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                    最近保存（本次运行）
+                  </span>
+                  <button
+                    onClick={handleClearRecentSaves}
+                    className="text-xs transition-colors"
+                    style={{
+                      padding: "3px 10px",
+                      borderRadius: "9999px",
+                      border: "none",
+                      cursor: "pointer",
+                      backgroundColor: "var(--color-surface)",
+                      color: "var(--color-text-secondary)",
+                    }}
+                  >
+                    清空列表
+                  </button>
+                </div>
+                <div className="space-y-0.5 mt-1">
+                  {recentSaves.map((saved) => (
+                    <div key={saved.path + saved.saved_at} className="flex items-center gap-2">
+                      <div
+                        className="text-xs truncate flex-1"
+                        style={{ color: "var(--color-text)" }}
+                        title={saved.path}
+                      >
+                        {new Date(saved.saved_at).toLocaleTimeString()} · {saved.width}×{saved.height} ·{" "}
+                        {(saved.bytes / 1024).toFixed(1)} KB · {saved.path}
+                      </div>
+                      {saved.annotations && (
+                        <button
+                          onClick={() => handleEditAnnotation(saved.path)}
+                          className="text-xs shrink-0 transition-colors"
+                          style={{
+                            border: "none",
+                            cursor: "pointer",
+                            background: "none",
+                            color: "var(--color-primary)",
+                          }}
+                        >
+                          编辑
+                        </button>
+                      )}
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+          </div>
+        </div>
+
+        {/* Text actions */}
+        <div style={{ marginTop: "14px" }}>
+          <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
+            文本动作（对选中文本执行，可绑定快捷键）
+          </h3>
+          <TextActions
+            builtins={builtinActions}
+            custom={settings.actions}
+            hotkeys={settings.hotkeys.actions ?? {}}
+            onCustomChange={(actions) => setSettings((prev) => ({ ...prev, actions }))}
+            onHotkeysChange={(actions) =>
+              setSettings((prev) => ({ ...prev, hotkeys: { ...prev.hotkeys, actions } }))
+            }
+          />
+        </div>
+
+        {/* Document translation */}
+        <div style={{ marginTop: "14px" }}>
+          <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
+            文档翻译
+          </h3>
+          <DocumentTranslation />
+        </div>
+
+        {/* Batch OCR */}
+        <div style={{ marginTop: "14px" }}>
+          <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
+            批量 OCR（文件夹）
+          </h3>
+          <BatchOcr />
+        </div>
+
         {/* Hotkeys */}
         <div style={{ marginTop: "14px" }}>
           <h3 className="text-xs font-medium" style={{ color: "var(--color-text-secondary)", marginBottom: "6px" }}>
@@ -524,6 +1161,86 @@ export function SettingsPanel() {
                 />
               </div>
             ))}
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                延时截图
+              </span>
+              <HotkeyInput
+                clearable
+                value={settings.hotkeys?.delayed_screenshot ?? ""}
+                onChange={(v) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    hotkeys: { ...prev.hotkeys, delayed_screenshot: v },
+                  }))
+                }
+              />
+            </div>
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                全屏截图
+              </span>
+              <HotkeyInput
+                clearable
+                value={settings.hotkeys?.fullscreen_screenshot ?? ""}
+                onChange={(v) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    hotkeys: { ...prev.hotkeys, fullscreen_screenshot: v },
+                  }))
+                }
+              />
+            </div>
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                窗口截图
+              </span>
+              <HotkeyInput
+                clearable
+                value={settings.hotkeys?.window_screenshot ?? ""}
+                onChange={(v) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    hotkeys: { ...prev.hotkeys, window_screenshot: v },
+                  }))
+                }
+              />
+            </div>
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                长截图
+              </span>
+              <HotkeyInput
+                clearable
+                value={settings.hotkeys?.scroll_screenshot ?? ""}
+                onChange={(v) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    hotkeys: { ...prev.hotkeys, scroll_screenshot: v },
+                  }))
+                }
+              />
+            </div>
+            {ocrModes.map(({ mode }) => (
+              <div key={mode} className="flex items-center justify-between gap-2">
+                <span className="text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                  区域识别（{ocrModeLabels[mode]}）
+                </span>
+                <HotkeyInput
+                  clearable
+                  value={settings.hotkeys?.ocr_modes?.[mode] ?? ""}
+                  onChange={(v) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      hotkeys: {
+                        ...prev.hotkeys,
+                        ocr_modes: { ...prev.hotkeys.ocr_modes, [mode]: v },
+                      },
+                    }))
+                  }
+                />
+              </div>
+            ))}
           </div>
         </div>
       </div>
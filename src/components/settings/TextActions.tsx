@@ -0,0 +1,148 @@
+import { useState } from "react";
+import { HotkeyInput } from "./HotkeyInput";
+import type { TextAction } from "../../types";
+
+interface TextActionsProps {
+  /** 内置动作（`listTextActions` 中 builtin 为 true 的项） */
+  builtins: TextAction[];
+  /** settings.actions：自定义动作，与内置动作同 id 时覆盖内置提示词 */
+  custom: TextAction[];
+  /** settings.hotkeys.actions：动作 id → 快捷键 */
+  hotkeys: Record<string, string>;
+  onCustomChange: (actions: TextAction[]) => void;
+  onHotkeysChange: (hotkeys: Record<string, string>) => void;
+}
+
+const inputStyle = {
+  backgroundColor: "var(--color-surface)",
+  color: "var(--color-text)",
+  borderRadius: "8px",
+  padding: "8px 10px",
+  marginTop: "4px",
+  border: "none",
+};
+
+const linkStyle = {
+  border: "none",
+  cursor: "pointer",
+  background: "none",
+  color: "var(--color-primary)",
+};
+
+/** 文本动作列表：内置 + 自定义，每项可绑定快捷键；自定义项可编辑名称与提示词 */
+export function TextActions({ builtins, custom, hotkeys, onCustomChange, onHotkeysChange }: TextActionsProps) {
+  const [editingId, setEditingId] = useState<string | null>(null);
+
+  const builtinIds = new Set(builtins.map((a) => a.id));
+  const rows: TextAction[] = [
+    ...builtins.map((b) => custom.find((c) => c.id === b.id) ?? b),
+    ...custom.filter((c) => !builtinIds.has(c.id)),
+  ];
+  const isOverride = (id: string) => builtinIds.has(id) && custom.some((c) => c.id === id);
+
+  const setHotkey = (id: string, value: string) => {
+    const next = { ...hotkeys };
+    if (value) {
+      next[id] = value;
+    } else {
+      delete next[id];
+    }
+    onHotkeysChange(next);
+  };
+
+  const updateCustom = (id: string, key: "name" | "prompt", value: string) => {
+    onCustomChange(custom.map((a) => (a.id === id ? { ...a, [key]: value } : a)));
+  };
+
+  // Editing a built-in stores a same-id copy in settings.actions, which replaces it
+  const startEdit = (action: TextAction) => {
+    if (action.builtin) {
+      onCustomChange([...custom, { ...action, builtin: false }]);
+    }
+    setEditingId(action.id);
+  };
+
+  const addAction = () => {
+    const id = "custom_" + Date.now().toString(36);
+    onCustomChange([
+      ...custom,
+      { id, name: `自定义动作 ${custom.length + 1}`, prompt: "", builtin: false },
+    ]);
+    setEditingId(id);
+  };
+
+  // Removing an override restores the built-in; removing a custom action also drops its hotkey
+  const removeCustom = (id: string) => {
+    onCustomChange(custom.filter((a) => a.id !== id));
+    if (!builtinIds.has(id) && id in hotkeys) {
+      setHotkey(id, "");
+    }
+    if (editingId === id) setEditingId(null);
+  };
+
+  return (
+    <div className="space-y-1.5">
+      {rows.map((action) => (
+        <div key={action.id}>
+          <div className="flex items-center justify-between gap-2">
+            <span className="text-xs truncate" style={{ color: "var(--color-text-secondary)" }} title={action.prompt}>
+              {action.name || action.id}
+            </span>
+            <div className="flex items-center gap-2 shrink-0">
+              {editingId === action.id ? (
+                <button onClick={() => setEditingId(null)} className="text-xs" style={linkStyle}>
+                  收起
+                </button>
+              ) : (
+                <button onClick={() => startEdit(action)} className="text-xs" style={linkStyle}>
+                  编辑
+                </button>
+              )}
+              {!action.builtin && (
+                <button onClick={() => removeCustom(action.id)} className="text-xs" style={{ ...linkStyle, color: "#ef4444" }}>
+                  {isOverride(action.id) ? "恢复默认" : "删除"}
+                </button>
+              )}
+              <HotkeyInput clearable value={hotkeys[action.id] ?? ""} onChange={(v) => setHotkey(action.id, v)} />
+            </div>
+          </div>
+          {editingId === action.id && !action.builtin && (
+            <div className="space-y-1.5" style={{ marginTop: "4px" }}>
+              <input
+                type="text"
+                value={action.name}
+                onChange={(e) => updateCustom(action.id, "name", e.target.value)}
+                className="w-full text-sm outline-none"
+                style={inputStyle}
+                placeholder="动作名称"
+              />
+              <textarea
+                value={action.prompt}
+                onChange={(e) => updateCustom(action.id, "prompt", e.target.value)}
+                className="w-full text-sm outline-none resize-none"
+                style={{ ...inputStyle, minHeight: "72px" }}
+                placeholder="系统提示词，选中的文本作为用户消息；{target_lang} 会替换为目标语言"
+                rows={3}
+              />
+            </div>
+          )}
+        </div>
+      ))}
+      <button
+        onClick={addAction}
+        className="text-xs transition-colors"
+        style={{
+          padding: "3px 10px",
+          borderRadius: "9999px",
+          border: "1px dashed var(--color-text-secondary)",
+          cursor: "pointer",
+          backgroundColor: "transparent",
+          color: "var(--color-text-secondary)",
+          opacity: 0.7,
+        }}
+      >
+        + 新增动作
+      </button>
+    </div>
+  );
+}
@@ -1,5 +1,5 @@
 import { useCallback } from "react";
-import { startRegionSelect } from "../lib/invoke";
+import { startRegionSelect, startDelayedRegionSelect } from "../lib/invoke";
 import { appLog } from "../stores/logStore";
 
 export function useScreenshot() {
@@ -13,5 +13,14 @@ export function useScreenshot() {
     }
   }, []);
 
-  return { startRegion };
+  const startDelayedRegion = useCallback(async (mode: string = "screenshot") => {
+    try {
+      appLog.info("[Screenshot] 启动延时区域选择, mode=" + mode);
+      await startDelayedRegionSelect(mode);
+    } catch (e) {
+      appLog.error("[Screenshot] 延时区域选择启动失败: " + String(e));
+    }
+  }, []);
+
+  return { startRegion, startDelayedRegion };
 }
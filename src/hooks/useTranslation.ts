@@ -46,13 +46,20 @@ export function useTranslation() {
         return;
       }
 
-      appLog.info("[Translate] 手动翻译: " + sourceLang + " → " + targetLang + ", 文本长度=" + input.length);
+      const { domain, history_turns } = settings.translation_context;
+      appLog.info(
+        "[Translate] 手动翻译: " + sourceLang + " → " + targetLang + ", 文本长度=" + input.length +
+          ", domain=" + (domain || "-") + ", history_turns=" + history_turns
+      );
       const generation = ++translateGeneration;
       setIsTranslating(true);
       setError(null);
 
       try {
-        const result = await translateText(input, sourceLang, targetLang);
+        const result = await translateText(input, sourceLang, targetLang, {
+          domain: domain.trim() || undefined,
+          historyTurns: history_turns || undefined,
+        });
         if (generation !== translateGeneration) {
           appLog.info("[Translate] 翻译结果已过期, 丢弃");
           return;
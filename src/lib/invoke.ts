@@ -1,10 +1,44 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings, ScreenshotInitEvent } from "../types";
+import type { Settings, ScreenshotInitEvent, TextAction, ImageTranslation, DecodedCode, ImageOcrResult, VisionTranslation, OcrMode, OcrModeInfo, PreprocessProfile, OcrCacheStats, BatchOcrRequest, SavedScreenshot, DirectCapture, PinInfo, Annotation } from "../types";
 
 export async function startRegionSelect(mode: string): Promise<void> {
   return invoke("start_region_select", { mode });
 }
 
+/** 倒计时结束后再冻结屏幕；倒计时期间再次调用会取消。delaySecs 缺省取设置值 */
+export async function startDelayedRegionSelect(mode: string, delaySecs?: number): Promise<void> {
+  return invoke("start_delayed_region_select", { mode, delaySecs: delaySecs ?? null });
+}
+
+/** 直接截取整个显示器并复制 / 自动保存；monitor 缺省为光标所在显示器 */
+export async function captureFullscreen(monitor?: number): Promise<DirectCapture> {
+  return invoke("capture_fullscreen", { monitor: monitor ?? null });
+}
+
+/** 直接截取单个窗口并复制 / 自动保存；windowId 缺省为当前活动窗口 */
+export async function captureWindow(windowId?: number): Promise<DirectCapture> {
+  return invoke("capture_window", { windowId: windowId ?? null });
+}
+
+/**
+ * 长截图：在区域选择后反复截取该区域并自动拼接，直到 stopScrollCapture / 达到高度上限 / 60 秒无新内容。
+ * 结束后复制到剪贴板并走自动保存，Promise 在此时才 resolve
+ */
+export async function startScrollCapture(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number
+): Promise<DirectCapture> {
+  return invoke("start_scroll_capture", { monitorIndex, x, y, width, height });
+}
+
+/** 结束进行中的长截图；返回是否确实有长截图在进行 */
+export async function stopScrollCapture(): Promise<boolean> {
+  return invoke("stop_scroll_capture");
+}
+
 export async function captureRegion(
   monitorIndex: number,
   x: number,
@@ -25,17 +59,133 @@ export async function captureAndOcr(
   y: number,
   width: number,
   height: number,
-  language: string
+  language: string,
+  mode?: OcrMode
 ): Promise<string> {
-  return invoke("capture_and_ocr", { monitorIndex, x, y, width, height, language });
+  return invoke("capture_and_ocr", { monitorIndex, x, y, width, height, language, mode });
+}
+
+/** 一次视觉请求完成识别 + 翻译 */
+export async function captureAndTranslate(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number,
+  sourceLang: string,
+  targetLang: string
+): Promise<VisionTranslation> {
+  return invoke("capture_and_translate", { monitorIndex, x, y, width, height, sourceLang, targetLang });
+}
+
+export async function listOcrModes(): Promise<OcrModeInfo[]> {
+  return invoke("list_ocr_modes");
+}
+
+export async function listPreprocessProfiles(): Promise<PreprocessProfile[]> {
+  return invoke("list_preprocess_profiles");
+}
+
+export async function getOcrCacheStats(): Promise<OcrCacheStats> {
+  return invoke("get_ocr_cache_stats");
+}
+
+export async function clearOcrCache(): Promise<void> {
+  return invoke("clear_ocr_cache");
+}
+
+/** OCR 图片文件（PNG/JPEG/WebP/BMP/TIFF）；传入 targetLang 时同时翻译 */
+export async function ocrImageFile(
+  path: string,
+  language: string,
+  mode?: OcrMode,
+  targetLang?: string
+): Promise<ImageOcrResult> {
+  return invoke("ocr_image_file", { path, language, mode, targetLang });
+}
+
+/** OCR 剪贴板中的图片；传入 targetLang 时同时翻译 */
+export async function ocrClipboardImage(
+  language: string,
+  mode?: OcrMode,
+  targetLang?: string
+): Promise<ImageOcrResult> {
+  return invoke("ocr_clipboard_image", { language, mode, targetLang });
+}
+
+/** OCR 最近一次长截图的拼接结果；传入 targetLang 时同时翻译 */
+export async function ocrScrollCapture(
+  language: string,
+  mode?: OcrMode,
+  targetLang?: string
+): Promise<ImageOcrResult> {
+  return invoke("ocr_scroll_capture", { language, mode, targetLang });
+}
+
+/** 省略区域时扫描整个显示器 */
+export async function decodeCodes(
+  monitorIndex: number,
+  region?: { x: number; y: number; width: number; height: number }
+): Promise<DecodedCode[]> {
+  return invoke("decode_codes", { monitorIndex, ...region });
+}
+
+/** OCR the region, translate each text block and paint translations in place */
+export async function translateImageRegion(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number,
+  sourceLang: string,
+  targetLang: string
+): Promise<ImageTranslation> {
+  return invoke("translate_image_region", {
+    monitorIndex,
+    x,
+    y,
+    width,
+    height,
+    sourceLang,
+    targetLang,
+  });
+}
+
+export interface TranslateOptions {
+  /** Surrounding text (neighbouring OCR text, window title...) — reference only */
+  context?: string;
+  /** Domain hint, e.g. "finance", "software UI" */
+  domain?: string;
+  /** Include the last N translations of this session as context */
+  historyTurns?: number;
 }
 
 export async function translateText(
   text: string,
   sourceLang: string,
+  targetLang: string,
+  options: TranslateOptions = {}
+): Promise<string> {
+  return invoke("translate_text", {
+    text,
+    sourceLang,
+    targetLang,
+    context: options.context ?? null,
+    domain: options.domain ?? null,
+    historyTurns: options.historyTurns ?? null,
+  });
+}
+
+export async function listTextActions(): Promise<TextAction[]> {
+  return invoke("list_text_actions");
+}
+
+export async function runTextAction(
+  actionId: string,
+  text: string,
   targetLang: string
 ): Promise<string> {
-  return invoke("translate_text", { text, sourceLang, targetLang });
+  return invoke("run_text_action", { actionId, text, targetLang });
 }
 
 export async function getSettings(): Promise<Settings> {
@@ -62,6 +212,78 @@ export async function saveFile(path: string, base64Data: string): Promise<void>
   return invoke("save_file", { path, base64Data });
 }
 
+/** 把图片（base64 PNG）钉到屏幕；left/top 为该显示器覆盖层内的 CSS 坐标。返回贴图窗口 label */
+export async function pinImage(monitorIndex: number, left: number, top: number, imageBase64: string): Promise<string> {
+  return invoke("pin_image", { monitorIndex, left, top, imageBase64 });
+}
+
+export async function getPin(label: string): Promise<PinInfo> {
+  return invoke("get_pin", { label });
+}
+
+export async function copyPin(label: string): Promise<void> {
+  return invoke("copy_pin", { label });
+}
+
+/** 按扩展名（png / jpg / webp）保存贴图 */
+export async function savePin(label: string, path: string): Promise<void> {
+  return invoke("save_pin", { label, path });
+}
+
+/**
+ * 自动保存关闭时返回 null；传入 annotation 时在后端把标注渲染到裁切图上，并写出 sidecar。
+ * 重新编辑的标注截图总是写回原文件
+ */
+export async function saveScreenshot(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number,
+  mode: string,
+  annotation?: Annotation
+): Promise<SavedScreenshot | null> {
+  return invoke("save_screenshot", { monitorIndex, x, y, width, height, mode, annotation: annotation ?? null });
+}
+
+/** 在后端把标注渲染到冻结截图的区域上，返回 base64 PNG */
+export async function renderAnnotation(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number,
+  annotation: Annotation
+): Promise<string> {
+  return invoke("render_annotation", { monitorIndex, x, y, width, height, annotation });
+}
+
+/** 渲染标注并按扩展名（png / jpg / webp）保存到 path，同时写出 sidecar */
+export async function saveAnnotated(
+  monitorIndex: number,
+  x: number,
+  y: number,
+  width: number,
+  height: number,
+  annotation: Annotation,
+  path: string
+): Promise<void> {
+  return invoke("save_annotated", { monitorIndex, x, y, width, height, annotation, path });
+}
+
+/** 重新打开标注截图（图片或其 sidecar）进入标注模式；确认后写回原图片 */
+export async function openAnnotation(path: string): Promise<void> {
+  return invoke("open_annotation", { path });
+}
+
+export async function getRecentSaves(): Promise<SavedScreenshot[]> {
+  return invoke("get_recent_saves");
+}
+
+export async function clearRecentSaves(): Promise<void> {
+  return invoke("clear_recent_saves");
+}
+
 export async function synthesizeSpeech(text: string): Promise<string> {
   return invoke("synthesize_speech", { text });
 }
@@ -73,3 +295,33 @@ export async function suspendHotkeys(): Promise<void> {
 export async function resumeHotkeys(): Promise<void> {
   return invoke("resume_hotkeys");
 }
+
+export async function translateDocument(
+  inputPath: string,
+  outputPath: string | null,
+  sourceLang: string,
+  targetLang: string
+): Promise<string> {
+  return invoke("translate_document", { inputPath, outputPath, sourceLang, targetLang });
+}
+
+export async function cancelDocumentJob(jobId: string): Promise<void> {
+  return invoke("cancel_document_job", { jobId });
+}
+
+/** Start a folder OCR job; returns the job id. Re-running with the same options resumes. */
+export async function startBatchOcr(request: BatchOcrRequest): Promise<string> {
+  return invoke("start_batch_ocr", { request });
+}
+
+export async function pauseBatchOcr(jobId: string): Promise<void> {
+  return invoke("pause_batch_ocr", { jobId });
+}
+
+export async function resumeBatchOcr(jobId: string): Promise<void> {
+  return invoke("resume_batch_ocr", { jobId });
+}
+
+export async function cancelBatchOcr(jobId: string): Promise<void> {
+  return invoke("cancel_batch_ocr", { jobId });
+}
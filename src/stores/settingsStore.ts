@@ -24,6 +24,10 @@ export const defaultSettings: Settings = {
     providers: [],
     active: -1,
   },
+  translation_context: {
+    domain: "",
+    history_turns: 0,
+  },
   ocr: {
     model: "Qwen/Qwen3.5-4B",
     extra: `{
@@ -34,6 +38,13 @@ export const defaultSettings: Settings = {
 }`,
     providers: [],
     active: -1,
+    engine: "vision",
+    tesseract_path: "",
+    prompts: {},
+    preprocess_profile: "",
+    preprocess_profiles: [],
+    tiling: "auto",
+    translate_pipeline: "two_step",
   },
   tts: {
     model: "FunAudioLLM/CosyVoice2-0.5B",
@@ -51,6 +62,22 @@ export const defaultSettings: Settings = {
     screenshot: "Alt+A",
     ocr_translate: "Alt+S",
     clipboard_translate: "Alt+Q",
+    actions: {},
+    ocr_modes: {},
+    delayed_screenshot: "",
+    fullscreen_screenshot: "",
+    window_screenshot: "",
+    scroll_screenshot: "",
+  },
+  actions: [],
+  screenshot: {
+    auto_save: false,
+    save_dir: "",
+    filename_template: "TransShot_{date}_{time}",
+    format: "png",
+    jpeg_quality: 90,
+    collision: "suffix",
+    capture_delay_secs: 3,
   },
 };
 
@@ -2,9 +2,111 @@ export interface Settings {
   base_url: string;
   api_key: string;
   translation: ServiceConfig;
-  ocr: ServiceConfig;
+  /** 主窗口手动翻译附带的上下文 */
+  translation_context: TranslationContextConfig;
+  ocr: OcrConfig;
   tts: ServiceConfig;
   hotkeys: HotkeyConfig;
+  /** 用户自定义文本动作（内置动作不保存在这里） */
+  actions: TextAction[];
+  screenshot: ScreenshotConfig;
+}
+
+export interface TranslationContextConfig {
+  /** 领域提示，如 "finance"、"software UI"；留空不发送 */
+  domain: string;
+  /** 附带本次会话最近几条翻译作为参考（0 为关闭） */
+  history_turns: number;
+}
+
+export type SaveFormat = "png" | "jpeg" | "webp";
+
+/** 重名处理：suffix 追加 -2、-3…；overwrite 直接覆盖 */
+export type CollisionPolicy = "suffix" | "overwrite";
+
+export interface ScreenshotConfig {
+  /** 区域截图复制到剪贴板的同时自动保存到文件 */
+  auto_save: boolean;
+  /** 保存目录；留空为 图片/DH-TransShot */
+  save_dir: string;
+  /** 文件名模板，支持 {date} {time} {datetime} {monitor} {mode} {counter} */
+  filename_template: string;
+  format: SaveFormat;
+  /** JPEG 质量 1-100，仅 format 为 jpeg 时生效 */
+  jpeg_quality: number;
+  collision: CollisionPolicy;
+  /** 延时截图倒计时秒数（1-10） */
+  capture_delay_secs: number;
+}
+
+export interface SavedScreenshot {
+  path: string;
+  format: SaveFormat;
+  width: number;
+  height: number;
+  bytes: number;
+  /** Unix 毫秒时间戳 */
+  saved_at: number;
+  /** 标注截图的 sidecar（<图片>.annotations.json）路径，可用 openAnnotation 重新编辑 */
+  annotations: string | null;
+}
+
+export interface AnnotationPoint {
+  x: number;
+  y: number;
+}
+
+/** 一个标注图形；坐标与尺寸均为裁切后截图的像素，颜色为 #rrggbb(aa) */
+export type AnnotationShape =
+  | { type: "rect"; x: number; y: number; w: number; h: number; color: string; strokeWidth: number; radius: number }
+  | { type: "ellipse"; x: number; y: number; w: number; h: number; color: string; strokeWidth: number }
+  | { type: "arrow"; x1: number; y1: number; x2: number; y2: number; color: string; strokeWidth: number }
+  | { type: "pen"; points: AnnotationPoint[]; color: string; strokeWidth: number }
+  | { type: "text"; x: number; y: number; text: string; color: string; fontSize: number; bold: boolean }
+  /** 序号标记：以 (x, y) 为圆心的实心圆，内写 number */
+  | { type: "step"; x: number; y: number; number: number; color: string; radius: number }
+  | { type: "mosaic"; points: AnnotationPoint[]; strokeWidth: number; blockSize?: number }
+  /** 高斯模糊；radius 为标准差，与 CSS blur() 一致 */
+  | { type: "blur"; x: number; y: number; w: number; h: number; radius: number };
+
+/** 矢量标注，由后端渲染到截图上，并随图片保存为 sidecar 以便重新编辑 */
+export interface Annotation {
+  version: number;
+  /** 自下而上绘制 */
+  shapes: AnnotationShape[];
+}
+
+/** 重新打开的标注截图在冻结截图中的位置（图像像素）和已有标注 */
+export interface FrozenEdit {
+  monitor_index: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  annotation: Annotation;
+  /** 确认后写回的图片 */
+  path: string;
+}
+
+/** 贴图窗口显示所需信息（get_pin 返回） */
+export interface PinInfo {
+  /** transshot:// 协议地址 */
+  image_url: string;
+  /** 图像像素尺寸 */
+  width: number;
+  height: number;
+  /** 截图所在显示器的缩放，100% 时 1 图像像素 = 1 物理像素 */
+  scale_factor: number;
+}
+
+/** 全屏 / 窗口截图（不经过选区覆盖层）的结果 */
+export interface DirectCapture {
+  width: number;
+  height: number;
+  /** 是否已复制到剪贴板 */
+  copied: boolean;
+  /** 自动保存的文件；未开启自动保存或保存失败时为 null */
+  saved: SavedScreenshot | null;
 }
 
 export interface ExtraProvider {
@@ -22,10 +124,93 @@ export interface ServiceConfig {
   active: number;
 }
 
+/** vision = 视觉模型（远程）；tesseract = 本地 tesseract CLI（离线） */
+export type OcrEngine = "vision" | "tesseract";
+
+/** OCR 输出模式：纯文本 / Markdown（含表格）/ LaTeX（公式）/ 代码（保留缩进） */
+export type OcrMode = "plain" | "markdown" | "latex" | "code";
+
+export interface OcrModeInfo {
+  mode: OcrMode;
+  /** 内置输出要求，作为自定义提示词的占位提示 */
+  default_prompt: string;
+}
+
+/** 大图分块识别：auto = 超过 2048px 且长宽比 ≥ 2 时分块；always = 超过 2048px 即分块；off = 缩放整图 */
+export type OcrTiling = "auto" | "always" | "off";
+
+/** 区域翻译流程：two_step = OCR 后再用翻译模型翻译；one_shot = 视觉模型一次请求识别并翻译 */
+export type TranslatePipeline = "two_step" | "one_shot";
+
+/** capture_and_translate 的结果 */
+export interface VisionTranslation {
+  source: string;
+  translation: string;
+}
+
+/** OCR 预处理步骤，按顺序执行 */
+export type PreprocessStep =
+  | { type: "upscale"; min_text_height: number }
+  | { type: "grayscale" }
+  | { type: "auto_contrast"; cutoff: number }
+  | { type: "binarize" }
+  | { type: "invert_dark" }
+  | { type: "pad"; pixels: number };
+
+export interface PreprocessProfile {
+  name: string;
+  steps: PreprocessStep[];
+  builtin?: boolean;
+}
+
+/** OCR 结果缓存统计（命中计数自启动或上次清空起） */
+export interface OcrCacheStats {
+  memory_hits: number;
+  disk_hits: number;
+  misses: number;
+  memory_entries: number;
+  disk_entries: number;
+  disk_bytes: number;
+}
+
+export interface OcrConfig extends ServiceConfig {
+  engine: OcrEngine;
+  /** tesseract 可执行文件路径；留空使用 PATH 中的 tesseract */
+  tesseract_path: string;
+  /** 各模式的自定义提示词（替换内置输出要求）；缺省或为空使用内置提示词 */
+  prompts: Partial<Record<OcrMode, string>>;
+  /** 识别前使用的预处理 profile 名称；空表示不预处理 */
+  preprocess_profile: string;
+  /** 自定义预处理 profile（内置 profile 不保存在这里） */
+  preprocess_profiles: PreprocessProfile[];
+  tiling: OcrTiling;
+  translate_pipeline: TranslatePipeline;
+}
+
 export interface HotkeyConfig {
   screenshot: string;
   ocr_translate: string;
   clipboard_translate: string;
+  /** 文本动作 id → 快捷键；未绑定的动作没有条目 */
+  actions: Record<string, string>;
+  /** OCR 输出模式 → 快捷键（只识别不翻译）；空值表示不绑定 */
+  ocr_modes: Partial<Record<OcrMode, string>>;
+  /** 延时截图快捷键；空值表示不绑定 */
+  delayed_screenshot: string;
+  /** 全屏截图（光标所在显示器）快捷键；空值表示不绑定 */
+  fullscreen_screenshot: string;
+  /** 当前窗口截图快捷键；空值表示不绑定 */
+  window_screenshot: string;
+  /** 长截图快捷键（长截图进行中再次按下结束）；空值表示不绑定 */
+  scroll_screenshot: string;
+}
+
+export interface TextAction {
+  id: string;
+  name: string;
+  /** System prompt；`{target_lang}` 会被替换为目标语言 */
+  prompt: string;
+  builtin: boolean;
 }
 
 export interface RegionSelectEvent {
@@ -35,7 +220,8 @@ export interface RegionSelectEvent {
   height: number;
   mode: string;
   monitor_index: number;
-  annotatedImage?: string;
+  /** 标注模式确认时的矢量标注；区域为选区在冻结截图中的图像像素 */
+  annotation?: Annotation;
 }
 
 export interface WindowRect {
@@ -55,8 +241,99 @@ export interface MonitorInfo {
 }
 
 export interface ScreenshotInitEvent {
-  image: string;
+  /** 冻结截图 PNG 的地址（transshot:// 自定义协议，每次截图会话不同） */
+  image_url: string;
+  image_width: number;
+  image_height: number;
   mode: string;
   window_rects: WindowRect[];
   monitors: MonitorInfo[];
+  /** 重新编辑标注截图时，本显示器上要直接进入标注的区域；否则为 null */
+  edit: FrozenEdit | null;
+}
+
+export interface DocumentProgressEvent {
+  job_id: string;
+  completed: number;
+  total: number;
+}
+
+export interface DocumentFinishedEvent {
+  job_id: string;
+  output_path: string | null;
+  cancelled: boolean;
+  error: string | null;
+}
+
+export type BatchOutputFormat = "txt" | "md" | "json";
+
+export interface BatchOcrRequest {
+  input_dir: string;
+  /** 默认 `<input_dir>/transshot-ocr` */
+  output_dir?: string | null;
+  recursive?: boolean;
+  language: string;
+  /** 默认：md 格式用 markdown，其余 plain */
+  mode?: OcrMode;
+  /** 设置后同时翻译每张图片的识别结果 */
+  target_lang?: string | null;
+  format?: BatchOutputFormat;
+  /** 同时处理的图片数，默认 2，上限 6 */
+  concurrency?: number;
+}
+
+export interface BatchOcrProgressEvent {
+  job_id: string;
+  /** 含已跳过（上次已完成）与失败的文件 */
+  completed: number;
+  failed: number;
+  skipped: number;
+  total: number;
+  current: string | null;
+}
+
+export interface BatchOcrFinishedEvent {
+  job_id: string;
+  output_dir: string;
+  completed: number;
+  failed: number;
+  cancelled: boolean;
+  error: string | null;
+}
+
+/** Box in crop pixel coordinates (origin top-left) */
+export interface BoundingBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface TranslatedBlock {
+  bbox: BoundingBox;
+  source: string;
+  /** null when this block failed to translate (left unpainted) */
+  translation: string | null;
+}
+
+/** 图片文件/剪贴板图片的 OCR 结果；请求了目标语言时附带译文 */
+export interface ImageOcrResult {
+  text: string;
+  translation: string | null;
+}
+
+/** 本地解码的二维码/条码，坐标为冻结截图像素坐标 */
+export interface DecodedCode {
+  format: string;
+  text: string;
+  /** 内容是否为可打开的链接 */
+  is_url: boolean;
+  bbox: BoundingBox;
+  points: [number, number][];
+}
+
+export interface ImageTranslation {
+  /** base64 PNG, usable with copyImageToClipboard / saveFile */
+  image: string;
+  blocks: TranslatedBlock[];
 }
@@ -30,6 +30,7 @@ export default defineConfig(async () => ({
         screenshot: resolve(__dirname, "screenshot.html"),
         debug: resolve(__dirname, "debug.html"),
         settings: resolve(__dirname, "settings.html"),
+        pin: resolve(__dirname, "pin.html"),
       },
     },
   },